@@ -0,0 +1,69 @@
+mod bench_util;
+
+use ac_library::{Additive, Max};
+use mylib::data_structure::segtree_lib::segtree::range_max_segtree::range_max_segtree::RangeMaxSegtree;
+use mylib::data_structure::segtree_lib::segtree::range_sum_segtree::range_sum_segtree::RangeSumSegtree;
+use mylib::data_structure::sparse_table::disjoint_sparse_table::DisjointSparseTable;
+use mylib::data_structure::sparse_table::sparse_table::SparseTable;
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+struct Query {
+    l: usize,
+    r: usize,
+}
+
+fn main() {
+    let n = 1_000_000;
+    let q = 1_000_000;
+    let seed = 42;
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    println!(
+        "Generating {} queries for N = {} (Static Range Query Comparison)...",
+        q, n
+    );
+    let xs: Vec<i64> = (0..n)
+        .map(|_| rng.random_range(-1_000_000..=1_000_000))
+        .collect();
+    let queries: Vec<Query> = (0..q)
+        .map(|_| {
+            let l = rng.random_range(0..n);
+            let r = rng.random_range(l + 1..=n);
+            Query { l, r }
+        })
+        .collect();
+
+    println!("Running Benchmarks (Release mode required)\n");
+
+    // 1. Range Max: Segtree vs Sparse Table vs Disjoint Sparse Table
+    // 構築時間は含めず、クエリのみを計測する
+    let seg = RangeMaxSegtree::<i64>::from_slice(&xs);
+    let res_seg_max = bench_util::run_bench("Segtree (max)", seg, &queries, |seg, q| {
+        seg.range_max(q.l..q.r)
+    });
+
+    let st = SparseTable::<Max<i64>>::new(&xs);
+    let res_st = bench_util::run_bench("Sparse Table (max)", st, &queries, |st, q| {
+        st.prod(q.l..q.r)
+    });
+
+    let dst = DisjointSparseTable::<Max<i64>>::new(&xs);
+    let res_dst_max = bench_util::run_bench("Disjoint ST (max)", dst, &queries, |dst, q| {
+        dst.prod(q.l..q.r)
+    });
+
+    bench_util::print_results(&[res_seg_max, res_st, res_dst_max]);
+
+    // 2. Range Sum: Segtree vs Disjoint Sparse Table
+    let seg = RangeSumSegtree::<i64>::from_slice(&xs);
+    let res_seg_sum = bench_util::run_bench("Segtree (sum)", seg, &queries, |seg, q| {
+        seg.range_sum(q.l..q.r)
+    });
+
+    let dst = DisjointSparseTable::<Additive<i64>>::new(&xs);
+    let res_dst_sum = bench_util::run_bench("Disjoint ST (sum)", dst, &queries, |dst, q| {
+        dst.prod(q.l..q.r)
+    });
+
+    bench_util::print_results(&[res_seg_sum, res_dst_sum]);
+}
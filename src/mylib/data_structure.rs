@@ -3,11 +3,16 @@ pub mod btree_multiset;
 pub mod default_hash_map;
 pub mod dsu;
 pub mod fenwick_tree;
+pub mod foldable_queue;
 pub mod ix;
+pub mod li_chao_tree;
 pub mod offset_vec;
+pub mod persistent_array;
 pub mod queue;
 pub mod range_set;
 pub mod segtree_lib;
+pub mod sparse_table;
 pub mod stack;
 pub mod topk;
 pub mod union_find;
+pub mod wavelet_matrix;
@@ -5,5 +5,6 @@ pub mod enumerable_dsu;
 pub mod grid_dsu;
 pub mod leader_tracking_dsu;
 pub mod monoid_dsu;
+pub mod offline_dynamic_connectivity;
 pub mod potentialized_dsu;
 pub mod rollback_dsu;
@@ -0,0 +1,320 @@
+use cargo_snippet::snippet;
+
+use crate::data_structure::dsu::rollback_dsu::rollback_dsu;
+
+#[allow(clippy::module_inception)]
+#[snippet(
+    prefix = "use offline_dynamic_connectivity::*;",
+    include = "rollback_dsu"
+)]
+pub mod offline_dynamic_connectivity {
+    use super::rollback_dsu::RollbackDsu;
+    use std::collections::HashMap;
+    use std::hash::Hash;
+
+    /// 辺の追加とロールバックができる状態
+    ///
+    /// `offline_dynamic_connectivity` から呼び出される。
+    /// 重み付き DSU や二部グラフ判定用の DSU などを使う場合は、この trait を実装する。
+    pub trait RollbackState {
+        /// 辺の型
+        type Edge;
+        /// スナップショットの型
+        type Snapshot;
+        /// 辺 `e` を追加する
+        fn add_edge(&mut self, e: &Self::Edge);
+        /// `Add` と `Remove` の辺を対応づけるためのキーに正規化する
+        ///
+        /// 無向辺の `(u, v)` と `(v, u)` を同じ辺として扱う場合は、向きを揃えたものを返す。
+        fn normalize(e: &Self::Edge) -> Self::Edge
+        where
+            Self::Edge: Clone,
+        {
+            e.clone()
+        }
+        /// 現在の状態を記録するスナップショットを作成する
+        fn snapshot(&self) -> Self::Snapshot;
+        /// `snapshot` で作成した時点まで状態を巻き戻す
+        fn rollback(&mut self, snapshot: Self::Snapshot);
+    }
+
+    impl RollbackState for RollbackDsu {
+        type Edge = (usize, usize);
+        type Snapshot = usize;
+        fn add_edge(&mut self, &(u, v): &(usize, usize)) {
+            self.merge(u, v);
+        }
+        fn normalize(&(u, v): &(usize, usize)) -> (usize, usize) {
+            (u.min(v), u.max(v))
+        }
+        fn snapshot(&self) -> usize {
+            RollbackDsu::snapshot(self)
+        }
+        fn rollback(&mut self, snapshot: usize) {
+            RollbackDsu::rollback(self, snapshot);
+        }
+    }
+
+    /// 時系列のイベント
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub enum Event<E, Q> {
+        /// 辺の追加
+        Add(E),
+        /// 辺の削除。同じ辺が複数ある場合は、そのうち 1 本を削除する。
+        ///
+        /// 追加した辺との対応は `RollbackState::normalize` で正規化した辺で判定する。
+        /// `RollbackDsu` では `(u, v)` と `(v, u)` は同じ辺として扱われる。
+        Remove(E),
+        /// クエリ
+        Query(Q),
+    }
+
+    /// オフライン動的グラフ連結性 (segment tree over time)
+    ///
+    /// 辺の追加・削除・クエリからなるイベント列を受け取り、各辺が存在する時間区間をセグメント木の
+    /// O(log T) 個のノードに割り当てる。セグメント木を DFS しながら辺の追加とロールバックを行い、
+    /// 各クエリの時点の状態でコールバックを呼ぶ。
+    ///
+    /// # Arguments
+    /// * `events` - イベント列。`Remove(e)` の時点で辺 `e` が存在すること。
+    /// * `state` - 辺を追加する状態。初期状態は辺がない状態であること。処理後は初期状態に戻る。
+    /// * `f` - クエリの時点の状態とクエリを受け取り、答えを返すクロージャー
+    ///
+    /// # Returns
+    /// 各クエリの答えを、イベント列に現れる順に並べたもの
+    ///
+    /// # 計算量
+    /// イベント数を T として、O(T log T) 回の `add_edge` と `rollback`、T 回の `f` の呼び出し
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use Event::*;
+    /// let events = vec![Add((0, 1)), Query((0, 1)), Remove((1, 0)), Query((0, 1))];
+    /// let mut dsu = RollbackDsu::new(2);
+    /// let ans = offline_dynamic_connectivity(&events, &mut dsu, |dsu, &(u, v)| dsu.same(u, v));
+    /// assert_eq!(ans, vec![true, false]);
+    /// ```
+    pub fn offline_dynamic_connectivity<S, Q, R>(
+        events: &[Event<S::Edge, Q>],
+        state: &mut S,
+        mut f: impl FnMut(&mut S, &Q) -> R,
+    ) -> Vec<R>
+    where
+        S: RollbackState,
+        S::Edge: Clone + Eq + Hash,
+    {
+        let t = events.len();
+        let size = t.next_power_of_two();
+        // node[i]: セグメント木のノード i が表す時間区間全体で存在する辺
+        let mut node = vec![vec![]; 2 * size];
+        let mut add_segment = |mut l: usize, mut r: usize, e: &S::Edge| {
+            l += size;
+            r += size;
+            while l < r {
+                if l & 1 == 1 {
+                    node[l].push(e.clone());
+                    l += 1;
+                }
+                if r & 1 == 1 {
+                    r -= 1;
+                    node[r].push(e.clone());
+                }
+                l >>= 1;
+                r >>= 1;
+            }
+        };
+
+        // 正規化した辺ごとに、まだ削除されていない (追加された時刻, 追加された辺)
+        let mut open: HashMap<S::Edge, Vec<(usize, &S::Edge)>> = HashMap::new();
+        for (i, event) in events.iter().enumerate() {
+            match event {
+                Event::Add(e) => open.entry(S::normalize(e)).or_default().push((i, e)),
+                Event::Remove(e) => {
+                    let (start, added) = open
+                        .get_mut(&S::normalize(e))
+                        .and_then(|starts| starts.pop())
+                        .expect("removed edge does not exist");
+                    add_segment(start, i, added);
+                }
+                Event::Query(_) => {}
+            }
+        }
+        for (start, added) in open.into_values().flatten() {
+            add_segment(start, t, added);
+        }
+
+        let mut ans = vec![];
+        // (ノード, 戻るときのスナップショット)。スナップショットが None なら行きがけ
+        let mut stack = vec![(1, None)];
+        while let Some((k, snapshot)) = stack.pop() {
+            if let Some(snapshot) = snapshot {
+                state.rollback(snapshot);
+                continue;
+            }
+            if k >= size && k - size >= t {
+                continue;
+            }
+            stack.push((k, Some(state.snapshot())));
+            for e in &node[k] {
+                state.add_edge(e);
+            }
+            if k >= size {
+                if let Event::Query(q) = &events[k - size] {
+                    ans.push(f(state, q));
+                }
+            } else {
+                stack.push((2 * k + 1, None));
+                stack.push((2 * k, None));
+            }
+        }
+        ans
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::offline_dynamic_connectivity::*;
+    use crate::data_structure::dsu::rollback_dsu::rollback_dsu::RollbackDsu;
+
+    #[test]
+    fn test_offline_dynamic_connectivity() {
+        use Event::*;
+        let events = vec![
+            Query((0, 2)),
+            Add((0, 1)),
+            Add((1, 2)),
+            Query((0, 2)),
+            Add((0, 1)),
+            Remove((0, 1)),
+            Query((0, 2)),
+            Remove((0, 1)),
+            Query((0, 2)),
+            Query((1, 2)),
+            Remove((1, 2)),
+            Query((1, 2)),
+        ];
+        let mut dsu = RollbackDsu::new(3);
+        let ans = offline_dynamic_connectivity(&events, &mut dsu, |dsu, &(u, v)| dsu.same(u, v));
+        assert_eq!(ans, vec![false, true, true, false, true, false]);
+        // 処理後は初期状態に戻る
+        assert_eq!(dsu.snapshot(), 0);
+
+        let events: Vec<Event<(usize, usize), ()>> = vec![];
+        assert!(offline_dynamic_connectivity(&events, &mut dsu, |_, _| ()).is_empty());
+    }
+
+    #[test]
+    fn test_offline_dynamic_connectivity_reversed_edge() {
+        use Event::*;
+        // 追加したときと逆向きで削除しても同じ辺として扱う
+        let events = vec![
+            Add((0, 1)),
+            Add((2, 1)),
+            Query((0, 2)),
+            Remove((1, 0)),
+            Query((0, 2)),
+            Query((1, 2)),
+            Remove((1, 2)),
+            Query((1, 2)),
+        ];
+        let mut dsu = RollbackDsu::new(3);
+        let ans = offline_dynamic_connectivity(&events, &mut dsu, |dsu, &(u, v)| dsu.same(u, v));
+        assert_eq!(ans, vec![true, false, true, false]);
+    }
+
+    /// 二部グラフかどうかを管理する状態
+    struct BipartiteState {
+        n: usize,
+        /// 頂点 v と v + n は異なる色を表す
+        dsu: RollbackDsu,
+        /// 奇閉路を作った辺の数
+        odd: usize,
+    }
+
+    impl RollbackState for BipartiteState {
+        type Edge = (usize, usize);
+        type Snapshot = (usize, usize);
+        fn add_edge(&mut self, &(u, v): &(usize, usize)) {
+            self.dsu.merge(u, v + self.n);
+            self.dsu.merge(u + self.n, v);
+            if self.dsu.same(u, v) {
+                self.odd += 1;
+            }
+        }
+        fn normalize(&(u, v): &(usize, usize)) -> (usize, usize) {
+            (u.min(v), u.max(v))
+        }
+        fn snapshot(&self) -> (usize, usize) {
+            (self.dsu.snapshot(), self.odd)
+        }
+        fn rollback(&mut self, (snapshot, odd): (usize, usize)) {
+            self.dsu.rollback(snapshot);
+            self.odd = odd;
+        }
+    }
+
+    #[test]
+    fn test_offline_dynamic_connectivity_bipartite() {
+        use Event::*;
+        // 三角形 0-1-2 を作ってから辺を削除する
+        let events = vec![
+            Add((0, 1)),
+            Add((1, 2)),
+            Query(()),
+            Add((2, 0)),
+            Query(()),
+            Remove((0, 1)),
+            Query(()),
+        ];
+        let mut state = BipartiteState {
+            n: 3,
+            dsu: RollbackDsu::new(6),
+            odd: 0,
+        };
+        let ans = offline_dynamic_connectivity(&events, &mut state, |s, _| s.odd == 0);
+        assert_eq!(ans, vec![true, false, true]);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_offline_dynamic_connectivity_random() {
+        use rand::prelude::*;
+
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let n = rng.random_range(1..=8);
+            let t = rng.random_range(0..=60);
+            let mut edges: Vec<(usize, usize)> = vec![];
+            let mut events = vec![];
+            let mut expected = vec![];
+            for _ in 0..t {
+                match rng.random_range(0..3) {
+                    0 => {
+                        let e = (rng.random_range(0..n), rng.random_range(0..n));
+                        edges.push(e);
+                        events.push(Event::Add(e));
+                    }
+                    1 if !edges.is_empty() => {
+                        let (u, v) = edges.swap_remove(rng.random_range(0..edges.len()));
+                        let e = if rng.random_bool(0.5) { (u, v) } else { (v, u) };
+                        events.push(Event::Remove(e));
+                    }
+                    _ => {
+                        let (u, v) = (rng.random_range(0..n), rng.random_range(0..n));
+                        let mut dsu = RollbackDsu::new(n);
+                        for &(a, b) in &edges {
+                            dsu.merge(a, b);
+                        }
+                        expected.push((dsu.same(u, v), dsu.size(u)));
+                        events.push(Event::Query((u, v)));
+                    }
+                }
+            }
+            let mut dsu = RollbackDsu::new(n);
+            let ans = offline_dynamic_connectivity(&events, &mut dsu, |dsu, &(u, v)| {
+                (dsu.same(u, v), dsu.size(u))
+            });
+            assert_eq!(ans, expected);
+        }
+    }
+}
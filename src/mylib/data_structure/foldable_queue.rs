@@ -0,0 +1,387 @@
+use cargo_snippet::snippet;
+
+#[allow(clippy::module_inception)]
+#[snippet(prefix = "use foldable_queue::*;")]
+pub mod foldable_queue {
+    use ac_library::Monoid;
+
+    /// 要素全体の総積を取得できるキュー (Sliding Window Aggregation)
+    ///
+    /// 2 つのスタックで実装しており、非可換なモノイドにも使える。
+    /// 総積は先頭から末尾の順に `op(a_front, ..., a_back)` となる。
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let mut q = FoldableQueue::<Max<i64>>::new();
+    /// q.push(3);
+    /// q.push(1);
+    /// assert_eq!(q.fold(), 3);
+    /// q.pop();
+    /// assert_eq!(q.fold(), 1);
+    /// ```
+    #[derive(Clone, Debug)]
+    pub struct FoldableQueue<M: Monoid> {
+        /// 取り出し用のスタック。`(値, その値から front の底までの総積)` を持ち、末尾が先頭の要素
+        front: Vec<(M::S, M::S)>,
+        /// 追加用のスタック
+        back: Vec<M::S>,
+        /// `back` の総積
+        back_fold: M::S,
+    }
+
+    impl<M: Monoid> Default for FoldableQueue<M> {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl<M: Monoid> FoldableQueue<M> {
+        /// 空のキューを作る
+        pub fn new() -> Self {
+            Self {
+                front: vec![],
+                back: vec![],
+                back_fold: M::identity(),
+            }
+        }
+
+        /// 要素数を返す
+        pub fn len(&self) -> usize {
+            self.front.len() + self.back.len()
+        }
+
+        /// 空かどうかを返す
+        pub fn is_empty(&self) -> bool {
+            self.len() == 0
+        }
+
+        /// 末尾に要素を追加する
+        ///
+        /// # 計算量
+        /// O(1)
+        pub fn push(&mut self, x: M::S) {
+            self.back_fold = M::binary_operation(&self.back_fold, &x);
+            self.back.push(x);
+        }
+
+        /// 先頭の要素を取り出す
+        ///
+        /// # 計算量
+        /// ならし O(1)
+        pub fn pop(&mut self) -> Option<M::S> {
+            if self.front.is_empty() {
+                self.back_fold = M::identity();
+                while let Some(x) = self.back.pop() {
+                    let fold = match self.front.last() {
+                        Some((_, f)) => M::binary_operation(&x, f),
+                        None => x.clone(),
+                    };
+                    self.front.push((x, fold));
+                }
+            }
+            self.front.pop().map(|(x, _)| x)
+        }
+
+        /// 先頭の要素を返す
+        ///
+        /// # 計算量
+        /// O(1)
+        pub fn peek(&self) -> Option<&M::S> {
+            self.front
+                .last()
+                .map(|(x, _)| x)
+                .or_else(|| self.back.first())
+        }
+
+        /// 全要素の総積を返す (空なら単位元)
+        ///
+        /// # 計算量
+        /// O(1)
+        pub fn fold(&self) -> M::S {
+            match self.front.last() {
+                Some((_, f)) => M::binary_operation(f, &self.back_fold),
+                None => self.back_fold.clone(),
+            }
+        }
+    }
+}
+
+#[snippet(prefix = "use foldable_deque::*;")]
+pub mod foldable_deque {
+    use ac_library::Monoid;
+
+    /// 要素全体の総積を取得できる両端キュー
+    ///
+    /// 2 つのスタックで実装しており、片方が空になったらもう片方を半分に分けて作り直す。
+    /// 非可換なモノイドにも使える。総積は先頭から末尾の順に `op(a_front, ..., a_back)` となる。
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let mut dq = FoldableDeque::<Additive<i64>>::new();
+    /// dq.push_back(1);
+    /// dq.push_front(2);
+    /// assert_eq!(dq.fold(), 3);
+    /// assert_eq!(dq.pop_back(), Some(1));
+    /// ```
+    #[derive(Clone, Debug)]
+    pub struct FoldableDeque<M: Monoid> {
+        /// `(値, その値から front の底までの総積)`。末尾が先頭の要素
+        front: Vec<(M::S, M::S)>,
+        /// `(値, back の底からその値までの総積)`。末尾が末尾の要素
+        back: Vec<(M::S, M::S)>,
+    }
+
+    impl<M: Monoid> Default for FoldableDeque<M> {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl<M: Monoid> FoldableDeque<M> {
+        /// 空の両端キューを作る
+        pub fn new() -> Self {
+            Self {
+                front: vec![],
+                back: vec![],
+            }
+        }
+
+        /// 要素数を返す
+        pub fn len(&self) -> usize {
+            self.front.len() + self.back.len()
+        }
+
+        /// 空かどうかを返す
+        pub fn is_empty(&self) -> bool {
+            self.len() == 0
+        }
+
+        /// 先頭に要素を追加する
+        ///
+        /// # 計算量
+        /// O(1)
+        pub fn push_front(&mut self, x: M::S) {
+            let fold = match self.front.last() {
+                Some((_, f)) => M::binary_operation(&x, f),
+                None => x.clone(),
+            };
+            self.front.push((x, fold));
+        }
+
+        /// 末尾に要素を追加する
+        ///
+        /// # 計算量
+        /// O(1)
+        pub fn push_back(&mut self, x: M::S) {
+            let fold = match self.back.last() {
+                Some((_, f)) => M::binary_operation(f, &x),
+                None => x.clone(),
+            };
+            self.back.push((x, fold));
+        }
+
+        /// 先頭の要素を取り出す
+        ///
+        /// # 計算量
+        /// ならし O(1)
+        pub fn pop_front(&mut self) -> Option<M::S> {
+            if self.front.is_empty() {
+                self.rebalance(true);
+            }
+            self.front.pop().map(|(x, _)| x)
+        }
+
+        /// 末尾の要素を取り出す
+        ///
+        /// # 計算量
+        /// ならし O(1)
+        pub fn pop_back(&mut self) -> Option<M::S> {
+            if self.back.is_empty() {
+                self.rebalance(false);
+            }
+            self.back.pop().map(|(x, _)| x)
+        }
+
+        /// 先頭の要素を返す
+        pub fn front(&self) -> Option<&M::S> {
+            self.front
+                .last()
+                .or_else(|| self.back.first())
+                .map(|(x, _)| x)
+        }
+
+        /// 末尾の要素を返す
+        pub fn back(&self) -> Option<&M::S> {
+            self.back
+                .last()
+                .or_else(|| self.front.first())
+                .map(|(x, _)| x)
+        }
+
+        /// 全要素の総積を返す (空なら単位元)
+        ///
+        /// # 計算量
+        /// O(1)
+        pub fn fold(&self) -> M::S {
+            match (self.front.last(), self.back.last()) {
+                (Some((_, f)), Some((_, b))) => M::binary_operation(f, b),
+                (Some((_, f)), None) => f.clone(),
+                (None, Some((_, b))) => b.clone(),
+                (None, None) => M::identity(),
+            }
+        }
+
+        /// 全要素を前半と後半に分けて 2 つのスタックを作り直す
+        ///
+        /// 要素数が奇数のとき、`to_front` なら前半、そうでなければ後半を 1 つ多くする
+        fn rebalance(&mut self, to_front: bool) {
+            // 先頭から末尾の順に並べる
+            let mut all = std::mem::take(&mut self.front)
+                .into_iter()
+                .rev()
+                .map(|(x, _)| x)
+                .collect::<Vec<_>>();
+            all.extend(std::mem::take(&mut self.back).into_iter().map(|(x, _)| x));
+
+            let mid = if to_front {
+                all.len().div_ceil(2)
+            } else {
+                all.len() / 2
+            };
+            let back = all.split_off(mid);
+            for x in all.into_iter().rev() {
+                self.push_front(x);
+            }
+            for x in back {
+                self.push_back(x);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::foldable_deque::*;
+    use super::foldable_queue::*;
+    use crate::math::algebra::monoid::monoid_affine::{AffineComposition, AffineTransform};
+    use ac_library::{Additive, Max, Monoid};
+
+    type M = AffineComposition<i64>;
+
+    fn naive_fold<'a>(xs: impl Iterator<Item = &'a AffineTransform<i64>>) -> AffineTransform<i64> {
+        xs.fold(M::identity(), |acc, x| M::binary_operation(&acc, x))
+    }
+
+    #[test]
+    fn test_foldable_queue() {
+        let mut q = FoldableQueue::<Max<i64>>::new();
+        assert!(q.is_empty());
+        assert_eq!(q.fold(), i64::MIN);
+        assert_eq!(q.pop(), None);
+
+        q.push(3);
+        q.push(1);
+        q.push(2);
+        assert_eq!(q.len(), 3);
+        assert_eq!(q.peek(), Some(&3));
+        assert_eq!(q.fold(), 3);
+        assert_eq!(q.pop(), Some(3));
+        assert_eq!(q.fold(), 2);
+        q.push(0);
+        assert_eq!(q.peek(), Some(&1));
+        assert_eq!(q.pop(), Some(1));
+        assert_eq!(q.pop(), Some(2));
+        assert_eq!(q.fold(), 0);
+
+        // 非可換: f(x) = 2x + 1, g(x) = 3x として op(f, g) = f∘g
+        let f = AffineTransform::new(2, 1);
+        let g = AffineTransform::new(3, 0);
+        let mut q = FoldableQueue::<M>::default();
+        q.push(f);
+        q.push(g);
+        assert_eq!(q.fold().apply(1), 7);
+        q.pop();
+        q.push(f);
+        assert_eq!(q.fold().apply(1), 9);
+    }
+
+    #[test]
+    fn test_foldable_deque() {
+        let mut dq = FoldableDeque::<Additive<i64>>::new();
+        assert!(dq.is_empty());
+        assert_eq!(dq.fold(), 0);
+        assert_eq!(dq.pop_front(), None);
+        assert_eq!(dq.pop_back(), None);
+
+        dq.push_back(1);
+        dq.push_front(2);
+        dq.push_back(3);
+        assert_eq!(dq.len(), 3);
+        assert_eq!(dq.front(), Some(&2));
+        assert_eq!(dq.back(), Some(&3));
+        assert_eq!(dq.fold(), 6);
+        assert_eq!(dq.pop_back(), Some(3));
+        assert_eq!(dq.pop_back(), Some(1));
+        assert_eq!(dq.pop_back(), Some(2));
+        assert_eq!(dq.pop_back(), None);
+
+        dq.push_front(5);
+        assert_eq!(dq.back(), Some(&5));
+        assert_eq!(dq.pop_back(), Some(5));
+
+        let f = AffineTransform::new(2, 1);
+        let g = AffineTransform::new(3, 0);
+        let mut dq = FoldableDeque::<M>::default();
+        dq.push_back(g);
+        dq.push_front(f);
+        assert_eq!(dq.fold().apply(1), 7);
+        assert_eq!(dq.pop_front(), Some(f));
+        dq.push_back(f);
+        assert_eq!(dq.fold().apply(1), 9);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_foldable_queue_random() {
+        use rand::prelude::*;
+        use std::collections::VecDeque;
+
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let mut q = FoldableQueue::<M>::new();
+            let mut dq = FoldableDeque::<M>::new();
+            let mut naive_q = VecDeque::new();
+            let mut naive_dq = VecDeque::new();
+            for _ in 0..200 {
+                let x = AffineTransform::new(rng.random_range(-3..=3), rng.random_range(-3..=3));
+                match rng.random_range(0..4) {
+                    0 | 1 => {
+                        q.push(x);
+                        naive_q.push_back(x);
+                    }
+                    _ => assert_eq!(q.pop(), naive_q.pop_front()),
+                }
+                assert_eq!(q.len(), naive_q.len());
+                assert_eq!(q.peek(), naive_q.front());
+                assert_eq!(q.fold(), naive_fold(naive_q.iter()));
+
+                match rng.random_range(0..6) {
+                    0 | 1 => {
+                        dq.push_front(x);
+                        naive_dq.push_front(x);
+                    }
+                    2 | 3 => {
+                        dq.push_back(x);
+                        naive_dq.push_back(x);
+                    }
+                    4 => assert_eq!(dq.pop_front(), naive_dq.pop_front()),
+                    _ => assert_eq!(dq.pop_back(), naive_dq.pop_back()),
+                }
+                assert_eq!(dq.len(), naive_dq.len());
+                assert_eq!(dq.front(), naive_dq.front());
+                assert_eq!(dq.back(), naive_dq.back());
+                assert_eq!(dq.fold(), naive_fold(naive_dq.iter()));
+            }
+        }
+    }
+}
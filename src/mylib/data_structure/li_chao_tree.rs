@@ -0,0 +1,657 @@
+use crate::algorithm::coordinate_compression::coordinate_compression::CoordinateCompression;
+use cargo_snippet::snippet;
+
+#[allow(clippy::module_inception)]
+#[snippet(prefix = "use li_chao_tree::*;", include = "coordinate_compression")]
+pub mod li_chao_tree {
+    use super::CoordinateCompression;
+    use std::ops::{Add, Mul, Neg, RangeBounds};
+
+    /// 直線 `y = a x + b`
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct Line<T> {
+        pub a: T,
+        pub b: T,
+    }
+
+    impl<T: Copy + Add<Output = T> + Mul<Output = T>> Line<T> {
+        pub fn new(a: T, b: T) -> Self {
+            Self { a, b }
+        }
+
+        pub fn eval(&self, x: T) -> T {
+            self.a * x + self.b
+        }
+    }
+
+    /// 変更したノードと変更前の直線の履歴
+    type History<T> = Vec<(usize, Option<Line<T>>)>;
+
+    /// 座標圧縮した x 座標の集合の上で、直線・線分の追加と最小値 (最大値) の取得を行う Li Chao Tree
+    ///
+    /// `T` には `i64` や `i128` を想定している。`a * x + b` がオーバーフローしうる場合は `i128` を使う。
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let mut lct = LiChaoTree::<i64>::new_min(&[0, 1, 2, 3]);
+    /// lct.add_line(1, 0);
+    /// lct.add_segment(-1, 2, 2..);
+    /// assert_eq!(lct.query(1), Some(1));
+    /// assert_eq!(lct.query(3), Some(-1));
+    /// ```
+    #[derive(Clone, Debug)]
+    pub struct LiChaoTree<T> {
+        cc: CoordinateCompression<T>,
+        n: usize,
+        size: usize,
+        /// 最大値を求める場合は符号を反転した直線を持つ
+        lines: Vec<Option<Line<T>>>,
+        is_max: bool,
+    }
+
+    impl<T> LiChaoTree<T>
+    where
+        T: Copy + Ord + Add<Output = T> + Mul<Output = T> + Neg<Output = T>,
+    {
+        /// クエリで使う x 座標の集合 `xs` を与えて、最小値を求める Li Chao Tree を作る
+        ///
+        /// # 計算量
+        /// O(n log n)
+        pub fn new_min(xs: &[T]) -> Self {
+            Self::new(xs, false)
+        }
+
+        /// クエリで使う x 座標の集合 `xs` を与えて、最大値を求める Li Chao Tree を作る
+        ///
+        /// # 計算量
+        /// O(n log n)
+        pub fn new_max(xs: &[T]) -> Self {
+            Self::new(xs, true)
+        }
+
+        fn new(xs: &[T], is_max: bool) -> Self {
+            let cc = CoordinateCompression::new(xs);
+            let n = cc.space_size();
+            let size = n.next_power_of_two();
+            Self {
+                cc,
+                n,
+                size,
+                lines: vec![None; 2 * size],
+                is_max,
+            }
+        }
+
+        /// 直線 `y = a x + b` を追加する
+        ///
+        /// # 計算量
+        /// O(log n)
+        pub fn add_line(&mut self, a: T, b: T) {
+            self.add_line_impl(a, b, None);
+        }
+
+        /// x 座標が `range` に含まれる部分だけの線分 `y = a x + b` を追加する
+        ///
+        /// # 計算量
+        /// O(log^2 n)
+        pub fn add_segment(&mut self, a: T, b: T, range: impl RangeBounds<T>) {
+            self.add_segment_impl(a, b, range, None);
+        }
+
+        /// x 座標 `x` における直線の最小値 (最大値) を返す。`x` を通る直線がなければ `None`
+        ///
+        /// `x` は構築時に与えた座標に含まれている必要がある。
+        ///
+        /// # 計算量
+        /// O(log n)
+        pub fn query(&self, x: T) -> Option<T> {
+            let mut k = self.cc.compress(x) + self.size;
+            let mut res: Option<T> = None;
+            while k >= 1 {
+                if let Some(line) = self.lines[k] {
+                    let y = line.eval(x);
+                    res = Some(res.map_or(y, |r| r.min(y)));
+                }
+                k >>= 1;
+            }
+            if self.is_max { res.map(|y| -y) } else { res }
+        }
+
+        /// 圧縮後の座標 `i` に対応する x 座標 (2 冪に揃えるための余りは最後の座標とみなす)
+        fn x(&self, i: usize) -> T {
+            self.cc.decompress(i.min(self.n - 1))
+        }
+
+        fn add_line_impl(&mut self, a: T, b: T, history: Option<&mut History<T>>) {
+            if self.n == 0 {
+                return;
+            }
+            let line = self.signed_line(a, b);
+            self.add_rec(line, 1, 0, self.size, history);
+        }
+
+        fn add_segment_impl(
+            &mut self,
+            a: T,
+            b: T,
+            range: impl RangeBounds<T>,
+            mut history: Option<&mut History<T>>,
+        ) {
+            let range = self.cc.compress_range(range);
+            if range.start >= range.end {
+                return;
+            }
+            let line = self.signed_line(a, b);
+            let (mut l, mut r) = (range.start + self.size, range.end + self.size);
+            let mut nodes = vec![];
+            while l < r {
+                if l & 1 == 1 {
+                    nodes.push(l);
+                    l += 1;
+                }
+                if r & 1 == 1 {
+                    r -= 1;
+                    nodes.push(r);
+                }
+                l >>= 1;
+                r >>= 1;
+            }
+            for k in nodes {
+                let depth = k.ilog2();
+                let width = self.size >> depth;
+                let kl = (k - (1 << depth)) * width;
+                self.add_rec(line, k, kl, kl + width, history.as_deref_mut());
+            }
+        }
+
+        fn signed_line(&self, a: T, b: T) -> Line<T> {
+            if self.is_max {
+                Line::new(-a, -b)
+            } else {
+                Line::new(a, b)
+            }
+        }
+
+        /// ノード `k` (担当区間 `[l, r)`) 以下に直線を追加する
+        fn add_rec(
+            &mut self,
+            mut line: Line<T>,
+            mut k: usize,
+            mut l: usize,
+            mut r: usize,
+            mut history: Option<&mut History<T>>,
+        ) {
+            loop {
+                let Some(cur) = self.lines[k] else {
+                    if let Some(history) = history.as_deref_mut() {
+                        history.push((k, None));
+                    }
+                    self.lines[k] = Some(line);
+                    return;
+                };
+                let m = (l + r) / 2;
+                let (xl, xm) = (self.x(l), self.x(m));
+                let left_better = line.eval(xl) < cur.eval(xl);
+                let mid_better = line.eval(xm) < cur.eval(xm);
+                if mid_better {
+                    if let Some(history) = history.as_deref_mut() {
+                        history.push((k, Some(cur)));
+                    }
+                    self.lines[k] = Some(line);
+                    line = cur;
+                }
+                if r - l == 1 {
+                    return;
+                }
+                // 負けた直線は、勝っている区間が [l, m) にあれば左、そうでなければ右に降ろす
+                if left_better != mid_better {
+                    k *= 2;
+                    r = m;
+                } else {
+                    k = 2 * k + 1;
+                    l = m;
+                }
+            }
+        }
+    }
+
+    /// `rollback` で直線・線分の追加を巻き戻せる Li Chao Tree
+    ///
+    /// 木上の DFS で、根からのパス上の直線だけを持ちながら DP をする場合などに使う。
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let mut lct = RollbackLiChaoTree::<i64>::new_min(&[0, 1, 2]);
+    /// lct.add_line(1, 0);
+    /// let snapshot = lct.snapshot();
+    /// lct.add_line(-1, 0);
+    /// assert_eq!(lct.query(2), Some(-2));
+    /// lct.rollback(snapshot);
+    /// assert_eq!(lct.query(2), Some(2));
+    /// ```
+    #[derive(Clone, Debug)]
+    pub struct RollbackLiChaoTree<T> {
+        tree: LiChaoTree<T>,
+        history: History<T>,
+    }
+
+    impl<T> RollbackLiChaoTree<T>
+    where
+        T: Copy + Ord + Add<Output = T> + Mul<Output = T> + Neg<Output = T>,
+    {
+        /// クエリで使う x 座標の集合 `xs` を与えて、最小値を求める Li Chao Tree を作る
+        pub fn new_min(xs: &[T]) -> Self {
+            Self {
+                tree: LiChaoTree::new_min(xs),
+                history: vec![],
+            }
+        }
+
+        /// クエリで使う x 座標の集合 `xs` を与えて、最大値を求める Li Chao Tree を作る
+        pub fn new_max(xs: &[T]) -> Self {
+            Self {
+                tree: LiChaoTree::new_max(xs),
+                history: vec![],
+            }
+        }
+
+        /// 直線 `y = a x + b` を追加する
+        ///
+        /// # 計算量
+        /// O(log n)
+        pub fn add_line(&mut self, a: T, b: T) {
+            self.tree.add_line_impl(a, b, Some(&mut self.history));
+        }
+
+        /// x 座標が `range` に含まれる部分だけの線分 `y = a x + b` を追加する
+        ///
+        /// # 計算量
+        /// O(log^2 n)
+        pub fn add_segment(&mut self, a: T, b: T, range: impl RangeBounds<T>) {
+            self.tree
+                .add_segment_impl(a, b, range, Some(&mut self.history));
+        }
+
+        /// x 座標 `x` における直線の最小値 (最大値) を返す。`x` を通る直線がなければ `None`
+        ///
+        /// # 計算量
+        /// O(log n)
+        pub fn query(&self, x: T) -> Option<T> {
+            self.tree.query(x)
+        }
+
+        /// 現在の状態を記録するスナップショットを作成する。
+        ///
+        /// このスナップショットは`rollback`メソッドに渡して使用する。
+        pub fn snapshot(&self) -> usize {
+            self.history.len()
+        }
+
+        /// `snapshot`で指定された時点まで状態を巻き戻す。
+        pub fn rollback(&mut self, snapshot: usize) {
+            while self.history.len() > snapshot {
+                let (k, line) = self.history.pop().unwrap();
+                self.tree.lines[k] = line;
+            }
+        }
+    }
+}
+
+#[snippet(prefix = "use monotone_cht::*;")]
+pub mod monotone_cht {
+    use std::collections::VecDeque;
+    use std::ops::{Add, Mul, Neg, Sub};
+
+    /// 傾きが単調な順に直線を追加する Convex Hull Trick
+    ///
+    /// 最小値を求める場合は傾きが広義単調減少、最大値を求める場合は広義単調増加の順に追加する必要がある。
+    /// `T` には `i64` や `i128` を想定している。不要な直線の判定で (傾きの差) * (切片の差) を計算するため、
+    /// これがオーバーフローしうる場合は `i128` を使う。
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let mut cht = MonotoneCht::<i64>::new_min();
+    /// cht.add_line(1, 0);
+    /// cht.add_line(-1, 2);
+    /// assert_eq!(cht.query(0), Some(0));
+    /// assert_eq!(cht.query(3), Some(-1));
+    /// ```
+    #[derive(Clone, Debug)]
+    pub struct MonotoneCht<T> {
+        /// `(傾き, 切片)`。最大値を求める場合は符号を反転して持つ
+        lines: VecDeque<(T, T)>,
+        is_max: bool,
+    }
+
+    impl<T> MonotoneCht<T>
+    where
+        T: Copy + Ord + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Neg<Output = T>,
+    {
+        /// 最小値を求める CHT を作る
+        pub fn new_min() -> Self {
+            Self {
+                lines: VecDeque::new(),
+                is_max: false,
+            }
+        }
+
+        /// 最大値を求める CHT を作る
+        pub fn new_max() -> Self {
+            Self {
+                lines: VecDeque::new(),
+                is_max: true,
+            }
+        }
+
+        /// 下側 (上側) の包絡線を構成する直線の本数を返す
+        pub fn len(&self) -> usize {
+            self.lines.len()
+        }
+
+        /// 直線が 1 本もないかどうかを返す
+        pub fn is_empty(&self) -> bool {
+            self.lines.is_empty()
+        }
+
+        /// 直線 `y = a x + b` を追加する
+        ///
+        /// # Panics
+        /// 傾きが単調な順になっていない場合
+        ///
+        /// # 計算量
+        /// ならし O(1)
+        pub fn add_line(&mut self, a: T, b: T) {
+            let (a, b) = if self.is_max { (-a, -b) } else { (a, b) };
+            if let Some(&(last_a, last_b)) = self.lines.back() {
+                assert!(last_a >= a, "slopes must be added in monotone order");
+                if last_a == a {
+                    if last_b <= b {
+                        return;
+                    }
+                    self.lines.pop_back();
+                }
+            }
+            while self.lines.len() >= 2 {
+                let (a1, b1) = self.lines[self.lines.len() - 2];
+                let (a2, b2) = self.lines[self.lines.len() - 1];
+                // 直線 1, 2 の交点が直線 2, 3 の交点より右にあれば直線 2 は不要
+                if (b2 - b1) * (a2 - a) >= (b - b2) * (a1 - a2) {
+                    self.lines.pop_back();
+                } else {
+                    break;
+                }
+            }
+            self.lines.push_back((a, b));
+        }
+
+        /// x 座標 `x` における直線の最小値 (最大値) を返す。直線がなければ `None`
+        ///
+        /// # 計算量
+        /// O(log n)
+        pub fn query(&self, x: T) -> Option<T> {
+            if self.lines.is_empty() {
+                return None;
+            }
+            let (mut lo, mut hi) = (0, self.lines.len() - 1);
+            while lo < hi {
+                let mid = (lo + hi) / 2;
+                if self.eval(mid, x) >= self.eval(mid + 1, x) {
+                    lo = mid + 1;
+                } else {
+                    hi = mid;
+                }
+            }
+            Some(self.signed(self.eval(lo, x)))
+        }
+
+        /// x 座標 `x` における直線の最小値 (最大値) を返す。直線がなければ `None`
+        ///
+        /// 呼び出しごとの `x` が広義単調増加である必要がある。
+        /// 以降の `x` で最適にならない直線を先頭から取り除く。
+        ///
+        /// # 計算量
+        /// ならし O(1)
+        pub fn query_increasing(&mut self, x: T) -> Option<T> {
+            while self.lines.len() >= 2 && self.eval(0, x) >= self.eval(1, x) {
+                self.lines.pop_front();
+            }
+            if self.lines.is_empty() {
+                return None;
+            }
+            Some(self.signed(self.eval(0, x)))
+        }
+
+        fn eval(&self, i: usize, x: T) -> T {
+            let (a, b) = self.lines[i];
+            a * x + b
+        }
+
+        fn signed(&self, y: T) -> T {
+            if self.is_max { -y } else { y }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::li_chao_tree::*;
+    use super::monotone_cht::*;
+
+    #[test]
+    fn test_li_chao_tree() {
+        let xs = [-2, 0, 1, 3, 5];
+        let mut lct = LiChaoTree::<i64>::new_min(&xs);
+        assert_eq!(lct.query(0), None);
+        lct.add_line(1, 0);
+        lct.add_line(-1, 2);
+        assert_eq!(lct.query(-2), Some(-2));
+        assert_eq!(lct.query(1), Some(1));
+        assert_eq!(lct.query(5), Some(-3));
+        lct.add_segment(0, -10, 0..3);
+        assert_eq!(lct.query(-2), Some(-2));
+        assert_eq!(lct.query(0), Some(-10));
+        assert_eq!(lct.query(1), Some(-10));
+        assert_eq!(lct.query(3), Some(-1));
+
+        let mut lct = LiChaoTree::<i128>::new_max(&[0, 1_000_000_000]);
+        lct.add_line(1_000_000_000_000, 0);
+        lct.add_segment(0, 7, ..=0);
+        assert_eq!(lct.query(0), Some(7));
+        assert_eq!(
+            lct.query(1_000_000_000),
+            Some(1_000_000_000_000_000_000_000)
+        );
+
+        let mut lct = LiChaoTree::<i64>::new_min(&[]);
+        lct.add_line(1, 1);
+        lct.add_segment(1, 1, ..);
+    }
+
+    #[test]
+    fn test_rollback_li_chao_tree() {
+        let mut lct = RollbackLiChaoTree::<i64>::new_min(&[0, 1, 2]);
+        lct.add_line(1, 0);
+        let s1 = lct.snapshot();
+        lct.add_line(-1, 0);
+        assert_eq!(lct.query(2), Some(-2));
+        let s2 = lct.snapshot();
+        lct.add_segment(0, -5, 1..2);
+        assert_eq!(lct.query(1), Some(-5));
+        lct.rollback(s2);
+        assert_eq!(lct.query(1), Some(-1));
+        lct.rollback(s1);
+        assert_eq!(lct.query(2), Some(2));
+        lct.rollback(0);
+        assert_eq!(lct.query(0), None);
+    }
+
+    #[test]
+    fn test_monotone_cht() {
+        let mut cht = MonotoneCht::<i64>::new_min();
+        assert_eq!(cht.query(0), None);
+        cht.add_line(2, 0);
+        cht.add_line(0, 5);
+        cht.add_line(0, 1);
+        cht.add_line(-1, 3);
+        // y = 2x は x <= 0 で、y = 1 は 0 <= x <= 2 で、y = -x + 3 は x >= 2 で最小
+        assert_eq!(cht.len(), 3);
+        assert_eq!(cht.query(-3), Some(-6));
+        assert_eq!(cht.query(1), Some(1));
+        assert_eq!(cht.query(5), Some(-2));
+        assert_eq!(cht.query_increasing(-3), Some(-6));
+        assert_eq!(cht.query_increasing(1), Some(1));
+        assert_eq!(cht.query_increasing(5), Some(-2));
+        assert_eq!(cht.len(), 1);
+
+        let mut cht = MonotoneCht::<i128>::new_max();
+        cht.add_line(-1, 0);
+        cht.add_line(1, 0);
+        assert_eq!(cht.query(-4), Some(4));
+        assert_eq!(cht.query(4), Some(4));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_monotone_cht_unsorted() {
+        let mut cht = MonotoneCht::<i64>::new_min();
+        cht.add_line(0, 0);
+        cht.add_line(1, 0);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_li_chao_tree_random() {
+        use rand::prelude::*;
+
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let is_max = rng.random_bool(0.5);
+            let better = |x: i64, y: i64| if is_max { x.max(y) } else { x.min(y) };
+            let n = rng.random_range(1..=20);
+            let xs = (0..n)
+                .map(|_| rng.random_range(-20..=20))
+                .collect::<Vec<i64>>();
+            let mut lct = if is_max {
+                LiChaoTree::new_max(&xs)
+            } else {
+                LiChaoTree::new_min(&xs)
+            };
+            // (a, b, l, r): x が [l, r) の線分
+            let mut segs: Vec<(i64, i64, i64, i64)> = vec![];
+            for _ in 0..50 {
+                let a = rng.random_range(-10..=10);
+                let b = rng.random_range(-100..=100);
+                match rng.random_range(0..3) {
+                    0 => {
+                        lct.add_line(a, b);
+                        segs.push((a, b, i64::MIN, i64::MAX));
+                    }
+                    1 => {
+                        let l = rng.random_range(-25..=25);
+                        let r = rng.random_range(l..=25);
+                        lct.add_segment(a, b, l..r);
+                        segs.push((a, b, l, r));
+                    }
+                    _ => {
+                        let x = xs[rng.random_range(0..n)];
+                        let expected = segs
+                            .iter()
+                            .filter(|&&(_, _, l, r)| l <= x && x < r)
+                            .map(|&(a, b, _, _)| a * x + b)
+                            .reduce(better);
+                        assert_eq!(lct.query(x), expected);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn test_rollback_li_chao_tree_random() {
+        use rand::prelude::*;
+
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let n = rng.random_range(1..=20);
+            let xs = (0..n)
+                .map(|_| rng.random_range(-20..=20))
+                .collect::<Vec<i64>>();
+            let mut rb = RollbackLiChaoTree::new_min(&xs);
+            let mut segs: Vec<(i64, i64, i64, i64)> = vec![];
+            let mut snapshots = vec![];
+            for _ in 0..100 {
+                match rng.random_range(0..3) {
+                    0 => {
+                        let a = rng.random_range(-10..=10);
+                        let b = rng.random_range(-100..=100);
+                        let l = rng.random_range(-25..=25);
+                        let r = rng.random_range(l..=25);
+                        snapshots.push((rb.snapshot(), segs.len()));
+                        rb.add_segment(a, b, l..r);
+                        segs.push((a, b, l, r));
+                    }
+                    1 => {
+                        if let Some((snapshot, len)) = snapshots.pop() {
+                            rb.rollback(snapshot);
+                            segs.truncate(len);
+                        }
+                    }
+                    _ => {
+                        let x = xs[rng.random_range(0..n)];
+                        let expected = segs
+                            .iter()
+                            .filter(|&&(_, _, l, r)| l <= x && x < r)
+                            .map(|&(a, b, _, _)| a * x + b)
+                            .min();
+                        assert_eq!(rb.query(x), expected);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn test_monotone_cht_random() {
+        use rand::prelude::*;
+
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let is_max = rng.random_bool(0.5);
+            let mut slopes = (0..rng.random_range(1..=20))
+                .map(|_| rng.random_range(-10..=10))
+                .collect::<Vec<i64>>();
+            slopes.sort();
+            if !is_max {
+                slopes.reverse();
+            }
+            let lines = slopes
+                .iter()
+                .map(|&a| (a, rng.random_range(-100..=100)))
+                .collect::<Vec<_>>();
+            let mut cht = if is_max {
+                MonotoneCht::new_max()
+            } else {
+                MonotoneCht::new_min()
+            };
+            for &(a, b) in &lines {
+                cht.add_line(a, b);
+            }
+            let naive = |x: i64| {
+                let ys = lines.iter().map(|&(a, b)| a * x + b);
+                if is_max { ys.max() } else { ys.min() }
+            };
+            let mut queries = (0..30)
+                .map(|_| rng.random_range(-30..=30))
+                .collect::<Vec<i64>>();
+            for &x in &queries {
+                assert_eq!(cht.query(x), naive(x));
+            }
+            queries.sort();
+            for &x in &queries {
+                assert_eq!(cht.query_increasing(x), naive(x));
+            }
+        }
+    }
+}
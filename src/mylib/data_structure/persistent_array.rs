@@ -0,0 +1,221 @@
+use cargo_snippet::snippet;
+
+#[allow(clippy::module_inception)]
+#[snippet(prefix = "use persistent_array::*;")]
+pub mod persistent_array {
+    #[derive(Clone, Debug)]
+    enum Node<T> {
+        Leaf(T),
+        Branch(usize, usize),
+    }
+
+    /// 永続配列
+    ///
+    /// 更新のたびに新しいバージョン (0 始まりの番号) を作り、過去の任意のバージョンに対して取得・更新ができる。
+    /// バージョン 0 は構築時の配列を表す。
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let mut arr = PersistentArray::from(vec![1, 2, 3]);
+    /// let v1 = arr.set(0, 1, 10);
+    /// assert_eq!(*arr.get(0, 1), 2);
+    /// assert_eq!(*arr.get(v1, 1), 10);
+    /// ```
+    #[derive(Clone, Debug)]
+    pub struct PersistentArray<T> {
+        n: usize,
+        nodes: Vec<Node<T>>,
+        roots: Vec<usize>,
+    }
+
+    impl<T: Clone> From<Vec<T>> for PersistentArray<T> {
+        fn from(v: Vec<T>) -> Self {
+            let n = v.len();
+            let mut arr = Self {
+                n,
+                nodes: vec![],
+                roots: vec![],
+            };
+            let root = if n == 0 {
+                arr.new_node(Node::Branch(0, 0))
+            } else {
+                arr.build(&v, 0, n)
+            };
+            arr.roots.push(root);
+            arr
+        }
+    }
+
+    impl<T: Clone> PersistentArray<T> {
+        /// 長さ `n` で全要素が `value` の配列をバージョン 0 として構築する
+        ///
+        /// # 計算量
+        /// O(n)
+        pub fn new(n: usize, value: T) -> Self {
+            vec![value; n].into()
+        }
+
+        /// 配列の長さを返す
+        pub fn len(&self) -> usize {
+            self.n
+        }
+
+        /// 配列が空かどうかを返す
+        pub fn is_empty(&self) -> bool {
+            self.n == 0
+        }
+
+        /// これまでに作られたバージョンの個数を返す
+        pub fn num_versions(&self) -> usize {
+            self.roots.len()
+        }
+
+        /// バージョン `ver` の `i` 番目の要素を返す
+        ///
+        /// # 計算量
+        /// O(log n)
+        pub fn get(&self, ver: usize, i: usize) -> &T {
+            assert!(i < self.n);
+            let (mut node, mut l, mut r) = (self.roots[ver], 0, self.n);
+            loop {
+                match &self.nodes[node] {
+                    Node::Leaf(x) => return x,
+                    &Node::Branch(left, right) => {
+                        let mid = (l + r) / 2;
+                        if i < mid {
+                            node = left;
+                            r = mid;
+                        } else {
+                            node = right;
+                            l = mid;
+                        }
+                    }
+                }
+            }
+        }
+
+        /// バージョン `ver` の `i` 番目の要素を `x` にした新しいバージョンを作り、その番号を返す
+        ///
+        /// # 計算量
+        /// O(log n)
+        pub fn set(&mut self, ver: usize, i: usize, x: T) -> usize {
+            assert!(i < self.n);
+            let root = self.set_rec(self.roots[ver], 0, self.n, i, x);
+            self.roots.push(root);
+            self.roots.len() - 1
+        }
+
+        /// バージョン `ver` の配列を `Vec` として返す
+        ///
+        /// # 計算量
+        /// O(n)
+        pub fn to_vec(&self, ver: usize) -> Vec<T> {
+            let mut res = Vec::with_capacity(self.n);
+            if self.n > 0 {
+                self.collect_rec(self.roots[ver], &mut res);
+            }
+            res
+        }
+
+        fn new_node(&mut self, node: Node<T>) -> usize {
+            self.nodes.push(node);
+            self.nodes.len() - 1
+        }
+
+        fn build(&mut self, v: &[T], l: usize, r: usize) -> usize {
+            if r - l == 1 {
+                return self.new_node(Node::Leaf(v[l].clone()));
+            }
+            let mid = (l + r) / 2;
+            let left = self.build(v, l, mid);
+            let right = self.build(v, mid, r);
+            self.new_node(Node::Branch(left, right))
+        }
+
+        fn set_rec(&mut self, node: usize, l: usize, r: usize, i: usize, x: T) -> usize {
+            if r - l == 1 {
+                return self.new_node(Node::Leaf(x));
+            }
+            let Node::Branch(mut left, mut right) = self.nodes[node] else {
+                unreachable!()
+            };
+            let mid = (l + r) / 2;
+            if i < mid {
+                left = self.set_rec(left, l, mid, i, x);
+            } else {
+                right = self.set_rec(right, mid, r, i, x);
+            }
+            self.new_node(Node::Branch(left, right))
+        }
+
+        fn collect_rec(&self, node: usize, res: &mut Vec<T>) {
+            match &self.nodes[node] {
+                Node::Leaf(x) => res.push(x.clone()),
+                &Node::Branch(left, right) => {
+                    self.collect_rec(left, res);
+                    self.collect_rec(right, res);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::persistent_array::*;
+
+    #[test]
+    fn test_persistent_array() {
+        let mut arr = PersistentArray::from(vec![1, 2, 3]);
+        let v1 = arr.set(0, 1, 10);
+        let v2 = arr.set(v1, 2, 20);
+        let v3 = arr.set(0, 0, 0);
+        assert_eq!(arr.num_versions(), 4);
+        assert_eq!(arr.len(), 3);
+
+        assert_eq!(arr.to_vec(0), vec![1, 2, 3]);
+        assert_eq!(arr.to_vec(v1), vec![1, 10, 3]);
+        assert_eq!(arr.to_vec(v2), vec![1, 10, 20]);
+        assert_eq!(arr.to_vec(v3), vec![0, 2, 3]);
+        assert_eq!(*arr.get(v2, 1), 10);
+
+        let arr = PersistentArray::new(0, 'a');
+        assert!(arr.is_empty());
+        assert_eq!(arr.to_vec(0), vec![]);
+
+        let mut arr = PersistentArray::new(1, "x".to_string());
+        let v1 = arr.set(0, 0, "y".to_string());
+        assert_eq!(arr.get(0, 0), "x");
+        assert_eq!(arr.get(v1, 0), "y");
+    }
+
+    #[test]
+    #[ignore]
+    fn test_persistent_array_random() {
+        use rand::prelude::*;
+
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..50 {
+            let n = rng.random_range(1..=20);
+            let init = (0..n)
+                .map(|_| rng.random_range(0..100))
+                .collect::<Vec<i32>>();
+            let mut arr = PersistentArray::from(init.clone());
+            let mut versions = vec![init];
+            for _ in 0..100 {
+                let ver = rng.random_range(0..versions.len());
+                let i = rng.random_range(0..n);
+                if rng.random_bool(0.5) {
+                    let x = rng.random_range(0..100);
+                    let mut next = versions[ver].clone();
+                    next[i] = x;
+                    assert_eq!(arr.set(ver, i, x), versions.len());
+                    versions.push(next);
+                } else {
+                    assert_eq!(*arr.get(ver, i), versions[ver][i]);
+                    assert_eq!(arr.to_vec(ver), versions[ver]);
+                }
+            }
+        }
+    }
+}
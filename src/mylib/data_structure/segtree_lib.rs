@@ -1,6 +1,8 @@
 pub mod dual_segtree;
 pub mod dynamic_monoid_segtree;
 pub mod lazy_segtree;
+pub mod persistent_lazy_segtree;
+pub mod persistent_segtree;
 pub mod segtree;
 pub mod segtree_2d;
 pub mod segtree_beats;
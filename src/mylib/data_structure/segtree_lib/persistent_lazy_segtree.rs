@@ -0,0 +1,364 @@
+use cargo_snippet::snippet;
+
+#[allow(clippy::module_inception)]
+#[snippet(prefix = "use persistent_lazy_segtree::*;")]
+pub mod persistent_lazy_segtree {
+    use ac_library::{MapMonoid, Monoid};
+    use std::ops::{Bound, RangeBounds};
+
+    #[derive(Clone, Debug)]
+    struct Node<S, F> {
+        val: S,
+        /// 子に伝播していない作用 (val には適用済み)
+        lazy: F,
+        left: usize,
+        right: usize,
+    }
+
+    /// 永続遅延セグメント木
+    ///
+    /// 区間作用も新しいバージョンとして記録し、古いバージョンの区間積は変わらない。
+    /// 遅延伝播は子を書き換える代わりにコピーして行う (copy-on-write) ため、操作ごとに O(log n) 個のノードが増える。
+    /// バージョン 0 は構築時の列を表す。
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let mut seg = PersistentLazySegtree::<RangeAffineRangeSum<i64>>::from(vec![RangeSum::unit(1); 3]);
+    /// let v1 = seg.apply_range(0, 0..2, Affine::addition_func(10));
+    /// assert_eq!(seg.prod(0, ..).sum, 3);
+    /// assert_eq!(seg.prod(v1, ..).sum, 23);
+    /// ```
+    #[derive(Clone)]
+    pub struct PersistentLazySegtree<F: MapMonoid> {
+        n: usize,
+        nodes: Vec<Node<<F::M as Monoid>::S, F::F>>,
+        roots: Vec<usize>,
+    }
+
+    impl<F: MapMonoid> From<Vec<<F::M as Monoid>::S>> for PersistentLazySegtree<F> {
+        fn from(v: Vec<<F::M as Monoid>::S>) -> Self {
+            let n = v.len();
+            let mut seg = Self {
+                n,
+                nodes: vec![],
+                roots: vec![],
+            };
+            let root = if n == 0 {
+                seg.new_node(F::identity_element(), F::identity_map(), 0, 0)
+            } else {
+                seg.build(&v, 0, n)
+            };
+            seg.roots.push(root);
+            seg
+        }
+    }
+
+    impl<F: MapMonoid> PersistentLazySegtree<F> {
+        /// 長さ `n` で全要素が単位元の列をバージョン 0 として構築する
+        ///
+        /// # 計算量
+        /// O(n)
+        pub fn new(n: usize) -> Self {
+            vec![F::identity_element(); n].into()
+        }
+
+        /// 列の長さを返す
+        pub fn len(&self) -> usize {
+            self.n
+        }
+
+        /// 列が空かどうかを返す
+        pub fn is_empty(&self) -> bool {
+            self.n == 0
+        }
+
+        /// これまでに作られたバージョンの個数を返す
+        pub fn num_versions(&self) -> usize {
+            self.roots.len()
+        }
+
+        /// バージョン `ver` の `p` 番目の要素を `x` にした新しいバージョンを作り、その番号を返す
+        ///
+        /// # 計算量
+        /// O(log n)
+        pub fn set(&mut self, ver: usize, p: usize, x: <F::M as Monoid>::S) -> usize {
+            assert!(p < self.n);
+            let root = self.set_rec(self.roots[ver], 0, self.n, p, x);
+            self.roots.push(root);
+            self.roots.len() - 1
+        }
+
+        /// バージョン `ver` の区間 `range` に作用 `f` を適用した新しいバージョンを作り、その番号を返す
+        ///
+        /// # 計算量
+        /// O(log n)
+        pub fn apply_range(
+            &mut self,
+            ver: usize,
+            range: impl RangeBounds<usize>,
+            f: F::F,
+        ) -> usize {
+            let (l, r) = self.range(range);
+            let root = if l == r {
+                self.roots[ver]
+            } else {
+                self.apply_rec(self.roots[ver], 0, self.n, l, r, &f)
+            };
+            self.roots.push(root);
+            self.roots.len() - 1
+        }
+
+        /// バージョン `ver` の `p` 番目の要素を返す
+        ///
+        /// # 計算量
+        /// O(log n)
+        pub fn get(&self, ver: usize, p: usize) -> <F::M as Monoid>::S {
+            assert!(p < self.n);
+            self.prod(ver, p..=p)
+        }
+
+        /// バージョン `ver` の区間 `range` の総積を返す
+        ///
+        /// # 計算量
+        /// O(log n)
+        pub fn prod(&self, ver: usize, range: impl RangeBounds<usize>) -> <F::M as Monoid>::S {
+            let (l, r) = self.range(range);
+            if l == r {
+                return F::identity_element();
+            }
+            self.prod_rec(self.roots[ver], 0, self.n, l, r, &F::identity_map())
+        }
+
+        /// バージョン `ver` の全体の総積を返す
+        ///
+        /// # 計算量
+        /// O(1)
+        pub fn all_prod(&self, ver: usize) -> <F::M as Monoid>::S {
+            self.nodes[self.roots[ver]].val.clone()
+        }
+
+        fn range(&self, range: impl RangeBounds<usize>) -> (usize, usize) {
+            let l = match range.start_bound() {
+                Bound::Included(&l) => l,
+                Bound::Excluded(&l) => l + 1,
+                Bound::Unbounded => 0,
+            };
+            let r = match range.end_bound() {
+                Bound::Included(&r) => r + 1,
+                Bound::Excluded(&r) => r,
+                Bound::Unbounded => self.n,
+            };
+            assert!(l <= r && r <= self.n);
+            (l, r)
+        }
+
+        fn new_node(
+            &mut self,
+            val: <F::M as Monoid>::S,
+            lazy: F::F,
+            left: usize,
+            right: usize,
+        ) -> usize {
+            self.nodes.push(Node {
+                val,
+                lazy,
+                left,
+                right,
+            });
+            self.nodes.len() - 1
+        }
+
+        fn build(&mut self, v: &[<F::M as Monoid>::S], l: usize, r: usize) -> usize {
+            if r - l == 1 {
+                return self.new_node(v[l].clone(), F::identity_map(), 0, 0);
+            }
+            let mid = (l + r) / 2;
+            let left = self.build(v, l, mid);
+            let right = self.build(v, mid, r);
+            let val = F::binary_operation(&self.nodes[left].val, &self.nodes[right].val);
+            self.new_node(val, F::identity_map(), left, right)
+        }
+
+        /// `node` のコピーに作用 `f` を適用した節点を作る
+        fn applied(&mut self, node: usize, f: &F::F) -> usize {
+            let Node {
+                val,
+                lazy,
+                left,
+                right,
+            } = &self.nodes[node];
+            let (val, lazy) = (F::mapping(f, val), F::composition(f, lazy));
+            let (left, right) = (*left, *right);
+            self.new_node(val, lazy, left, right)
+        }
+
+        /// `node` の遅延作用を子のコピーに伝播させ、新しい子の組を返す
+        fn pushed_children(&mut self, node: usize) -> (usize, usize) {
+            let lazy = self.nodes[node].lazy.clone();
+            let (left, right) = (self.nodes[node].left, self.nodes[node].right);
+            (self.applied(left, &lazy), self.applied(right, &lazy))
+        }
+
+        fn set_rec(
+            &mut self,
+            node: usize,
+            l: usize,
+            r: usize,
+            p: usize,
+            x: <F::M as Monoid>::S,
+        ) -> usize {
+            if r - l == 1 {
+                return self.new_node(x, F::identity_map(), 0, 0);
+            }
+            let mid = (l + r) / 2;
+            let (mut left, mut right) = self.pushed_children(node);
+            if p < mid {
+                left = self.set_rec(left, l, mid, p, x);
+            } else {
+                right = self.set_rec(right, mid, r, p, x);
+            }
+            let val = F::binary_operation(&self.nodes[left].val, &self.nodes[right].val);
+            self.new_node(val, F::identity_map(), left, right)
+        }
+
+        fn apply_rec(
+            &mut self,
+            node: usize,
+            l: usize,
+            r: usize,
+            ql: usize,
+            qr: usize,
+            f: &F::F,
+        ) -> usize {
+            if qr <= l || r <= ql {
+                return node;
+            }
+            if ql <= l && r <= qr {
+                return self.applied(node, f);
+            }
+            let mid = (l + r) / 2;
+            let (left, right) = self.pushed_children(node);
+            let left = self.apply_rec(left, l, mid, ql, qr, f);
+            let right = self.apply_rec(right, mid, r, ql, qr, f);
+            let val = F::binary_operation(&self.nodes[left].val, &self.nodes[right].val);
+            self.new_node(val, F::identity_map(), left, right)
+        }
+
+        /// 祖先に溜まっている作用の合成 `acc` を適用しながら区間積を求める (節点は書き換えない)
+        fn prod_rec(
+            &self,
+            node: usize,
+            l: usize,
+            r: usize,
+            ql: usize,
+            qr: usize,
+            acc: &F::F,
+        ) -> <F::M as Monoid>::S {
+            if ql <= l && r <= qr {
+                return F::mapping(acc, &self.nodes[node].val);
+            }
+            let mid = (l + r) / 2;
+            let Node {
+                lazy, left, right, ..
+            } = &self.nodes[node];
+            let acc = F::composition(acc, lazy);
+            if qr <= mid {
+                self.prod_rec(*left, l, mid, ql, qr, &acc)
+            } else if mid <= ql {
+                self.prod_rec(*right, mid, r, ql, qr, &acc)
+            } else {
+                F::binary_operation(
+                    &self.prod_rec(*left, l, mid, ql, qr, &acc),
+                    &self.prod_rec(*right, mid, r, ql, qr, &acc),
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::persistent_lazy_segtree::*;
+    use crate::data_structure::segtree_lib::lazy_segtree::range_affine_range_sum::range_affine_range_sum::{
+        Affine, RangeAffineRangeSum, RangeSum,
+    };
+
+    type Seg = PersistentLazySegtree<RangeAffineRangeSum<i64>>;
+
+    #[test]
+    fn test_persistent_lazy_segtree() {
+        let mut seg = Seg::from((1..=4).map(RangeSum::unit).collect::<Vec<_>>());
+        let v1 = seg.apply_range(0, 0..2, Affine::addition_func(10));
+        let v2 = seg.apply_range(v1, 1..4, Affine::constant_func(5));
+        let v3 = seg.set(v2, 2, RangeSum::unit(-1));
+        let v4 = seg.apply_range(v3, 2..2, Affine::constant_func(100));
+        assert_eq!(seg.num_versions(), 5);
+
+        assert_eq!(seg.prod(0, ..).sum, 10);
+        // [11, 12, 3, 4]
+        assert_eq!(seg.prod(v1, ..).sum, 30);
+        assert_eq!(seg.prod(v1, 1..3).sum, 15);
+        // [11, 5, 5, 5]
+        assert_eq!(seg.all_prod(v2).sum, 26);
+        assert_eq!(seg.get(v2, 1).sum, 5);
+        // [11, 5, -1, 5]
+        assert_eq!(seg.prod(v3, 1..).sum, 9);
+        assert_eq!(seg.get(v3, 0).sum, 11);
+        assert_eq!(seg.prod(v4, ..).sum, 20);
+        assert_eq!(seg.get(0, 3).sum, 4);
+
+        let seg = Seg::new(0);
+        assert!(seg.is_empty());
+        assert_eq!(seg.prod(0, ..).sum, 0);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_persistent_lazy_segtree_random() {
+        use rand::prelude::*;
+
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..50 {
+            let n = rng.random_range(1..=20);
+            let init = (0..n)
+                .map(|_| rng.random_range(-10..=10))
+                .collect::<Vec<i64>>();
+            let mut seg = Seg::from(init.iter().map(|&x| RangeSum::unit(x)).collect::<Vec<_>>());
+            let mut versions = vec![init];
+            for _ in 0..100 {
+                let ver = rng.random_range(0..versions.len());
+                let l = rng.random_range(0..=n);
+                let r = rng.random_range(l..=n);
+                match rng.random_range(0..3) {
+                    0 => {
+                        let p = rng.random_range(0..n);
+                        let x = rng.random_range(-10..=10);
+                        let mut next = versions[ver].clone();
+                        next[p] = x;
+                        assert_eq!(seg.set(ver, p, RangeSum::unit(x)), versions.len());
+                        versions.push(next);
+                    }
+                    1 => {
+                        let slope = rng.random_range(-2..=2);
+                        let intercept = rng.random_range(-10..=10);
+                        let mut next = versions[ver].clone();
+                        for x in &mut next[l..r] {
+                            *x = slope * *x + intercept;
+                        }
+                        let f = Affine { slope, intercept };
+                        assert_eq!(seg.apply_range(ver, l..r, f), versions.len());
+                        versions.push(next);
+                    }
+                    _ => {
+                        let a = &versions[ver];
+                        let res = seg.prod(ver, l..r);
+                        assert_eq!(res.sum, a[l..r].iter().sum::<i64>());
+                        assert_eq!(res.len, (r - l) as i64);
+                        let p = rng.random_range(0..n);
+                        assert_eq!(seg.get(ver, p).sum, a[p]);
+                    }
+                }
+            }
+        }
+    }
+}
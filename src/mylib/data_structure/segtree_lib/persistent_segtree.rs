@@ -0,0 +1,322 @@
+use cargo_snippet::snippet;
+
+#[allow(clippy::module_inception)]
+#[snippet(prefix = "use persistent_segtree::*;")]
+pub mod persistent_segtree {
+    use ac_library::Monoid;
+    use std::ops::{Bound, RangeBounds};
+
+    #[derive(Clone, Debug)]
+    struct Node<S> {
+        val: S,
+        left: usize,
+        right: usize,
+    }
+
+    /// 永続セグメント木
+    ///
+    /// 一点更新では根から葉までのパス上の O(log n) 個のノードだけを複製し、新しいバージョンの根とする。
+    /// どのバージョンに対しても区間積や二分探索ができる。バージョン 0 は構築時の列を表す。
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let mut seg = PersistentSegtree::<Additive<i64>>::from(vec![1, 2, 3]);
+    /// let v1 = seg.set(0, 1, 10);
+    /// assert_eq!(seg.prod(0, ..), 6);
+    /// assert_eq!(seg.prod(v1, ..), 14);
+    /// ```
+    #[derive(Clone, Debug)]
+    pub struct PersistentSegtree<M: Monoid> {
+        n: usize,
+        nodes: Vec<Node<M::S>>,
+        roots: Vec<usize>,
+    }
+
+    impl<M: Monoid> From<Vec<M::S>> for PersistentSegtree<M> {
+        fn from(v: Vec<M::S>) -> Self {
+            let n = v.len();
+            let mut seg = Self {
+                n,
+                nodes: vec![],
+                roots: vec![],
+            };
+            let root = if n == 0 {
+                seg.new_node(M::identity(), 0, 0)
+            } else {
+                seg.build(&v, 0, n)
+            };
+            seg.roots.push(root);
+            seg
+        }
+    }
+
+    impl<M: Monoid> PersistentSegtree<M> {
+        /// 長さ `n` で全要素が単位元の列をバージョン 0 として構築する
+        ///
+        /// # 計算量
+        /// O(n)
+        pub fn new(n: usize) -> Self {
+            vec![M::identity(); n].into()
+        }
+
+        /// 列の長さを返す
+        pub fn len(&self) -> usize {
+            self.n
+        }
+
+        /// 列が空かどうかを返す
+        pub fn is_empty(&self) -> bool {
+            self.n == 0
+        }
+
+        /// これまでに作られたバージョンの個数を返す
+        pub fn num_versions(&self) -> usize {
+            self.roots.len()
+        }
+
+        /// バージョン `ver` の `p` 番目の要素を `x` にした新しいバージョンを作り、その番号を返す
+        ///
+        /// # 計算量
+        /// O(log n)
+        pub fn set(&mut self, ver: usize, p: usize, x: M::S) -> usize {
+            assert!(p < self.n);
+            let root = self.set_rec(self.roots[ver], 0, self.n, p, x);
+            self.roots.push(root);
+            self.roots.len() - 1
+        }
+
+        /// バージョン `ver` の `p` 番目の要素を返す
+        ///
+        /// # 計算量
+        /// O(log n)
+        pub fn get(&self, ver: usize, p: usize) -> M::S {
+            assert!(p < self.n);
+            let (mut node, mut l, mut r) = (self.roots[ver], 0, self.n);
+            while r - l > 1 {
+                let mid = (l + r) / 2;
+                if p < mid {
+                    node = self.nodes[node].left;
+                    r = mid;
+                } else {
+                    node = self.nodes[node].right;
+                    l = mid;
+                }
+            }
+            self.nodes[node].val.clone()
+        }
+
+        /// バージョン `ver` の区間 `range` の総積を返す
+        ///
+        /// # 計算量
+        /// O(log n)
+        pub fn prod(&self, ver: usize, range: impl RangeBounds<usize>) -> M::S {
+            let (l, r) = self.range(range);
+            if l == r {
+                return M::identity();
+            }
+            self.prod_rec(self.roots[ver], 0, self.n, l, r)
+        }
+
+        /// バージョン `ver` の全体の総積を返す
+        ///
+        /// # 計算量
+        /// O(1)
+        pub fn all_prod(&self, ver: usize) -> M::S {
+            self.nodes[self.roots[ver]].val.clone()
+        }
+
+        fn range(&self, range: impl RangeBounds<usize>) -> (usize, usize) {
+            let l = match range.start_bound() {
+                Bound::Included(&l) => l,
+                Bound::Excluded(&l) => l + 1,
+                Bound::Unbounded => 0,
+            };
+            let r = match range.end_bound() {
+                Bound::Included(&r) => r + 1,
+                Bound::Excluded(&r) => r,
+                Bound::Unbounded => self.n,
+            };
+            assert!(l <= r && r <= self.n);
+            (l, r)
+        }
+
+        /// バージョン `ver` において、`f(prod(l..r)) == true` となる最大の `r` を返す
+        ///
+        /// `f` は単調で、`f(identity) == true` である必要がある。
+        ///
+        /// # 計算量
+        /// O(log n)
+        pub fn max_right<F>(&self, ver: usize, l: usize, f: F) -> usize
+        where
+            F: Fn(&M::S) -> bool,
+        {
+            assert!(l <= self.n);
+            assert!(f(&M::identity()));
+            if l == self.n {
+                return self.n;
+            }
+            let mut acc = M::identity();
+            self.max_right_rec(self.roots[ver], 0, self.n, l, &f, &mut acc)
+        }
+
+        fn new_node(&mut self, val: M::S, left: usize, right: usize) -> usize {
+            self.nodes.push(Node { val, left, right });
+            self.nodes.len() - 1
+        }
+
+        fn build(&mut self, v: &[M::S], l: usize, r: usize) -> usize {
+            if r - l == 1 {
+                return self.new_node(v[l].clone(), 0, 0);
+            }
+            let mid = (l + r) / 2;
+            let left = self.build(v, l, mid);
+            let right = self.build(v, mid, r);
+            let val = M::binary_operation(&self.nodes[left].val, &self.nodes[right].val);
+            self.new_node(val, left, right)
+        }
+
+        fn set_rec(&mut self, node: usize, l: usize, r: usize, p: usize, x: M::S) -> usize {
+            if r - l == 1 {
+                return self.new_node(x, 0, 0);
+            }
+            let mid = (l + r) / 2;
+            let (mut left, mut right) = (self.nodes[node].left, self.nodes[node].right);
+            if p < mid {
+                left = self.set_rec(left, l, mid, p, x);
+            } else {
+                right = self.set_rec(right, mid, r, p, x);
+            }
+            let val = M::binary_operation(&self.nodes[left].val, &self.nodes[right].val);
+            self.new_node(val, left, right)
+        }
+
+        fn prod_rec(&self, node: usize, l: usize, r: usize, ql: usize, qr: usize) -> M::S {
+            if ql <= l && r <= qr {
+                return self.nodes[node].val.clone();
+            }
+            let mid = (l + r) / 2;
+            let Node { left, right, .. } = self.nodes[node];
+            if qr <= mid {
+                self.prod_rec(left, l, mid, ql, qr)
+            } else if mid <= ql {
+                self.prod_rec(right, mid, r, ql, qr)
+            } else {
+                M::binary_operation(
+                    &self.prod_rec(left, l, mid, ql, qr),
+                    &self.prod_rec(right, mid, r, ql, qr),
+                )
+            }
+        }
+
+        /// [max(l, ql), r) を左から見ていき、`f` を満たさなくなる位置を返す。最後まで満たせば `r` を返す
+        fn max_right_rec<F>(
+            &self,
+            node: usize,
+            l: usize,
+            r: usize,
+            ql: usize,
+            f: &F,
+            acc: &mut M::S,
+        ) -> usize
+        where
+            F: Fn(&M::S) -> bool,
+        {
+            if r <= ql {
+                return r;
+            }
+            if ql <= l {
+                let next = M::binary_operation(acc, &self.nodes[node].val);
+                if f(&next) {
+                    *acc = next;
+                    return r;
+                }
+                if r - l == 1 {
+                    return l;
+                }
+            }
+            let mid = (l + r) / 2;
+            let Node { left, right, .. } = self.nodes[node];
+            let res = self.max_right_rec(left, l, mid, ql, f, acc);
+            if res < mid {
+                return res;
+            }
+            self.max_right_rec(right, mid, r, ql, f, acc)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::persistent_segtree::*;
+    use ac_library::{Additive, Max};
+
+    #[test]
+    fn test_persistent_segtree() {
+        let mut seg = PersistentSegtree::<Additive<i64>>::from(vec![1, 2, 3, 4]);
+        let v1 = seg.set(0, 1, 10);
+        let v2 = seg.set(v1, 3, 0);
+        let v3 = seg.set(0, 0, 100);
+        assert_eq!(seg.num_versions(), 4);
+
+        assert_eq!(seg.prod(0, ..), 10);
+        assert_eq!(seg.prod(v1, ..), 18);
+        assert_eq!(seg.prod(v2, ..), 14);
+        assert_eq!(seg.prod(v3, ..), 109);
+        assert_eq!(seg.prod(v2, 1..=2), 13);
+        assert_eq!(seg.prod(v2, 2..2), 0);
+        assert_eq!(seg.all_prod(v3), 109);
+        assert_eq!(seg.get(v1, 1), 10);
+        assert_eq!(seg.get(v3, 1), 2);
+
+        // 累積和が 12 以下となる最大の r
+        assert_eq!(seg.max_right(v1, 0, |&s| s <= 12), 2);
+        assert_eq!(seg.max_right(0, 0, |&s| s <= 12), 4);
+        assert_eq!(seg.max_right(v2, 2, |&s| s <= 2), 2);
+        assert_eq!(seg.max_right(v2, 4, |&s| s <= 0), 4);
+
+        let seg = PersistentSegtree::<Max<i32>>::new(0);
+        assert!(seg.is_empty());
+        assert_eq!(seg.prod(0, ..), i32::MIN);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_persistent_segtree_random() {
+        use rand::prelude::*;
+
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..50 {
+            let n = rng.random_range(1..=20);
+            let init = (0..n)
+                .map(|_| rng.random_range(0..=10))
+                .collect::<Vec<i64>>();
+            let mut seg = PersistentSegtree::<Additive<i64>>::from(init.clone());
+            let mut versions = vec![init];
+            for _ in 0..100 {
+                let ver = rng.random_range(0..versions.len());
+                if rng.random_bool(0.5) {
+                    let p = rng.random_range(0..n);
+                    let x = rng.random_range(0..=10);
+                    let mut next = versions[ver].clone();
+                    next[p] = x;
+                    assert_eq!(seg.set(ver, p, x), versions.len());
+                    versions.push(next);
+                } else {
+                    let a = &versions[ver];
+                    let l = rng.random_range(0..=n);
+                    let r = rng.random_range(l..=n);
+                    assert_eq!(seg.prod(ver, l..r), a[l..r].iter().sum::<i64>());
+                    let p = rng.random_range(0..n);
+                    assert_eq!(seg.get(ver, p), a[p]);
+
+                    let th = rng.random_range(0..=30);
+                    let expected = (l..=n)
+                        .take_while(|&r| a[l..r].iter().sum::<i64>() <= th)
+                        .last()
+                        .unwrap();
+                    assert_eq!(seg.max_right(ver, l, |&s| s <= th), expected);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,260 @@
+use cargo_snippet::snippet;
+
+#[allow(clippy::module_inception)]
+#[snippet(prefix = "use sparse_table::*;")]
+pub mod sparse_table {
+    use ac_library::Monoid;
+    use std::ops::{Bound, RangeBounds};
+
+    /// 冪等なモノイド (min, max, gcd など) に対して、静的な列の区間積を O(1) で求める Sparse Table
+    ///
+    /// `M` は冪等 (`op(a, a) == a`) である必要がある。冪等でない場合は [`DisjointSparseTable`] を使う。
+    ///
+    /// [`DisjointSparseTable`]: super::disjoint_sparse_table::DisjointSparseTable
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let st = SparseTable::<Max<i64>>::new(&[3, 1, 4, 1, 5]);
+    /// assert_eq!(st.prod(1..4), 4);
+    /// ```
+    #[derive(Clone, Debug)]
+    pub struct SparseTable<M: Monoid> {
+        /// `table[k][i]` は `[i, i + 2^k)` の総積
+        table: Vec<Vec<M::S>>,
+    }
+
+    impl<M: Monoid> SparseTable<M> {
+        /// 列 `xs` から構築する
+        ///
+        /// # 計算量
+        /// O(n log n)
+        pub fn new(xs: &[M::S]) -> Self {
+            let n = xs.len();
+            let mut table = vec![xs.to_vec()];
+            for k in 1.. {
+                let half = 1 << (k - 1);
+                if 2 * half > n {
+                    break;
+                }
+                let prev: &Vec<M::S> = &table[k - 1];
+                let row = (0..=n - 2 * half)
+                    .map(|i| M::binary_operation(&prev[i], &prev[i + half]))
+                    .collect();
+                table.push(row);
+            }
+            Self { table }
+        }
+
+        /// 列の長さを返す
+        pub fn len(&self) -> usize {
+            self.table[0].len()
+        }
+
+        /// 列が空かどうかを返す
+        pub fn is_empty(&self) -> bool {
+            self.len() == 0
+        }
+
+        /// 区間 `range` の総積を返す (空区間なら単位元)
+        ///
+        /// # 計算量
+        /// O(1)
+        pub fn prod(&self, range: impl RangeBounds<usize>) -> M::S {
+            let n = self.len();
+            let l = match range.start_bound() {
+                Bound::Included(&l) => l,
+                Bound::Excluded(&l) => l + 1,
+                Bound::Unbounded => 0,
+            };
+            let r = match range.end_bound() {
+                Bound::Included(&r) => r + 1,
+                Bound::Excluded(&r) => r,
+                Bound::Unbounded => n,
+            };
+            assert!(l <= r && r <= n);
+            if l == r {
+                return M::identity();
+            }
+            let k = (r - l).ilog2() as usize;
+            M::binary_operation(&self.table[k][l], &self.table[k][r - (1 << k)])
+        }
+    }
+}
+
+#[snippet(prefix = "use disjoint_sparse_table::*;")]
+pub mod disjoint_sparse_table {
+    use ac_library::Monoid;
+    use std::ops::{Bound, RangeBounds};
+
+    /// 任意のモノイドに対して、静的な列の区間積を O(1) で求める Disjoint Sparse Table
+    ///
+    /// 各段 k で列を長さ 2^(k+1) のブロックに分け、ブロックの中央から左右に累積した値を持つ。
+    /// 区間 `[l, r)` は `l` と `r - 1` の最上位の異なるビットの段で、左右の累積値 1 回ずつの積で求まる。
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let dst = DisjointSparseTable::<Additive<i64>>::new(&[3, 1, 4, 1, 5]);
+    /// assert_eq!(dst.prod(1..4), 6);
+    /// ```
+    #[derive(Clone, Debug)]
+    pub struct DisjointSparseTable<M: Monoid> {
+        /// `table[k][i]` は、`i` を含む段 k のブロックの中央を `mid` としたとき、
+        /// `i < mid` なら `[i, mid)`、`i >= mid` なら `[mid, i]` の総積
+        table: Vec<Vec<M::S>>,
+    }
+
+    impl<M: Monoid> DisjointSparseTable<M> {
+        /// 列 `xs` から構築する
+        ///
+        /// # 計算量
+        /// O(n log n)
+        pub fn new(xs: &[M::S]) -> Self {
+            let n = xs.len();
+            let log = if n <= 1 {
+                1
+            } else {
+                (n - 1).ilog2() as usize + 1
+            };
+            let mut table = vec![xs.to_vec(); log];
+            for (k, row) in table.iter_mut().enumerate().skip(1) {
+                let half = 1 << k;
+                for mid in (half..n).step_by(2 * half) {
+                    for i in (mid - half..mid - 1).rev() {
+                        row[i] = M::binary_operation(&xs[i], &row[i + 1]);
+                    }
+                    for i in mid + 1..n.min(mid + half) {
+                        row[i] = M::binary_operation(&row[i - 1], &xs[i]);
+                    }
+                }
+            }
+            Self { table }
+        }
+
+        /// 列の長さを返す
+        pub fn len(&self) -> usize {
+            self.table[0].len()
+        }
+
+        /// 列が空かどうかを返す
+        pub fn is_empty(&self) -> bool {
+            self.len() == 0
+        }
+
+        /// 区間 `range` の総積を返す (空区間なら単位元)
+        ///
+        /// # 計算量
+        /// O(1)
+        pub fn prod(&self, range: impl RangeBounds<usize>) -> M::S {
+            let n = self.len();
+            let l = match range.start_bound() {
+                Bound::Included(&l) => l,
+                Bound::Excluded(&l) => l + 1,
+                Bound::Unbounded => 0,
+            };
+            let r = match range.end_bound() {
+                Bound::Included(&r) => r + 1,
+                Bound::Excluded(&r) => r,
+                Bound::Unbounded => n,
+            };
+            assert!(l <= r && r <= n);
+            if l == r {
+                return M::identity();
+            }
+            let last = r - 1;
+            if l == last {
+                return self.table[0][l].clone();
+            }
+            let k = (l ^ last).ilog2() as usize;
+            M::binary_operation(&self.table[k][l], &self.table[k][last])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::disjoint_sparse_table::*;
+    use super::sparse_table::*;
+    use crate::math::algebra::monoid::monoid_gcd_lcm::Gcd;
+    use ac_library::{Additive, Max, Min, Monoid};
+
+    #[test]
+    fn test_sparse_table() {
+        let xs = [3, 1, 4, 1, 5, 9, 2, 6];
+        let st = SparseTable::<Max<i64>>::new(&xs);
+        assert_eq!(st.len(), 8);
+        assert_eq!(st.prod(1..4), 4);
+        assert_eq!(st.prod(..), 9);
+        assert_eq!(st.prod(6..=7), 6);
+        assert_eq!(st.prod(3..3), i64::MIN);
+
+        let st = SparseTable::<Min<i64>>::new(&xs);
+        assert_eq!(st.prod(4..), 2);
+
+        let st = SparseTable::<Gcd<i64>>::new(&[12, 18, 24, 9]);
+        assert_eq!(st.prod(..3), 6);
+        assert_eq!(st.prod(..), 3);
+
+        let st = SparseTable::<Max<i64>>::new(&[]);
+        assert!(st.is_empty());
+        assert_eq!(st.prod(..), i64::MIN);
+    }
+
+    #[test]
+    fn test_disjoint_sparse_table() {
+        let xs = [3, 1, 4, 1, 5, 9, 2, 6];
+        let dst = DisjointSparseTable::<Additive<i64>>::new(&xs);
+        assert_eq!(dst.len(), 8);
+        assert_eq!(dst.prod(1..4), 6);
+        assert_eq!(dst.prod(..), 31);
+        assert_eq!(dst.prod(5..=5), 9);
+        assert_eq!(dst.prod(3..3), 0);
+
+        let dst = DisjointSparseTable::<Additive<i64>>::new(&[7]);
+        assert_eq!(dst.prod(..), 7);
+        let dst = DisjointSparseTable::<Additive<i64>>::new(&[]);
+        assert!(dst.is_empty());
+        assert_eq!(dst.prod(..), 0);
+    }
+
+    /// 非可換なモノイド (文字列の連結)
+    struct Concat;
+    impl Monoid for Concat {
+        type S = String;
+        fn identity() -> String {
+            String::new()
+        }
+        fn binary_operation(a: &String, b: &String) -> String {
+            format!("{a}{b}")
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn test_sparse_table_random() {
+        use rand::prelude::*;
+
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let n = rng.random_range(0..=40);
+            let xs = (0..n)
+                .map(|_| rng.random_range(-100..=100))
+                .collect::<Vec<i64>>();
+            let strs = (0..n)
+                .map(|_| ((b'a' + rng.random_range(0..26)) as char).to_string())
+                .collect::<Vec<_>>();
+            let st = SparseTable::<Max<i64>>::new(&xs);
+            let dst = DisjointSparseTable::<Additive<i64>>::new(&xs);
+            let dst_str = DisjointSparseTable::<Concat>::new(&strs);
+            for l in 0..=n {
+                for r in l..=n {
+                    assert_eq!(
+                        st.prod(l..r),
+                        xs[l..r].iter().copied().max().unwrap_or(i64::MIN)
+                    );
+                    assert_eq!(dst.prod(l..r), xs[l..r].iter().sum::<i64>());
+                    assert_eq!(dst_str.prod(l..r), strs[l..r].concat());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,540 @@
+use crate::math::algebra::ab_group::ab_group::AbGroup;
+use cargo_snippet::snippet;
+
+#[allow(clippy::module_inception)]
+#[snippet(prefix = "use wavelet_matrix::*;", include = "ab_group")]
+pub mod wavelet_matrix {
+    use super::AbGroup;
+    use std::cmp::Reverse;
+    use std::collections::BinaryHeap;
+    use std::ops::{Bound, RangeBounds};
+
+    /// 静的な列に対する区間 k 番目・区間頻度などのクエリに答える Wavelet Matrix
+    ///
+    /// 値は内部で座標圧縮するので、`i64` の任意の値を扱えます。
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let wm = WaveletMatrix::new(&[5, 1, 4, 1, 3]);
+    /// assert_eq!(wm.kth_smallest(1..4, 0), Some(1));
+    /// assert_eq!(wm.range_freq(.., 2..=4), 2);
+    /// assert_eq!(wm.prev_value(0..3, 5), Some(4));
+    /// ```
+    #[derive(Clone, Debug)]
+    pub struct WaveletMatrix {
+        n: usize,
+        /// 座標圧縮後の値から元の値への対応 (昇順)
+        vals: Vec<i64>,
+        log: usize,
+        /// `levels[d]` は上から d 番目のビット (ビット `log - 1 - d`) を表すビット列
+        levels: Vec<BitVector>,
+        /// `zeros[d]` は `levels[d]` の 0 の個数
+        zeros: Vec<usize>,
+    }
+
+    impl WaveletMatrix {
+        /// 列 `xs` から Wavelet Matrix を構築します。
+        ///
+        /// # 計算量
+        /// $O(N \log N)$
+        pub fn new(xs: &[i64]) -> Self {
+            let n = xs.len();
+            let mut vals = xs.to_vec();
+            vals.sort_unstable();
+            vals.dedup();
+            let log = (usize::BITS - vals.len().saturating_sub(1).leading_zeros()) as usize;
+
+            let mut cur = xs
+                .iter()
+                .map(|x| vals.binary_search(x).unwrap())
+                .collect::<Vec<_>>();
+            let mut levels = Vec::with_capacity(log);
+            let mut zeros = Vec::with_capacity(log);
+            for d in 0..log {
+                let shift = log - 1 - d;
+                let bits = cur.iter().map(|&c| c >> shift & 1 == 1).collect::<Vec<_>>();
+                let bv = BitVector::new(&bits);
+                zeros.push(bv.rank0(n));
+                levels.push(bv);
+                // 0 のものを前に、1 のものを後ろに安定に並べ替える
+                cur = cur
+                    .iter()
+                    .filter(|&&c| c >> shift & 1 == 0)
+                    .chain(cur.iter().filter(|&&c| c >> shift & 1 == 1))
+                    .copied()
+                    .collect();
+            }
+
+            Self {
+                n,
+                vals,
+                log,
+                levels,
+                zeros,
+            }
+        }
+
+        /// 列の長さを返します。
+        pub fn len(&self) -> usize {
+            self.n
+        }
+
+        /// 列が空かどうかを返します。
+        pub fn is_empty(&self) -> bool {
+            self.n == 0
+        }
+
+        /// `i` 番目の値を返します。
+        ///
+        /// # 計算量
+        /// $O(\log N)$
+        pub fn get(&self, mut i: usize) -> i64 {
+            assert!(i < self.n);
+            let mut c = 0;
+            for d in 0..self.log {
+                let bv = &self.levels[d];
+                if bv.get(i) {
+                    c |= 1 << (self.log - 1 - d);
+                    i = self.zeros[d] + bv.rank1(i);
+                } else {
+                    i = bv.rank0(i);
+                }
+            }
+            self.vals[c]
+        }
+
+        /// 区間 `range` の中で `k` 番目 (0-indexed) に小さい値を返します。区間の長さが `k` 以下なら `None`。
+        ///
+        /// # 計算量
+        /// $O(\log N)$
+        pub fn kth_smallest(&self, range: impl RangeBounds<usize>, k: usize) -> Option<i64> {
+            let (mut l, mut r) = self.index_range(range);
+            if k >= r - l {
+                return None;
+            }
+            let mut k = k;
+            let mut c = 0;
+            for d in 0..self.log {
+                let bv = &self.levels[d];
+                let (l0, r0) = (bv.rank0(l), bv.rank0(r));
+                if k < r0 - l0 {
+                    l = l0;
+                    r = r0;
+                } else {
+                    k -= r0 - l0;
+                    c |= 1 << (self.log - 1 - d);
+                    l = self.zeros[d] + (l - l0);
+                    r = self.zeros[d] + (r - r0);
+                }
+            }
+            Some(self.vals[c])
+        }
+
+        /// 区間 `range` の中で `k` 番目 (0-indexed) に大きい値を返します。区間の長さが `k` 以下なら `None`。
+        ///
+        /// # 計算量
+        /// $O(\log N)$
+        pub fn kth_largest(&self, range: impl RangeBounds<usize>, k: usize) -> Option<i64> {
+            let (l, r) = self.index_range(range);
+            if k >= r - l {
+                return None;
+            }
+            self.kth_smallest(l..r, r - l - 1 - k)
+        }
+
+        /// 区間 `range` の中で、値が `value_range` に含まれるものの個数を返します。
+        ///
+        /// # 計算量
+        /// $O(\log N)$
+        pub fn range_freq(
+            &self,
+            range: impl RangeBounds<usize>,
+            value_range: impl RangeBounds<i64>,
+        ) -> usize {
+            let (l, r) = self.index_range(range);
+            let (lo, hi) = self.value_range(value_range);
+            if lo >= hi {
+                return 0;
+            }
+            self.count_less(l, r, hi) - self.count_less(l, r, lo)
+        }
+
+        /// 区間 `range` の中で、`upper` 未満の最大の値を返します。存在しなければ `None`。
+        ///
+        /// # 計算量
+        /// $O(\log N)$
+        pub fn prev_value(&self, range: impl RangeBounds<usize>, upper: i64) -> Option<i64> {
+            let (l, r) = self.index_range(range);
+            let cnt = self.count_less(l, r, self.vals.partition_point(|&v| v < upper));
+            if cnt == 0 {
+                None
+            } else {
+                self.kth_smallest(l..r, cnt - 1)
+            }
+        }
+
+        /// 区間 `range` の中で、`lower` 以上の最小の値を返します。存在しなければ `None`。
+        ///
+        /// # 計算量
+        /// $O(\log N)$
+        pub fn next_value(&self, range: impl RangeBounds<usize>, lower: i64) -> Option<i64> {
+            let (l, r) = self.index_range(range);
+            let cnt = self.count_less(l, r, self.vals.partition_point(|&v| v < lower));
+            self.kth_smallest(l..r, cnt)
+        }
+
+        /// 区間 `range` の中で出現回数が多い順に `k` 個の `(値, 出現回数)` を返します。
+        ///
+        /// 出現回数が同じ場合は値の小さい方を先にします。
+        ///
+        /// # 計算量
+        /// $O(k \log N \log k)$ 程度
+        pub fn top_k(&self, range: impl RangeBounds<usize>, k: usize) -> Vec<(i64, usize)> {
+            let (l, r) = self.index_range(range);
+            let mut res = vec![];
+            if l >= r {
+                return res;
+            }
+            // (区間の幅, その節点に含まれる最小の値 (逆順), 深さ, l, r)
+            let mut pq = BinaryHeap::new();
+            pq.push((r - l, Reverse(0), 0, l, r));
+            while let Some((width, Reverse(c), d, l, r)) = pq.pop() {
+                if res.len() == k {
+                    break;
+                }
+                if d == self.log {
+                    res.push((self.vals[c], width));
+                    continue;
+                }
+                let bv = &self.levels[d];
+                let (l0, r0) = (bv.rank0(l), bv.rank0(r));
+                if l0 < r0 {
+                    pq.push((r0 - l0, Reverse(c), d + 1, l0, r0));
+                }
+                let (l1, r1) = (self.zeros[d] + (l - l0), self.zeros[d] + (r - r0));
+                if l1 < r1 {
+                    let c1 = c | 1 << (self.log - 1 - d);
+                    pq.push((r1 - l1, Reverse(c1), d + 1, l1, r1));
+                }
+            }
+            res
+        }
+
+        /// [l, r) の中で座標圧縮後の値が `c` 未満のものの個数
+        fn count_less(&self, l: usize, r: usize, c: usize) -> usize {
+            self.descend_less(l, r, c, |_, _, _| {})
+        }
+
+        /// [l, r) の中で座標圧縮後の値が `c` 未満のものの個数を返す。
+        /// 各段で「`c` 未満であることが確定した要素」の次の段での区間 `[l0, r0)` を `f(d, l0, r0)` で通知する
+        fn descend_less(
+            &self,
+            mut l: usize,
+            mut r: usize,
+            c: usize,
+            mut f: impl FnMut(usize, usize, usize),
+        ) -> usize {
+            if c >= 1 << self.log {
+                return r - l;
+            }
+            let mut res = 0;
+            for d in 0..self.log {
+                let bv = &self.levels[d];
+                let (l0, r0) = (bv.rank0(l), bv.rank0(r));
+                if c >> (self.log - 1 - d) & 1 == 1 {
+                    res += r0 - l0;
+                    f(d, l0, r0);
+                    l = self.zeros[d] + (l - l0);
+                    r = self.zeros[d] + (r - r0);
+                } else {
+                    l = l0;
+                    r = r0;
+                }
+            }
+            res
+        }
+
+        fn index_range(&self, range: impl RangeBounds<usize>) -> (usize, usize) {
+            let l = match range.start_bound() {
+                Bound::Included(&l) => l,
+                Bound::Excluded(&l) => l + 1,
+                Bound::Unbounded => 0,
+            };
+            let r = match range.end_bound() {
+                Bound::Included(&r) => r + 1,
+                Bound::Excluded(&r) => r,
+                Bound::Unbounded => self.n,
+            };
+            assert!(l <= r && r <= self.n);
+            (l, r)
+        }
+
+        /// 値の範囲を座標圧縮後の値の範囲 [lo, hi) に変換する
+        fn value_range(&self, range: impl RangeBounds<i64>) -> (usize, usize) {
+            let lo = match range.start_bound() {
+                Bound::Included(&x) => self.vals.partition_point(|&v| v < x),
+                Bound::Excluded(&x) => self.vals.partition_point(|&v| v <= x),
+                Bound::Unbounded => 0,
+            };
+            let hi = match range.end_bound() {
+                Bound::Included(&x) => self.vals.partition_point(|&v| v <= x),
+                Bound::Excluded(&x) => self.vals.partition_point(|&v| v < x),
+                Bound::Unbounded => self.vals.len(),
+            };
+            (lo, hi)
+        }
+    }
+
+    /// 各要素に可換群の重みを持たせ、区間内で値が指定範囲に含まれる要素の重みの総和も求められる Wavelet Matrix
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let xs = [5, 1, 4, 1, 3];
+    /// let wm = WaveletMatrixSum::<AdditiveAbGroup<i64>>::new(&xs, &xs);
+    /// // a[1..4] のうち 4 以下の値の和
+    /// assert_eq!(wm.range_sum(1..4, ..=4), 6);
+    /// ```
+    #[derive(Clone, Debug)]
+    pub struct WaveletMatrixSum<G: AbGroup> {
+        wm: WaveletMatrix,
+        /// `sums[0]` は元の列の重みの累積和、`sums[d + 1]` は d 段目で並べ替えた後の列の重みの累積和
+        sums: Vec<Vec<G::S>>,
+    }
+
+    impl<G: AbGroup> WaveletMatrixSum<G> {
+        /// 列 `xs` と各要素の重み `weights` から構築します。
+        ///
+        /// # 計算量
+        /// $O(N \log N)$
+        pub fn new(xs: &[i64], weights: &[G::S]) -> Self {
+            assert_eq!(xs.len(), weights.len());
+            let wm = WaveletMatrix::new(xs);
+            let prefix = |order: &[usize]| {
+                let mut acc = vec![G::zero()];
+                for &i in order {
+                    acc.push(G::add(acc.last().unwrap(), &weights[i]));
+                }
+                acc
+            };
+
+            let codes = xs
+                .iter()
+                .map(|x| wm.vals.binary_search(x).unwrap())
+                .collect::<Vec<_>>();
+            let mut order = (0..xs.len()).collect::<Vec<_>>();
+            let mut sums = vec![prefix(&order)];
+            for d in 0..wm.log {
+                let shift = wm.log - 1 - d;
+                order = order
+                    .iter()
+                    .filter(|&&i| codes[i] >> shift & 1 == 0)
+                    .chain(order.iter().filter(|&&i| codes[i] >> shift & 1 == 1))
+                    .copied()
+                    .collect();
+                sums.push(prefix(&order));
+            }
+            Self { wm, sums }
+        }
+
+        /// 内部の [`WaveletMatrix`] を返します。
+        pub fn wavelet_matrix(&self) -> &WaveletMatrix {
+            &self.wm
+        }
+
+        /// 区間 `range` の中で、値が `value_range` に含まれる要素の重みの総和を返します。
+        ///
+        /// # 計算量
+        /// $O(\log N)$
+        pub fn range_sum(
+            &self,
+            range: impl RangeBounds<usize>,
+            value_range: impl RangeBounds<i64>,
+        ) -> G::S {
+            let (l, r) = self.wm.index_range(range);
+            let (lo, hi) = self.wm.value_range(value_range);
+            if lo >= hi {
+                return G::zero();
+            }
+            G::sub(&self.sum_less(l, r, hi), &self.sum_less(l, r, lo))
+        }
+
+        /// [l, r) の中で座標圧縮後の値が `c` 未満の要素の重みの総和
+        fn sum_less(&self, l: usize, r: usize, c: usize) -> G::S {
+            if c >= 1 << self.wm.log {
+                return G::sub(&self.sums[0][r], &self.sums[0][l]);
+            }
+            let mut res = G::zero();
+            self.wm.descend_less(l, r, c, |d, l0, r0| {
+                let s = &self.sums[d + 1];
+                res = G::add(&res, &G::sub(&s[r0], &s[l0]));
+            });
+            res
+        }
+    }
+
+    /// rank を O(1) で求められるビット列
+    #[derive(Clone, Debug)]
+    struct BitVector {
+        blocks: Vec<u64>,
+        /// `cum[i]` は `blocks[..i]` の 1 の個数
+        cum: Vec<usize>,
+    }
+
+    impl BitVector {
+        fn new(bits: &[bool]) -> Self {
+            let mut blocks = vec![0_u64; bits.len() / 64 + 1];
+            for (i, &b) in bits.iter().enumerate() {
+                if b {
+                    blocks[i / 64] |= 1 << (i % 64);
+                }
+            }
+            let mut cum = vec![0; blocks.len() + 1];
+            for i in 0..blocks.len() {
+                cum[i + 1] = cum[i] + blocks[i].count_ones() as usize;
+            }
+            Self { blocks, cum }
+        }
+
+        fn get(&self, i: usize) -> bool {
+            self.blocks[i / 64] >> (i % 64) & 1 == 1
+        }
+
+        /// [0, i) の 1 の個数
+        fn rank1(&self, i: usize) -> usize {
+            let mask = (1_u64 << (i % 64)) - 1;
+            self.cum[i / 64] + (self.blocks[i / 64] & mask).count_ones() as usize
+        }
+
+        /// [0, i) の 0 の個数
+        fn rank0(&self, i: usize) -> usize {
+            i - self.rank1(i)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::wavelet_matrix::*;
+    use crate::math::algebra::ab_group::ab_group::AdditiveAbGroup;
+    use std::ops::Bound;
+
+    #[test]
+    fn test_wavelet_matrix() {
+        let xs = [5, 1, 4, 1, 3, -2, 1_000_000_000_000];
+        let wm = WaveletMatrix::new(&xs);
+        assert_eq!(wm.len(), 7);
+        assert!(!wm.is_empty());
+        for (i, &x) in xs.iter().enumerate() {
+            assert_eq!(wm.get(i), x);
+        }
+
+        assert_eq!(wm.kth_smallest(1..4, 0), Some(1));
+        assert_eq!(wm.kth_smallest(1..4, 2), Some(4));
+        assert_eq!(wm.kth_smallest(1..4, 3), None);
+        assert_eq!(wm.kth_largest(.., 0), Some(1_000_000_000_000));
+        assert_eq!(wm.kth_largest(..=5, 1), Some(4));
+
+        assert_eq!(wm.range_freq(.., 2..=4), 2);
+        assert_eq!(wm.range_freq(0..4, 1..2), 2);
+        assert_eq!(wm.range_freq(.., ..), 7);
+        assert_eq!(wm.range_freq(2..2, ..), 0);
+        assert_eq!(
+            wm.range_freq(.., (Bound::Excluded(4), Bound::Excluded(5))),
+            0
+        );
+
+        assert_eq!(wm.prev_value(0..3, 5), Some(4));
+        assert_eq!(wm.prev_value(0..3, 1), None);
+        assert_eq!(wm.next_value(0..5, 2), Some(3));
+        assert_eq!(wm.next_value(0..5, 6), None);
+        assert_eq!(wm.next_value(.., 6), Some(1_000_000_000_000));
+
+        assert_eq!(wm.top_k(.., 2), vec![(1, 2), (-2, 1)]);
+        assert_eq!(wm.top_k(1..4, 10), vec![(1, 2), (4, 1)]);
+
+        let empty = WaveletMatrix::new(&[]);
+        assert!(empty.is_empty());
+        assert_eq!(empty.kth_smallest(.., 0), None);
+        assert_eq!(empty.range_freq(.., ..), 0);
+        assert_eq!(empty.top_k(.., 3), vec![]);
+
+        let single = WaveletMatrix::new(&[7, 7, 7]);
+        assert_eq!(single.kth_smallest(.., 2), Some(7));
+        assert_eq!(single.range_freq(.., 7..8), 3);
+        assert_eq!(single.prev_value(.., 8), Some(7));
+        assert_eq!(single.top_k(.., 1), vec![(7, 3)]);
+    }
+
+    #[test]
+    fn test_wavelet_matrix_sum() {
+        let xs = [5, 1, 4, 1, 3];
+        let wm = WaveletMatrixSum::<AdditiveAbGroup<i64>>::new(&xs, &xs);
+        assert_eq!(wm.range_sum(1..4, ..=4), 6);
+        assert_eq!(wm.range_sum(.., ..), 14);
+        assert_eq!(wm.range_sum(.., 2..5), 7);
+        assert_eq!(wm.range_sum(.., 6..), 0);
+        assert_eq!(wm.wavelet_matrix().kth_smallest(.., 4), Some(5));
+    }
+
+    #[test]
+    #[ignore]
+    fn test_wavelet_matrix_random() {
+        use rand::prelude::*;
+        use std::collections::BTreeMap;
+
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let n = rng.random_range(0..=40);
+            let max = rng.random_range(1..=20);
+            let xs = (0..n)
+                .map(|_| rng.random_range(-max..=max))
+                .collect::<Vec<i64>>();
+            let ws = (0..n)
+                .map(|_| rng.random_range(-100..=100))
+                .collect::<Vec<i64>>();
+            let wm = WaveletMatrix::new(&xs);
+            let wms = WaveletMatrixSum::<AdditiveAbGroup<i64>>::new(&xs, &ws);
+
+            for l in 0..=n {
+                for r in l..=n {
+                    let mut sorted = xs[l..r].to_vec();
+                    sorted.sort();
+                    for k in 0..=r - l {
+                        assert_eq!(wm.kth_smallest(l..r, k), sorted.get(k).copied());
+                    }
+                    for _ in 0..5 {
+                        let lo = rng.random_range(-max - 1..=max + 1);
+                        let hi = rng.random_range(-max - 1..=max + 1);
+                        let in_range = |x: i64| lo <= x && x < hi;
+                        assert_eq!(
+                            wm.range_freq(l..r, lo..hi),
+                            xs[l..r].iter().filter(|&&x| in_range(x)).count()
+                        );
+                        assert_eq!(
+                            wms.range_sum(l..r, lo..hi),
+                            (l..r).filter(|&i| in_range(xs[i])).map(|i| ws[i]).sum()
+                        );
+                        assert_eq!(
+                            wm.prev_value(l..r, lo),
+                            xs[l..r].iter().copied().filter(|&x| x < lo).max()
+                        );
+                        assert_eq!(
+                            wm.next_value(l..r, lo),
+                            xs[l..r].iter().copied().filter(|&x| x >= lo).min()
+                        );
+                    }
+
+                    let mut freq = BTreeMap::new();
+                    for &x in &xs[l..r] {
+                        *freq.entry(x).or_insert(0) += 1;
+                    }
+                    let mut expected = freq.into_iter().collect::<Vec<_>>();
+                    expected.sort_by_key(|&(x, c)| (std::cmp::Reverse(c), x));
+                    let k = rng.random_range(0..=expected.len() + 1);
+                    expected.truncate(k);
+                    assert_eq!(wm.top_k(l..r, k), expected);
+                }
+            }
+        }
+    }
+}
@@ -1,15 +1,32 @@
+pub mod acl_flow_ix;
 pub mod acl_scc;
 pub mod acl_scc_ix;
+pub mod apsp;
 pub mod bellman_ford;
 pub mod bfs;
 pub mod bfs01;
+pub mod bipartite_matching;
+pub mod chromatic_number;
 pub mod cycle_detection_directed;
+pub mod dag;
 pub mod dijkstra;
+pub mod dominator_tree;
 pub mod doubling;
+pub mod euler_trail;
 pub mod functional_graph;
+pub mod general_matching;
 #[allow(clippy::module_inception)]
 pub mod graph;
+pub mod grid_graph;
+pub mod hamiltonian;
+pub mod hungarian;
+pub mod k_shortest_paths;
 pub mod lowlink;
+pub mod max_clique;
+pub mod mst;
+pub mod shortest_path_dag;
+pub mod steiner_tree;
 pub mod topo_sort;
 pub mod tree;
 pub mod tree_diameter;
+pub mod two_sat_builder;
@@ -0,0 +1,724 @@
+use crate::data_structure::ix::{Bounds, Ix, IxVec};
+use cargo_snippet::snippet;
+
+#[allow(clippy::module_inception)]
+#[snippet(prefix = "use acl_flow_ix::*;")]
+pub mod acl_flow_ix {
+    use super::{Bounds, Ix, IxVec};
+    use ac_library::{MfGraph, MinCostFlowGraph};
+
+    /// フローネットワークの辺 (Ix版)
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct FlowEdgeIx<I: Ix> {
+        pub from: I,
+        pub to: I,
+        pub cap: i64,
+        pub flow: i64,
+    }
+
+    /// 最大流 (Ix版)。`ac_library::MfGraph` のラッパー
+    #[derive(Clone)]
+    pub struct MfGraphIx<I: Ix> {
+        bounds: Bounds<I>,
+        graph: MfGraph<i64>,
+    }
+
+    impl<I: Ix> MfGraphIx<I> {
+        /// 指定された範囲の頂点を持ち、辺のないグラフを作成する
+        ///
+        /// # Arguments
+        /// * `bounds` - 頂点のインデックス範囲
+        pub fn new(bounds: Bounds<I>) -> Self {
+            Self {
+                bounds,
+                graph: MfGraph::new(bounds.range_size()),
+            }
+        }
+
+        /// 容量 `cap` の辺 `from -> to` を追加し、辺の番号を返す
+        pub fn add_edge(&mut self, from: I, to: I, cap: i64) -> usize {
+            self.graph
+                .add_edge(self.bounds.to_index(from), self.bounds.to_index(to), cap)
+        }
+
+        /// `i` 番目の辺の状態を返す
+        pub fn get_edge(&self, i: usize) -> FlowEdgeIx<I> {
+            let e = self.graph.get_edge(i);
+            FlowEdgeIx {
+                from: self.bounds.from_index(e.from),
+                to: self.bounds.from_index(e.to),
+                cap: e.cap,
+                flow: e.flow,
+            }
+        }
+
+        /// 全ての辺の状態を追加順に返す
+        pub fn edges(&self) -> Vec<FlowEdgeIx<I>> {
+            self.graph
+                .edges()
+                .into_iter()
+                .map(|e| FlowEdgeIx {
+                    from: self.bounds.from_index(e.from),
+                    to: self.bounds.from_index(e.to),
+                    cap: e.cap,
+                    flow: e.flow,
+                })
+                .collect()
+        }
+
+        /// `i` 番目の辺の容量と流量を変更する
+        pub fn change_edge(&mut self, i: usize, new_cap: i64, new_flow: i64) {
+            self.graph.change_edge(i, new_cap, new_flow);
+        }
+
+        /// `s` から `t` へ流せるだけ流し、流した量を返す
+        ///
+        /// # 計算量
+        /// O(V^2 E)
+        pub fn flow(&mut self, s: I, t: I) -> i64 {
+            self.graph
+                .flow(self.bounds.to_index(s), self.bounds.to_index(t))
+        }
+
+        /// `s` から `t` へ `flow_limit` を上限として流せるだけ流し、流した量を返す
+        ///
+        /// # 計算量
+        /// O(V^2 E)
+        pub fn flow_with_capacity(&mut self, s: I, t: I, flow_limit: i64) -> i64 {
+            self.graph.flow_with_capacity(
+                self.bounds.to_index(s),
+                self.bounds.to_index(t),
+                flow_limit,
+            )
+        }
+
+        /// `flow` を呼んだ後に、残余グラフで `s` から到達可能な頂点集合 (最小カットの `s` 側) を返す
+        ///
+        /// # 計算量
+        /// O(V + E)
+        pub fn min_cut(&self, s: I) -> IxVec<I, bool> {
+            IxVec::from_vec(self.bounds, self.graph.min_cut(self.bounds.to_index(s)))
+        }
+    }
+
+    /// 最小費用流の辺 (Ix版)
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct CostFlowEdgeIx<I: Ix> {
+        pub from: I,
+        pub to: I,
+        pub cap: i64,
+        pub flow: i64,
+        pub cost: i64,
+    }
+
+    /// 最小費用流 (Ix版)。`ac_library::MinCostFlowGraph` のラッパー
+    pub struct McfGraphIx<I: Ix> {
+        bounds: Bounds<I>,
+        graph: MinCostFlowGraph<i64>,
+    }
+
+    impl<I: Ix> McfGraphIx<I> {
+        /// 指定された範囲の頂点を持ち、辺のないグラフを作成する
+        ///
+        /// # Arguments
+        /// * `bounds` - 頂点のインデックス範囲
+        pub fn new(bounds: Bounds<I>) -> Self {
+            Self {
+                bounds,
+                graph: MinCostFlowGraph::new(bounds.range_size()),
+            }
+        }
+
+        /// 容量 `cap`、単位流量あたりのコスト `cost` (非負) の辺 `from -> to` を追加し、辺の番号を返す
+        pub fn add_edge(&mut self, from: I, to: I, cap: i64, cost: i64) -> usize {
+            self.graph.add_edge(
+                self.bounds.to_index(from),
+                self.bounds.to_index(to),
+                cap,
+                cost,
+            )
+        }
+
+        /// `i` 番目の辺の状態を返す
+        pub fn get_edge(&self, i: usize) -> CostFlowEdgeIx<I> {
+            let e = self.graph.get_edge(i);
+            CostFlowEdgeIx {
+                from: self.bounds.from_index(e.from),
+                to: self.bounds.from_index(e.to),
+                cap: e.cap,
+                flow: e.flow,
+                cost: e.cost,
+            }
+        }
+
+        /// 全ての辺の状態を追加順に返す
+        pub fn edges(&self) -> Vec<CostFlowEdgeIx<I>> {
+            self.graph
+                .edges()
+                .into_iter()
+                .map(|e| CostFlowEdgeIx {
+                    from: self.bounds.from_index(e.from),
+                    to: self.bounds.from_index(e.to),
+                    cap: e.cap,
+                    flow: e.flow,
+                    cost: e.cost,
+                })
+                .collect()
+        }
+
+        /// `s` から `t` へ `flow_limit` を上限として流せるだけ流し、`(流量, コスト)` を返す
+        ///
+        /// # 計算量
+        /// O(F (V + E) log (V + E)) (F は流量)
+        pub fn flow(&mut self, s: I, t: I, flow_limit: i64) -> (i64, i64) {
+            self.graph
+                .flow(self.bounds.to_index(s), self.bounds.to_index(t), flow_limit)
+        }
+
+        /// 流量とコストの関係を表す折れ線の頂点 `(流量, コスト)` のリストを返す
+        ///
+        /// # 計算量
+        /// O(F (V + E) log (V + E)) (F は流量)
+        pub fn slope(&mut self, s: I, t: I, flow_limit: i64) -> Vec<(i64, i64)> {
+            self.graph
+                .slope(self.bounds.to_index(s), self.bounds.to_index(t), flow_limit)
+        }
+    }
+
+    /// 最小流量制約付き最大流 (Ix版)
+    ///
+    /// 各辺に流量の下限 `lower` と上限 `upper` を持つネットワークで、制約を満たす s-t フローを扱う。
+    #[derive(Clone)]
+    pub struct LowerBoundedMfGraphIx<I: Ix> {
+        bounds: Bounds<I>,
+        edges: Vec<(usize, usize, i64, i64)>, // (from, to, lower, upper)
+        flows: Vec<i64>,                      // 最後に求めたフローでの各辺の流量
+    }
+
+    impl<I: Ix> LowerBoundedMfGraphIx<I> {
+        /// 指定された範囲の頂点を持ち、辺のないグラフを作成する
+        ///
+        /// # Arguments
+        /// * `bounds` - 頂点のインデックス範囲
+        pub fn new(bounds: Bounds<I>) -> Self {
+            Self {
+                bounds,
+                edges: vec![],
+                flows: vec![],
+            }
+        }
+
+        /// 流量が `lower` 以上 `upper` 以下の辺 `from -> to` を追加し、辺の番号を返す
+        pub fn add_edge(&mut self, from: I, to: I, lower: i64, upper: i64) -> usize {
+            assert!(0 <= lower && lower <= upper);
+            self.edges.push((
+                self.bounds.to_index(from),
+                self.bounds.to_index(to),
+                lower,
+                upper,
+            ));
+            self.edges.len() - 1
+        }
+
+        /// 下限制約を満たす s-t フローのうち、流量最大のものを求める
+        ///
+        /// 流量は `t` への正味の流入量で、下限制約によっては負になることもある。
+        ///
+        /// # Returns
+        /// 制約を満たすフローが存在する場合はその最大流量、存在しない場合は `None`
+        ///
+        /// # 計算量
+        /// O(V^2 E)
+        pub fn max_flow(&mut self, s: I, t: I) -> Option<i64> {
+            let s = self.bounds.to_index(s);
+            let t = self.bounds.to_index(t);
+            self.solve(Some((s, t)))
+        }
+
+        /// 下限制約を満たす循環流 (全頂点で流量保存) が存在するかを判定する
+        ///
+        /// # 計算量
+        /// O(V^2 E)
+        pub fn circulation(&mut self) -> bool {
+            self.solve(None).is_some()
+        }
+
+        fn solve(&mut self, st: Option<(usize, usize)>) -> Option<i64> {
+            let n = self.bounds.range_size();
+            let super_s = n;
+            let super_t = n + 1;
+            let mut graph = MfGraph::<i64>::new(n + 2);
+            let mut excess = vec![0; n];
+            let edge_ids = self
+                .edges
+                .iter()
+                .map(|&(from, to, lower, upper)| {
+                    excess[to] += lower;
+                    excess[from] -= lower;
+                    graph.add_edge(from, to, upper - lower)
+                })
+                .collect::<Vec<_>>();
+
+            let mut demand = 0;
+            for (v, &ex) in excess.iter().enumerate() {
+                if ex > 0 {
+                    graph.add_edge(super_s, v, ex);
+                    demand += ex;
+                } else if ex < 0 {
+                    graph.add_edge(v, super_t, -ex);
+                }
+            }
+
+            // s と t の間は両方向に自由に流せるようにしておく
+            let st = st.map(|(s, t)| {
+                let ts = graph.add_edge(t, s, i64::MAX);
+                let st = graph.add_edge(s, t, i64::MAX);
+                (s, t, ts, st)
+            });
+
+            if graph.flow(super_s, super_t) != demand {
+                return None;
+            }
+
+            let total = if let Some((s, t, ts, st)) = st {
+                let base = graph.get_edge(ts).flow - graph.get_edge(st).flow;
+                graph.change_edge(ts, 0, 0);
+                graph.change_edge(st, 0, 0);
+                base + graph.flow(s, t)
+            } else {
+                0
+            };
+
+            self.flows = edge_ids
+                .iter()
+                .zip(&self.edges)
+                .map(|(&id, &(_, _, lower, _))| lower + graph.get_edge(id).flow)
+                .collect();
+            Some(total)
+        }
+
+        /// 最後に `max_flow` で求めたフローにおける、`i` 番目の辺の流量を返す
+        pub fn edge_flow(&self, i: usize) -> i64 {
+            self.flows[i]
+        }
+    }
+
+    /// 燃やす埋める問題 (Project Selection Problem) を最小カットで解くためのビルダー (Ix版)
+    ///
+    /// 各変数 `x_v` (`v: I`) に `false` か `true` を割り当てる。
+    /// 制約をコストの形で追加していき、コストの総和の最小値と、そのときの割り当てを求める。
+    #[derive(Clone, Debug)]
+    pub struct ProjectSelectionIx<I: Ix> {
+        bounds: Bounds<I>,
+        n_aux: usize,                    // 補助頂点の数
+        edges: Vec<(usize, usize, i64)>, // 頂点番号は 0..nv が変数、nv.. が補助頂点
+        source_edges: Vec<(usize, i64)>, // s -> v
+        sink_edges: Vec<(usize, i64)>,   // v -> t
+        offset: i64,
+    }
+
+    impl<I: Ix> ProjectSelectionIx<I> {
+        /// 実行不可能な割り当てに課すコスト
+        ///
+        /// コストとしてこの値を渡すと、その割り当ては禁止される。
+        /// 実際の容量は `solve` の中で、他のコストの総和を超えつつオーバーフローしない値に置き換えられる。
+        pub const INF: i64 = i64::MAX;
+
+        /// 変数が `bounds` で指定される問題を作成する
+        pub fn new(bounds: Bounds<I>) -> Self {
+            Self {
+                bounds,
+                n_aux: 0,
+                edges: vec![],
+                source_edges: vec![],
+                sink_edges: vec![],
+                offset: 0,
+            }
+        }
+
+        fn add_aux(&mut self) -> usize {
+            self.n_aux += 1;
+            self.bounds.range_size() + self.n_aux - 1
+        }
+
+        fn cost_if_true_idx(&mut self, v: usize, cost: i64) {
+            if cost >= 0 {
+                self.source_edges.push((v, cost));
+            } else {
+                self.offset += cost;
+                self.sink_edges.push((v, -cost));
+            }
+        }
+
+        fn cost_if_false_idx(&mut self, v: usize, cost: i64) {
+            if cost >= 0 {
+                self.sink_edges.push((v, cost));
+            } else {
+                self.offset += cost;
+                self.source_edges.push((v, -cost));
+            }
+        }
+
+        /// `x_v = true` のときにコスト `cost` がかかる (`cost` は負でもよい)
+        pub fn cost_if_true(&mut self, v: I, cost: i64) {
+            self.cost_if_true_idx(self.bounds.to_index(v), cost);
+        }
+
+        /// `x_v = false` のときにコスト `cost` がかかる (`cost` は負でもよい)
+        pub fn cost_if_false(&mut self, v: I, cost: i64) {
+            self.cost_if_false_idx(self.bounds.to_index(v), cost);
+        }
+
+        /// `x_u = false` かつ `x_v = true` のときにコスト `cost` (非負) がかかる
+        pub fn cost_if_false_true(&mut self, u: I, v: I, cost: i64) {
+            assert!(cost >= 0);
+            self.edges
+                .push((self.bounds.to_index(u), self.bounds.to_index(v), cost));
+        }
+
+        /// `x_u = true` かつ `x_v = false` のときにコスト `cost` (非負) がかかる
+        pub fn cost_if_true_false(&mut self, u: I, v: I, cost: i64) {
+            self.cost_if_false_true(v, u, cost);
+        }
+
+        /// `x_u != x_v` のときにコスト `cost` (非負) がかかる
+        pub fn cost_if_different(&mut self, u: I, v: I, cost: i64) {
+            self.cost_if_false_true(u, v, cost);
+            self.cost_if_false_true(v, u, cost);
+        }
+
+        /// `x_u = true` ならば `x_v = true` でなければならない
+        pub fn implies(&mut self, u: I, v: I) {
+            self.cost_if_true_false(u, v, Self::INF);
+        }
+
+        /// `vs` の全ての変数が `true` のときに利得 `profit` (非負) を得る
+        pub fn profit_if_all_true(&mut self, vs: impl IntoIterator<Item = I>, profit: i64) {
+            assert!(profit >= 0);
+            // 補助変数 w: w = true ならば全ての v が true
+            let w = self.add_aux();
+            self.offset -= profit;
+            self.sink_edges.push((w, profit));
+            for v in vs {
+                self.edges.push((self.bounds.to_index(v), w, Self::INF));
+            }
+        }
+
+        /// `vs` の全ての変数が `false` のときに利得 `profit` (非負) を得る
+        pub fn profit_if_all_false(&mut self, vs: impl IntoIterator<Item = I>, profit: i64) {
+            assert!(profit >= 0);
+            // 補助変数 w: w = false ならば全ての v が false
+            let w = self.add_aux();
+            self.offset -= profit;
+            self.source_edges.push((w, profit));
+            for v in vs {
+                self.edges.push((w, self.bounds.to_index(v), Self::INF));
+            }
+        }
+
+        /// コストの総和の最小値と、そのときの各変数の割り当てを返す
+        ///
+        /// 全ての割り当てが実行不可能な場合は `None` を返す。
+        ///
+        /// # Panics
+        /// `INF` 以外のコストの総和と `INF` の個数によっては、容量が `i64` に収まらずパニックする。
+        ///
+        /// # 計算量
+        /// O(V^2 E) (V は変数と補助変数の数, E は制約の数)
+        pub fn solve(&self) -> Option<(i64, IxVec<I, bool>)> {
+            let nv = self.bounds.range_size();
+            let n = nv + self.n_aux;
+            let s = n;
+            let t = n + 1;
+
+            // INF の辺は、INF 以外の辺を全て切るよりも大きい容量に置き換える
+            let caps = || {
+                let edges = self.edges.iter().map(|&(_, _, c)| c);
+                let source = self.source_edges.iter().map(|&(_, c)| c);
+                let sink = self.sink_edges.iter().map(|&(_, c)| c);
+                edges.chain(source).chain(sink)
+            };
+            let n_inf = caps().filter(|&c| c == Self::INF).count() as i64;
+            let finite = caps()
+                .filter(|&c| c != Self::INF)
+                .try_fold(0_i64, |acc, c| acc.checked_add(c))
+                .expect("sum of costs overflows");
+            let inf = finite + 1;
+            inf.checked_mul(n_inf)
+                .and_then(|x| x.checked_add(finite))
+                .expect("sum of capacities overflows");
+            let cap = |c: i64| if c == Self::INF { inf } else { c };
+
+            let mut graph = MfGraph::<i64>::new(n + 2);
+            for &(u, v, c) in &self.edges {
+                graph.add_edge(u, v, cap(c));
+            }
+            for &(v, c) in &self.source_edges {
+                graph.add_edge(s, v, cap(c));
+            }
+            for &(v, c) in &self.sink_edges {
+                graph.add_edge(v, t, cap(c));
+            }
+            let cut = graph.flow(s, t);
+            if cut >= inf {
+                return None;
+            }
+            // s 側 (到達可能) の頂点が false
+            let reachable = graph.min_cut(s);
+            let assignment = (0..nv).map(|v| !reachable[v]).collect::<Vec<_>>();
+            Some((cut + self.offset, IxVec::from_vec(self.bounds, assignment)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::acl_flow_ix::*;
+    use crate::data_structure::ix::{Bounds, IxVec};
+    use crate::math::geometry::pos::pos::Pos;
+    use rand::prelude::*;
+
+    #[test]
+    fn test_mf_graph_ix_grid() {
+        // 2x2 のグリッドで (0, 0) から (1, 1) へ流す
+        let bounds = Bounds::new(Pos::new(0, 0), Pos::new(1, 1));
+        let mut g = MfGraphIx::new(bounds);
+        let e0 = g.add_edge(Pos::new(0, 0), Pos::new(1, 0), 3);
+        g.add_edge(Pos::new(0, 0), Pos::new(0, 1), 2);
+        g.add_edge(Pos::new(1, 0), Pos::new(1, 1), 1);
+        g.add_edge(Pos::new(0, 1), Pos::new(1, 1), 5);
+        assert_eq!(g.flow(Pos::new(0, 0), Pos::new(1, 1)), 3);
+        assert_eq!(
+            g.get_edge(e0),
+            FlowEdgeIx {
+                from: Pos::new(0, 0),
+                to: Pos::new(1, 0),
+                cap: 3,
+                flow: 1
+            }
+        );
+
+        let cut = g.min_cut(Pos::new(0, 0));
+        assert!(cut[Pos::new(0, 0)]);
+        assert!(cut[Pos::new(1, 0)]);
+        assert!(!cut[Pos::new(0, 1)]);
+        assert!(!cut[Pos::new(1, 1)]);
+    }
+
+    #[test]
+    fn test_mf_graph_ix_split_vertex() {
+        // 頂点容量 1 の頂点 0..3 を (v, false) -> (v, true) に分割する
+        // 0 -> 1 -> 3, 0 -> 2 -> 3
+        let bounds = Bounds::new((0_usize, false), (3, true));
+        let mut g = MfGraphIx::new(bounds);
+        for v in 0..4 {
+            g.add_edge((v, false), (v, true), 1);
+        }
+        for (u, v) in [(0, 1), (1, 3), (0, 2), (2, 3)] {
+            g.add_edge((u, true), (v, false), 10);
+        }
+        assert_eq!(g.flow((0, true), (3, false)), 2);
+    }
+
+    #[test]
+    fn test_mcf_graph_ix() {
+        let bounds = Bounds::new('a', 'd');
+        let mut g = McfGraphIx::new(bounds);
+        g.add_edge('a', 'b', 1, 1);
+        g.add_edge('a', 'c', 2, 3);
+        g.add_edge('b', 'd', 2, 1);
+        g.add_edge('c', 'd', 1, 1);
+        assert_eq!(g.flow('a', 'd', 10), (2, 2 + 4));
+        let edges = g.edges();
+        assert_eq!(edges[1].from, 'a');
+        assert_eq!(edges[1].flow, 1);
+    }
+
+    #[test]
+    fn test_lower_bounded_max_flow() {
+        let bounds = Bounds::new(0_usize, 3);
+        let mut g = LowerBoundedMfGraphIx::new(bounds);
+        let e0 = g.add_edge(0, 1, 0, 3);
+        g.add_edge(0, 2, 0, 3);
+        let e2 = g.add_edge(1, 3, 2, 2);
+        g.add_edge(2, 3, 1, 4);
+        assert_eq!(g.max_flow(0, 3), Some(5));
+        assert_eq!(g.edge_flow(e0), 2);
+        assert_eq!(g.edge_flow(e2), 2);
+
+        // 下限を満たせない
+        let mut g = LowerBoundedMfGraphIx::new(bounds);
+        g.add_edge(0, 1, 0, 1);
+        g.add_edge(1, 3, 2, 3);
+        assert_eq!(g.max_flow(0, 3), None);
+
+        // 循環流
+        let mut g = LowerBoundedMfGraphIx::new(bounds);
+        g.add_edge(0, 1, 1, 2);
+        g.add_edge(1, 2, 0, 2);
+        g.add_edge(2, 0, 2, 3);
+        assert!(g.circulation());
+        g.add_edge(1, 3, 1, 1);
+        assert!(!g.circulation());
+    }
+
+    #[test]
+    fn test_project_selection() {
+        // 3 つのプロジェクトのどれを採用するか (true: 採用)
+        // - プロジェクト 0, 1, 2 の利益は 5, -3, -2
+        // - 0 を採用するには 1 が必要
+        // - 1 と 2 を両方採用すると 4 のボーナス
+        let bounds = Bounds::new(0_usize, 2);
+        let mut ps = ProjectSelectionIx::new(bounds);
+        ps.cost_if_true(0, -5);
+        ps.cost_if_true(1, 3);
+        ps.cost_if_true(2, 2);
+        ps.implies(0, 1);
+        ps.profit_if_all_true([1, 2], 4);
+        let (cost, assignment) = ps.solve().unwrap();
+        assert_eq!(cost, -5 + 3 + 2 - 4);
+        assert_eq!(assignment.into_vec(), vec![true, true, true]);
+    }
+
+    #[test]
+    fn test_project_selection_hard_constraints() {
+        // 矛盾する INF 制約が多数あっても、オーバーフローせずに実行不可能と判定する
+        let n = 100;
+        let bounds = Bounds::new(0_usize, n - 1);
+        let mut ps = ProjectSelectionIx::new(bounds);
+        for v in 0..n {
+            ps.cost_if_true(v, ProjectSelectionIx::<usize>::INF);
+            ps.cost_if_false(v, ProjectSelectionIx::<usize>::INF);
+            ps.implies(v, (v + 1) % n);
+        }
+        assert!(ps.solve().is_none());
+
+        // 全て true が強制される: 多数の INF 辺があっても正しいコストを返す
+        let mut ps = ProjectSelectionIx::new(bounds);
+        ps.cost_if_false(0, ProjectSelectionIx::<usize>::INF);
+        for v in 0..n {
+            ps.cost_if_true(v, 1_000_000_000);
+            ps.implies(v, (v + 1) % n);
+            ps.profit_if_all_false([v, (v + 1) % n], 1_000_000_000);
+        }
+        let (cost, assignment) = ps.solve().unwrap();
+        assert_eq!(cost, 100 * 1_000_000_000);
+        assert_eq!(assignment.into_vec(), vec![true; n]);
+    }
+
+    type CostFn = Box<dyn Fn(&[bool]) -> i64>;
+
+    #[test]
+    #[ignore]
+    fn test_project_selection_random() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let n = rng.random_range(1..=6);
+            let bounds = Bounds::new(0_usize, n - 1);
+            let mut ps = ProjectSelectionIx::new(bounds);
+            // 全探索用にコスト関数を保持する
+            let mut costs: Vec<CostFn> = vec![];
+            for _ in 0..rng.random_range(0..10) {
+                let u = rng.random_range(0..n);
+                let v = rng.random_range(0..n);
+                let c = rng.random_range(-10..=10);
+                let p = rng.random_range(0..=10);
+                match rng.random_range(0..6) {
+                    0 => {
+                        ps.cost_if_true(u, c);
+                        costs.push(Box::new(move |x| if x[u] { c } else { 0 }));
+                    }
+                    1 => {
+                        ps.cost_if_false(u, c);
+                        costs.push(Box::new(move |x| if !x[u] { c } else { 0 }));
+                    }
+                    2 => {
+                        ps.cost_if_false_true(u, v, p);
+                        costs.push(Box::new(move |x| if !x[u] && x[v] { p } else { 0 }));
+                    }
+                    3 => {
+                        ps.cost_if_different(u, v, p);
+                        costs.push(Box::new(move |x| if x[u] != x[v] { p } else { 0 }));
+                    }
+                    4 => {
+                        ps.profit_if_all_true([u, v], p);
+                        costs.push(Box::new(move |x| if x[u] && x[v] { -p } else { 0 }));
+                    }
+                    _ => {
+                        ps.profit_if_all_false([u, v], p);
+                        costs.push(Box::new(move |x| if !x[u] && !x[v] { -p } else { 0 }));
+                    }
+                }
+            }
+            let total = |x: &[bool]| costs.iter().map(|f| f(x)).sum::<i64>();
+            let naive = (0..1_usize << n)
+                .map(|bits| total(&(0..n).map(|i| bits >> i & 1 == 1).collect::<Vec<_>>()))
+                .min()
+                .unwrap();
+            let (cost, assignment) = ps.solve().unwrap();
+            assert_eq!(cost, naive);
+            assert_eq!(total(assignment.as_vec()), naive);
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn test_lower_bounded_max_flow_random() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let n = rng.random_range(2..=4);
+            let m = rng.random_range(0..=4);
+            let edges = (0..m)
+                .map(|_| {
+                    let u = rng.random_range(0..n);
+                    let v = rng.random_range(0..n);
+                    let lower = rng.random_range(0..=2);
+                    let upper = lower + rng.random_range(0..=2);
+                    (u, v, lower, upper)
+                })
+                .collect::<Vec<_>>();
+            let bounds = Bounds::new(0_usize, n - 1);
+            let mut g = LowerBoundedMfGraphIx::new(bounds);
+            for &(u, v, l, r) in &edges {
+                g.add_edge(u, v, l, r);
+            }
+            let res = g.max_flow(0, n - 1);
+
+            // 全探索: 各辺の流量を下限から上限まで列挙し、流量保存を満たすものの中で t への流量の最大値を求める
+            let mut naive = None;
+            let mut flows = edges.iter().map(|e| e.2).collect::<Vec<_>>();
+            loop {
+                let mut balance = IxVec::new(bounds, 0_i64);
+                for (&(u, v, _, _), &f) in edges.iter().zip(&flows) {
+                    balance[u] -= f;
+                    balance[v] += f;
+                }
+                if (1..n - 1).all(|v| balance[v] == 0) {
+                    naive = naive.max(Some(balance[n - 1]));
+                }
+                let mut i = 0;
+                while i < m && flows[i] == edges[i].3 {
+                    flows[i] = edges[i].2;
+                    i += 1;
+                }
+                if i == m {
+                    break;
+                }
+                flows[i] += 1;
+            }
+            assert_eq!(res, naive);
+
+            if let Some(total) = res {
+                let mut balance = vec![0_i64; n];
+                for (i, &(u, v, l, r)) in edges.iter().enumerate() {
+                    let f = g.edge_flow(i);
+                    assert!(l <= f && f <= r);
+                    balance[u] -= f;
+                    balance[v] += f;
+                }
+                assert!((1..n - 1).all(|v| balance[v] == 0));
+                assert_eq!(balance[n - 1], total);
+            }
+        }
+    }
+}
@@ -5,6 +5,7 @@ use cargo_snippet::snippet;
 #[snippet(prefix = "use acl_scc_ix::*;")]
 pub mod acl_scc_ix {
     use super::{Bounds, Ix, IxVec};
+    use ac_library::Monoid;
 
     /// 強連結成分分解 (SCC) を行い、縮約グラフ (DAG) を構築するための構造体 (Ix版)
     #[derive(Clone, Debug)]
@@ -152,6 +153,122 @@ pub mod acl_scc_ix {
             }
             adj
         }
+
+        /// 縮約グラフ上でトポロジカル順に DP を行う
+        ///
+        /// 各グループ `g` について、`g` に入る辺を持つグループの値を `M` で集約した `acc` を求め、
+        /// `dp[g] = f(&acc, &groups[g])` とする (入る辺がなければ `acc` は単位元)。
+        ///
+        /// # Returns
+        /// グループ番号をインデックスとする DP の値
+        ///
+        /// # 計算量
+        /// O(V + E log E)
+        ///
+        /// # Examples
+        /// ```ignore
+        /// // 通れる頂点数の最大値 (どこかから出発して、その成分で終わる)
+        /// let dp = cg.fold_topological::<Max<usize>, _>(|acc, group| acc + group.len());
+        /// ```
+        pub fn fold_topological<M, F>(&self, mut f: F) -> Vec<M::S>
+        where
+            M: Monoid,
+            F: FnMut(&M::S, &[I]) -> M::S,
+        {
+            let n = self.group_count();
+            let mut acc = vec![M::identity(); n];
+            let mut dp = Vec::with_capacity(n);
+            let adj = self.condensation_adj();
+            for g in 0..n {
+                let val = f(&acc[g], &self.groups[g]);
+                for &next in &adj[g] {
+                    acc[next] = M::binary_operation(&acc[next], &val);
+                }
+                dp.push(val);
+            }
+            dp
+        }
+
+        /// 縮約グラフ上でトポロジカル順の逆順に DP を行う
+        ///
+        /// 各グループ `g` について、`g` から出る辺の先のグループの値を `M` で集約した `acc` を求め、
+        /// `dp[g] = f(&acc, &groups[g])` とする (出る辺がなければ `acc` は単位元)。
+        ///
+        /// # Returns
+        /// グループ番号をインデックスとする DP の値
+        ///
+        /// # 計算量
+        /// O(V + E log E)
+        pub fn fold_reverse_topological<M, F>(&self, mut f: F) -> Vec<M::S>
+        where
+            M: Monoid,
+            F: FnMut(&M::S, &[I]) -> M::S,
+        {
+            let n = self.group_count();
+            let adj = self.condensation_adj();
+            let mut dp = vec![M::identity(); n];
+            for g in (0..n).rev() {
+                let acc = adj[g].iter().fold(M::identity(), |acc, &next| {
+                    M::binary_operation(&acc, &dp[next])
+                });
+                dp[g] = f(&acc, &self.groups[g]);
+            }
+            dp
+        }
+
+        /// 各頂点から到達可能な頂点数 (自分自身を含む) を求める
+        ///
+        /// 頂点を 64 個ずつのブロックに分け、各ブロックについて「各グループから到達可能なブロック内の頂点」を
+        /// `u64` のビット集合で縮約グラフ上を逆トポロジカル順に伝播させる。
+        ///
+        /// # 計算量
+        /// O(V (V + E) / 64 + E log E)。メモリは O(V + E)。V = 5 * 10^4 程度まで現実的に動く。
+        pub fn reachable_counts(&self) -> IxVec<I, usize> {
+            let nv = self.bounds.range_size();
+            let n = self.group_count();
+            let adj = self.condensation_adj();
+            let mapping = self.mapping();
+            let mapping = (0..nv)
+                .map(|i| mapping[self.bounds.from_index(i)])
+                .collect::<Vec<_>>();
+
+            let mut counts = vec![0; n];
+            let mut bits = vec![0_u64; n];
+            for block_start in (0..nv).step_by(64) {
+                bits.fill(0);
+                for i in block_start..nv.min(block_start + 64) {
+                    bits[mapping[i]] |= 1 << (i - block_start);
+                }
+                for g in (0..n).rev() {
+                    let reach = adj[g].iter().fold(bits[g], |acc, &next| acc | bits[next]);
+                    bits[g] = reach;
+                    counts[g] += reach.count_ones() as usize;
+                }
+            }
+            IxVec::from_vec(self.bounds, mapping.iter().map(|&g| counts[g]).collect())
+        }
+
+        /// グラフ全体を強連結にするために追加する必要がある辺の本数の最小値を求める
+        ///
+        /// 縮約グラフの入次数 0 の頂点数と出次数 0 の頂点数の大きい方 (すでに強連結なら 0)。
+        ///
+        /// # 計算量
+        /// O(V + E log E)
+        pub fn min_edges_to_strongly_connect(&self) -> usize {
+            let n = self.group_count();
+            if n <= 1 {
+                return 0;
+            }
+            let mut has_in = vec![false; n];
+            let mut has_out = vec![false; n];
+            for (u, v) in self.condensation_edges() {
+                has_out[u] = true;
+                has_in[v] = true;
+            }
+            let sources = has_in.iter().filter(|&&b| !b).count();
+            let sinks = has_out.iter().filter(|&&b| !b).count();
+            sources.max(sinks)
+        }
     }
 }
 
@@ -210,4 +327,124 @@ mod tests {
         assert!(adj[g_u].contains(&g_v));
         assert!(adj[g_isolated].is_empty());
     }
+
+    #[test]
+    fn test_scc_ix_dp() {
+        use ac_library::{Additive, Max};
+
+        // 'a' <-> 'b' -> 'c' -> 'd', 'b' -> 'd', 'e' -> 'd'
+        let bounds = Bounds::new('a', 'e');
+        let mut scc_graph = SccGraphIxWrapper::new(bounds);
+        for (u, v) in [
+            ('a', 'b'),
+            ('b', 'a'),
+            ('b', 'c'),
+            ('c', 'd'),
+            ('b', 'd'),
+            ('e', 'd'),
+        ] {
+            scc_graph.add_edge(u, v);
+        }
+        let cg = scc_graph.scc();
+        let mapping = cg.mapping();
+
+        // その成分で終わるパスで通れる頂点数の最大値
+        let dp = cg.fold_topological::<Max<usize>, _>(|acc, group| acc + group.len());
+        assert_eq!(dp[mapping['d']], 4);
+        assert_eq!(dp[mapping['e']], 1);
+
+        // その成分から始まるパスの本数 (長さ 0 を含む)
+        let dp = cg.fold_reverse_topological::<Additive<usize>, _>(|acc, _| acc + 1);
+        assert_eq!(dp[mapping['a']], 4);
+        assert_eq!(dp[mapping['d']], 1);
+
+        let counts = cg.reachable_counts();
+        assert_eq!(counts['a'], 4);
+        assert_eq!(counts['b'], 4);
+        assert_eq!(counts['c'], 2);
+        assert_eq!(counts['d'], 1);
+        assert_eq!(counts['e'], 2);
+
+        // 始点 {a, b}, {e} と終点 {d}
+        assert_eq!(cg.min_edges_to_strongly_connect(), 2);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_scc_ix_reachable_counts_random() {
+        use rand::prelude::*;
+
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let nv = rng.random_range(1..=150);
+            let ne = rng.random_range(0..=2 * nv);
+            let bounds = Bounds::new(0, nv - 1);
+            let mut scc_graph = SccGraphIxWrapper::new(bounds);
+            let mut adj = vec![vec![]; nv];
+            for _ in 0..ne {
+                let u = rng.random_range(0..nv);
+                let v = rng.random_range(0..nv);
+                scc_graph.add_edge(u, v);
+                adj[u].push(v);
+            }
+            let cg = scc_graph.scc();
+            let counts = cg.reachable_counts();
+
+            let mut reach = vec![vec![false; nv]; nv];
+            for s in 0..nv {
+                let mut stack = vec![s];
+                reach[s][s] = true;
+                while let Some(u) = stack.pop() {
+                    for &v in &adj[u] {
+                        if !reach[s][v] {
+                            reach[s][v] = true;
+                            stack.push(v);
+                        }
+                    }
+                }
+                assert_eq!(counts[s], reach[s].iter().filter(|&&b| b).count());
+            }
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn test_scc_ix_min_edges_random() {
+        use itertools::Itertools;
+        use rand::prelude::*;
+
+        fn is_strongly_connected(nv: usize, edges: &[(usize, usize)]) -> bool {
+            let mut scc_graph = SccGraphIxWrapper::new(Bounds::new(0, nv - 1));
+            for &(u, v) in edges {
+                scc_graph.add_edge(u, v);
+            }
+            scc_graph.scc().group_count() == 1
+        }
+
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let nv = rng.random_range(1..=4);
+            let ne = rng.random_range(0..=nv + 1);
+            let edges = (0..ne)
+                .map(|_| (rng.random_range(0..nv), rng.random_range(0..nv)))
+                .collect::<Vec<_>>();
+            let mut scc_graph = SccGraphIxWrapper::new(Bounds::new(0, nv - 1));
+            for &(u, v) in &edges {
+                scc_graph.add_edge(u, v);
+            }
+
+            // 追加する辺の集合を全探索する
+            let all_pairs = (0..nv).cartesian_product(0..nv).collect::<Vec<_>>();
+            let expected = (0..=nv)
+                .find(|&k| {
+                    all_pairs.iter().combinations(k).any(|added| {
+                        let mut es = edges.clone();
+                        es.extend(added.into_iter().copied());
+                        is_strongly_connected(nv, &es)
+                    })
+                })
+                .unwrap();
+            assert_eq!(scc_graph.scc().min_edges_to_strongly_connect(), expected);
+        }
+    }
 }
@@ -0,0 +1,395 @@
+use cargo_snippet::snippet;
+
+use crate::graph::dijkstra::dijkstra::dijkstra_with_restore;
+use crate::math::ext_int::mod_ext_int::{ExtInt, INF, fin};
+
+#[allow(clippy::module_inception)]
+#[snippet(prefix = "use apsp::*;", include = "mod_ext_int, dijkstra")]
+pub mod apsp {
+    use super::dijkstra_with_restore;
+    use super::{ExtInt, INF, fin};
+
+    /// 負閉路を経由する場合に値がオーバーフローしないようにするための下限
+    const LOWER_LIMIT: i64 = -(1 << 61);
+
+    /// 全点対最短路
+    ///
+    /// 負の辺があってもよい。負閉路を経由していくらでも短くできる頂点対は `neg_inf` で判定できる。
+    #[derive(Clone, Debug)]
+    pub struct Apsp {
+        /// `dist[u][v]`: `u` から `v` への最短距離。到達不可能な場合は `INF`。
+        /// `neg_inf[u][v]` が `true` の場合は意味を持たない。
+        pub dist: Vec<Vec<ExtInt>>,
+        /// `neg_inf[u][v]`: `u` から `v` へのパスで負閉路を経由できる (最短距離が -∞) か
+        pub neg_inf: Vec<Vec<bool>>,
+        /// `next[u][v]`: `u` から `v` への最短路で `u` の次に通る頂点 (経路復元用)
+        next: Vec<Vec<usize>>,
+    }
+
+    impl Apsp {
+        fn empty(nv: usize) -> Self {
+            let mut dist = vec![vec![INF; nv]; nv];
+            let mut next = vec![vec![usize::MAX; nv]; nv];
+            for v in 0..nv {
+                dist[v][v] = fin(0);
+                next[v][v] = v;
+            }
+            Self {
+                dist,
+                neg_inf: vec![vec![false; nv]; nv],
+                next,
+            }
+        }
+
+        /// ワーシャルフロイド法で全点対最短路を求める
+        ///
+        /// # Arguments
+        /// * `nv` - 頂点数
+        /// * `edges` - 有向辺 `(from, to, cost)` のリスト。負のコストや多重辺、自己ループがあってもよい。
+        ///
+        /// # 計算量
+        /// O(V^3)
+        pub fn warshall_floyd(nv: usize, edges: &[(usize, usize, i64)]) -> Self {
+            let mut res = Self::empty(nv);
+            let Self { dist, next, .. } = &mut res;
+            for &(u, v, cost) in edges {
+                if fin(cost) < dist[u][v] {
+                    dist[u][v] = fin(cost);
+                    next[u][v] = v;
+                }
+            }
+            for k in 0..nv {
+                for i in 0..nv {
+                    if dist[i][k].is_inf() {
+                        continue;
+                    }
+                    for j in 0..nv {
+                        let d = dist[i][k] + dist[k][j];
+                        if d < dist[i][j] {
+                            dist[i][j] = d.max(fin(LOWER_LIMIT));
+                            next[i][j] = next[i][k];
+                        }
+                    }
+                }
+            }
+            res.compute_neg_inf();
+            res
+        }
+
+        /// Johnson のアルゴリズムで全点対最短路を求める。辺の少ないグラフで負の辺がある場合に使う。
+        ///
+        /// ポテンシャルで辺のコストを非負にしてから各頂点を始点にダイクストラ法を行う。
+        /// 負閉路がある場合は `warshall_floyd` で計算する。
+        ///
+        /// # Arguments
+        /// * `nv` - 頂点数
+        /// * `edges` - 有向辺 `(from, to, cost)` のリスト。負のコストや多重辺、自己ループがあってもよい。
+        ///
+        /// # 計算量
+        /// O(VE log V) (負閉路がある場合は O(V^3))
+        pub fn johnson(nv: usize, edges: &[(usize, usize, i64)]) -> Self {
+            // 全頂点に距離 0 の辺を張った仮想的な始点からの最短距離をポテンシャルとする
+            let mut potential = vec![0_i64; nv];
+            for iter in 0..=nv {
+                let mut updated = false;
+                for &(u, v, cost) in edges {
+                    if potential[u] + cost < potential[v] {
+                        potential[v] = potential[u] + cost;
+                        updated = true;
+                    }
+                }
+                if !updated {
+                    break;
+                }
+                if iter == nv {
+                    // 負閉路がある
+                    return Self::warshall_floyd(nv, edges);
+                }
+            }
+
+            let mut adj = vec![vec![]; nv];
+            for &(u, v, cost) in edges {
+                adj[u].push((v, cost + potential[u] - potential[v]));
+            }
+
+            let mut res = Self::empty(nv);
+            for s in 0..nv {
+                let dijkstra_res = dijkstra_with_restore(nv, |u| adj[u].iter().copied(), [s]);
+                for t in 0..nv {
+                    if let Some(d) = dijkstra_res.dist[t] {
+                        res.dist[s][t] = fin(d - potential[s] + potential[t]);
+                    }
+                }
+                // next[s][t] を最短路木の親をたどって求める
+                let next = &mut res.next[s];
+                for t in 0..nv {
+                    if dijkstra_res.dist[t].is_none() {
+                        continue;
+                    }
+                    let mut path = vec![];
+                    let mut x = t;
+                    let first = loop {
+                        if next[x] != usize::MAX {
+                            break next[x];
+                        }
+                        path.push(x);
+                        let p = dijkstra_res.prev[x].unwrap();
+                        if p == s {
+                            break x;
+                        }
+                        x = p;
+                    };
+                    for &v in &path {
+                        next[v] = first;
+                    }
+                }
+            }
+            res
+        }
+
+        /// `dist` から `neg_inf` を計算する
+        fn compute_neg_inf(&mut self) {
+            let nv = self.dist.len();
+            for k in 0..nv {
+                if self.dist[k][k] >= fin(0) {
+                    continue;
+                }
+                // k は負閉路上にある
+                for i in 0..nv {
+                    if self.dist[i][k].is_inf() {
+                        continue;
+                    }
+                    for j in 0..nv {
+                        if self.dist[k][j].is_fin() {
+                            self.neg_inf[i][j] = true;
+                        }
+                    }
+                }
+            }
+        }
+
+        /// 負閉路が存在するかどうか
+        ///
+        /// # 計算量
+        /// O(V)
+        pub fn has_negative_cycle(&self) -> bool {
+            (0..self.dist.len()).any(|v| self.neg_inf[v][v])
+        }
+
+        /// `u` から `v` への最短距離を返す
+        ///
+        /// # Returns
+        /// 最短距離。到達不可能な場合や、最短距離が -∞ の場合は `None`。
+        ///
+        /// # 計算量
+        /// O(1)
+        pub fn get(&self, u: usize, v: usize) -> Option<i64> {
+            if self.neg_inf[u][v] {
+                None
+            } else {
+                self.dist[u][v].to_option()
+            }
+        }
+
+        /// 辺 `(u, v, cost)` を追加して最短距離を更新する
+        ///
+        /// # 計算量
+        /// O(V^2)
+        pub fn add_edge(&mut self, u: usize, v: usize, cost: i64) {
+            let nv = self.dist.len();
+            let reach = |s: &Self, a: usize, b: usize| s.dist[a][b].is_fin() || s.neg_inf[a][b];
+            // 追加した辺を通る閉路で負閉路ができるか
+            let creates_neg_cycle =
+                reach(self, v, u) && (self.neg_inf[v][u] || self.dist[v][u] + cost < fin(0));
+
+            let mut new_neg_inf = vec![];
+            for i in 0..nv {
+                if !reach(self, i, u) {
+                    continue;
+                }
+                for j in 0..nv {
+                    if !reach(self, v, j) {
+                        continue;
+                    }
+                    if creates_neg_cycle || self.neg_inf[i][u] || self.neg_inf[v][j] {
+                        new_neg_inf.push((i, j));
+                    }
+                    let d = self.dist[i][u] + cost + self.dist[v][j];
+                    if d < self.dist[i][j] {
+                        self.dist[i][j] = d.max(fin(LOWER_LIMIT));
+                        self.next[i][j] = if i == u { v } else { self.next[i][u] };
+                    }
+                }
+            }
+            for (i, j) in new_neg_inf {
+                self.neg_inf[i][j] = true;
+            }
+        }
+
+        /// `u` から `v` への最短路を復元する
+        ///
+        /// # Returns
+        /// `u` から `v` までの頂点列。到達不可能な場合や、最短距離が -∞ の場合は `None`。
+        ///
+        /// # 計算量
+        /// O(経路の長さ)
+        pub fn restore(&self, u: usize, v: usize) -> Option<Vec<usize>> {
+            self.get(u, v)?;
+            let mut path = vec![u];
+            let mut cur = u;
+            while cur != v {
+                cur = self.next[cur][v];
+                path.push(cur);
+            }
+            Some(path)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::apsp::*;
+
+    #[test]
+    fn test_warshall_floyd() {
+        // 0 -(1)-> 1 -(2)-> 2
+        // 0 -(5)-> 2
+        // 2 -(-1)-> 3
+        let edges = [(0, 1, 1), (1, 2, 2), (0, 2, 5), (2, 3, -1)];
+        for apsp in [Apsp::warshall_floyd(5, &edges), Apsp::johnson(5, &edges)] {
+            assert_eq!(apsp.get(0, 2), Some(3));
+            assert_eq!(apsp.get(0, 3), Some(2));
+            assert_eq!(apsp.get(1, 3), Some(1));
+            assert_eq!(apsp.get(3, 0), None);
+            assert_eq!(apsp.get(4, 4), Some(0));
+            assert!(!apsp.has_negative_cycle());
+            assert_eq!(apsp.restore(0, 3), Some(vec![0, 1, 2, 3]));
+            assert_eq!(apsp.restore(2, 2), Some(vec![2]));
+            assert_eq!(apsp.restore(3, 0), None);
+        }
+    }
+
+    #[test]
+    fn test_negative_cycle() {
+        // 0 -> 1 <-> 2 (負閉路) -> 3, 4 -> 0
+        let edges = [(0, 1, 1), (1, 2, -3), (2, 1, 1), (2, 3, 1), (4, 0, 1)];
+        for apsp in [Apsp::warshall_floyd(5, &edges), Apsp::johnson(5, &edges)] {
+            assert!(apsp.has_negative_cycle());
+            assert!(apsp.neg_inf[0][3]);
+            assert!(apsp.neg_inf[4][1]);
+            assert!(!apsp.neg_inf[4][0]);
+            assert!(!apsp.neg_inf[3][3]);
+            assert_eq!(apsp.get(4, 0), Some(1));
+            assert_eq!(apsp.get(0, 3), None);
+            assert_eq!(apsp.restore(0, 3), None);
+        }
+    }
+
+    #[test]
+    fn test_add_edge() {
+        let mut apsp = Apsp::warshall_floyd(4, &[(0, 1, 5), (1, 2, 5)]);
+        assert_eq!(apsp.get(0, 2), Some(10));
+        apsp.add_edge(0, 2, 3);
+        assert_eq!(apsp.get(0, 2), Some(3));
+        apsp.add_edge(2, 3, 1);
+        assert_eq!(apsp.get(0, 3), Some(4));
+        assert_eq!(apsp.restore(0, 3), Some(vec![0, 2, 3]));
+        apsp.add_edge(3, 0, -5);
+        assert!(apsp.has_negative_cycle());
+        assert!(apsp.neg_inf[1][1]);
+        assert_eq!(apsp.get(1, 0), None);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_apsp_random() {
+        use rand::prelude::*;
+
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..300 {
+            let nv = rng.random_range(1..=7);
+            let ne = rng.random_range(0..=15);
+            let edges = (0..ne)
+                .map(|_| {
+                    (
+                        rng.random_range(0..nv),
+                        rng.random_range(0..nv),
+                        rng.random_range(-3..=10),
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            // ベルマンフォード法で各始点からの最短距離を求める (None: 到達不可能, Some(None): -∞)
+            let naive = (0..nv)
+                .map(|s| {
+                    let mut dist = vec![None; nv];
+                    dist[s] = Some(0_i64);
+                    for _ in 0..nv {
+                        for &(u, v, c) in &edges {
+                            if let Some(du) = dist[u] {
+                                if dist[v].is_none_or(|dv| du + c < dv) {
+                                    dist[v] = Some(du + c);
+                                }
+                            }
+                        }
+                    }
+                    let mut neg_inf = vec![false; nv];
+                    for _ in 0..nv {
+                        for &(u, v, c) in &edges {
+                            if let Some(du) = dist[u] {
+                                if neg_inf[u] || dist[v].is_none_or(|dv| du + c < dv) {
+                                    neg_inf[v] = true;
+                                }
+                            }
+                        }
+                    }
+                    (0..nv)
+                        .map(|v| dist[v].map(|d| (!neg_inf[v]).then_some(d)))
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+
+            let check = |apsp: &Apsp| {
+                for u in 0..nv {
+                    for v in 0..nv {
+                        match naive[u][v] {
+                            None => {
+                                assert!(apsp.dist[u][v].is_inf() && !apsp.neg_inf[u][v]);
+                                assert_eq!(apsp.restore(u, v), None);
+                            }
+                            Some(None) => assert!(apsp.neg_inf[u][v]),
+                            Some(Some(d)) => {
+                                assert_eq!(apsp.get(u, v), Some(d));
+                                let path = apsp.restore(u, v).unwrap();
+                                assert_eq!(path.first(), Some(&u));
+                                assert_eq!(path.last(), Some(&v));
+                                let len = path
+                                    .windows(2)
+                                    .map(|w| {
+                                        edges
+                                            .iter()
+                                            .filter(|e| (e.0, e.1) == (w[0], w[1]))
+                                            .map(|e| e.2)
+                                            .min()
+                                            .unwrap()
+                                    })
+                                    .sum::<i64>();
+                                assert_eq!(len, d);
+                            }
+                        }
+                    }
+                }
+            };
+
+            check(&Apsp::warshall_floyd(nv, &edges));
+            check(&Apsp::johnson(nv, &edges));
+
+            // 辺を 1 本ずつ追加する
+            let mut apsp = Apsp::warshall_floyd(nv, &[]);
+            for &(u, v, c) in &edges {
+                apsp.add_edge(u, v, c);
+            }
+            check(&apsp);
+        }
+    }
+}
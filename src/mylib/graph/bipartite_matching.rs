@@ -0,0 +1,269 @@
+use cargo_snippet::snippet;
+
+#[allow(clippy::module_inception)]
+#[snippet(prefix = "use bipartite_matching::*;")]
+pub mod bipartite_matching {
+    use std::collections::VecDeque;
+
+    /// Hopcroft-Karp 法による二部グラフの最大マッチング
+    ///
+    /// 左側の頂点 `0..n_left` と右側の頂点 `0..n_right` からなる二部グラフを扱う。
+    #[derive(Clone, Debug)]
+    pub struct BipartiteMatching {
+        n_left: usize,
+        n_right: usize,
+        adj: Vec<Vec<usize>>,
+        /// `match_left[l]`: 左側の頂点 `l` とマッチしている右側の頂点
+        pub match_left: Vec<Option<usize>>,
+        /// `match_right[r]`: 右側の頂点 `r` とマッチしている左側の頂点
+        pub match_right: Vec<Option<usize>>,
+    }
+
+    impl BipartiteMatching {
+        /// 最大マッチングを求める
+        ///
+        /// # Arguments
+        /// * `n_left` - 左側の頂点数
+        /// * `n_right` - 右側の頂点数
+        /// * `edges` - 辺 `(l, r)` のリスト (`l` は左側の頂点、`r` は右側の頂点)
+        ///
+        /// # 計算量
+        /// O(E √V)
+        pub fn new(n_left: usize, n_right: usize, edges: &[(usize, usize)]) -> Self {
+            let mut adj = vec![vec![]; n_left];
+            for &(l, r) in edges {
+                adj[l].push(r);
+            }
+            let mut res = Self {
+                n_left,
+                n_right,
+                adj,
+                match_left: vec![None; n_left],
+                match_right: vec![None; n_right],
+            };
+            res.solve();
+            res
+        }
+
+        fn solve(&mut self) {
+            loop {
+                // 未マッチの左側の頂点からの交互路の長さ
+                let mut dist = vec![usize::MAX; self.n_left];
+                let mut queue = VecDeque::new();
+                for l in 0..self.n_left {
+                    if self.match_left[l].is_none() {
+                        dist[l] = 0;
+                        queue.push_back(l);
+                    }
+                }
+                let mut found = false;
+                while let Some(l) = queue.pop_front() {
+                    for &r in &self.adj[l] {
+                        match self.match_right[r] {
+                            None => found = true,
+                            Some(l2) if dist[l2] == usize::MAX => {
+                                dist[l2] = dist[l] + 1;
+                                queue.push_back(l2);
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                if !found {
+                    break;
+                }
+
+                let mut iter = vec![0; self.n_left];
+                for l in 0..self.n_left {
+                    if self.match_left[l].is_none() {
+                        self.dfs(l, &mut dist, &mut iter);
+                    }
+                }
+            }
+        }
+
+        fn dfs(&mut self, l: usize, dist: &mut [usize], iter: &mut [usize]) -> bool {
+            while iter[l] < self.adj[l].len() {
+                let r = self.adj[l][iter[l]];
+                iter[l] += 1;
+                let ok = match self.match_right[r] {
+                    None => true,
+                    Some(l2) => dist[l2] == dist[l] + 1 && self.dfs(l2, dist, iter),
+                };
+                if ok {
+                    self.match_left[l] = Some(r);
+                    self.match_right[r] = Some(l);
+                    return true;
+                }
+            }
+            dist[l] = usize::MAX;
+            false
+        }
+
+        /// 最大マッチングのサイズ
+        ///
+        /// # 計算量
+        /// O(V)
+        pub fn size(&self) -> usize {
+            self.match_left.iter().filter(|m| m.is_some()).count()
+        }
+
+        /// 最大マッチングに含まれる辺 `(l, r)` のリスト (`l` の昇順)
+        ///
+        /// # 計算量
+        /// O(V)
+        pub fn pairs(&self) -> Vec<(usize, usize)> {
+            (0..self.n_left)
+                .filter_map(|l| self.match_left[l].map(|r| (l, r)))
+                .collect()
+        }
+
+        /// 未マッチの左側の頂点から交互路で到達できる頂点 (左側, 右側)
+        fn alternating_reachable(&self) -> (Vec<bool>, Vec<bool>) {
+            let mut visited_left = vec![false; self.n_left];
+            let mut visited_right = vec![false; self.n_right];
+            let mut stack = (0..self.n_left)
+                .filter(|&l| self.match_left[l].is_none())
+                .collect::<Vec<_>>();
+            for &l in &stack {
+                visited_left[l] = true;
+            }
+            while let Some(l) = stack.pop() {
+                for &r in &self.adj[l] {
+                    if visited_right[r] {
+                        continue;
+                    }
+                    visited_right[r] = true;
+                    if let Some(l2) = self.match_right[r] {
+                        if !visited_left[l2] {
+                            visited_left[l2] = true;
+                            stack.push(l2);
+                        }
+                    }
+                }
+            }
+            (visited_left, visited_right)
+        }
+
+        /// 最小頂点被覆を求める (König の定理より、サイズは最大マッチングのサイズと等しい)
+        ///
+        /// # Returns
+        /// `(被覆に含まれる左側の頂点のリスト, 被覆に含まれる右側の頂点のリスト)`
+        ///
+        /// # 計算量
+        /// O(V + E)
+        pub fn min_vertex_cover(&self) -> (Vec<usize>, Vec<usize>) {
+            let (visited_left, visited_right) = self.alternating_reachable();
+            (
+                (0..self.n_left).filter(|&l| !visited_left[l]).collect(),
+                (0..self.n_right).filter(|&r| visited_right[r]).collect(),
+            )
+        }
+
+        /// 最大独立集合を求める (最小頂点被覆の補集合)
+        ///
+        /// # Returns
+        /// `(独立集合に含まれる左側の頂点のリスト, 独立集合に含まれる右側の頂点のリスト)`
+        ///
+        /// # 計算量
+        /// O(V + E)
+        pub fn max_independent_set(&self) -> (Vec<usize>, Vec<usize>) {
+            let (visited_left, visited_right) = self.alternating_reachable();
+            (
+                (0..self.n_left).filter(|&l| visited_left[l]).collect(),
+                (0..self.n_right).filter(|&r| !visited_right[r]).collect(),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::bipartite_matching::*;
+
+    #[test]
+    fn test_bipartite_matching() {
+        // 左 0 - 右 0, 1
+        // 左 1 - 右 0
+        // 左 2 - 右 0
+        let edges = [(0, 0), (0, 1), (1, 0), (2, 0)];
+        let bm = BipartiteMatching::new(3, 2, &edges);
+        assert_eq!(bm.size(), 2);
+        let pairs = bm.pairs();
+        assert_eq!(pairs.len(), 2);
+        assert!(pairs.contains(&(0, 1)));
+
+        let (cover_left, cover_right) = bm.min_vertex_cover();
+        assert_eq!(cover_left, vec![0]);
+        assert_eq!(cover_right, vec![0]);
+
+        let (is_left, is_right) = bm.max_independent_set();
+        assert_eq!(is_left, vec![1, 2]);
+        assert_eq!(is_right, vec![1]);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_bipartite_matching_random() {
+        use ac_library::MfGraph;
+        use rand::prelude::*;
+
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let n_left = rng.random_range(0..=8);
+            let n_right = rng.random_range(0..=8);
+            let mut edges = vec![];
+            for l in 0..n_left {
+                for r in 0..n_right {
+                    if rng.random_bool(0.3) {
+                        edges.push((l, r));
+                    }
+                }
+            }
+
+            let bm = BipartiteMatching::new(n_left, n_right, &edges);
+
+            let mut graph = MfGraph::new(n_left + n_right + 2);
+            let s = n_left + n_right;
+            let t = s + 1;
+            for l in 0..n_left {
+                graph.add_edge(s, l, 1);
+            }
+            for r in 0..n_right {
+                graph.add_edge(n_left + r, t, 1);
+            }
+            for &(l, r) in &edges {
+                graph.add_edge(l, n_left + r, 1);
+            }
+            let expected = graph.flow(s, t) as usize;
+            assert_eq!(bm.size(), expected);
+
+            // マッチングになっている
+            let pairs = bm.pairs();
+            assert_eq!(pairs.len(), expected);
+            assert!(pairs.iter().all(|p| edges.contains(p)));
+            for &(l, r) in &pairs {
+                assert_eq!(bm.match_left[l], Some(r));
+                assert_eq!(bm.match_right[r], Some(l));
+            }
+
+            // 頂点被覆になっている
+            let (cover_left, cover_right) = bm.min_vertex_cover();
+            assert_eq!(cover_left.len() + cover_right.len(), expected);
+            assert!(
+                edges
+                    .iter()
+                    .all(|(l, r)| cover_left.contains(l) || cover_right.contains(r))
+            );
+
+            // 独立集合になっている
+            let (is_left, is_right) = bm.max_independent_set();
+            assert_eq!(is_left.len() + is_right.len(), n_left + n_right - expected);
+            assert!(
+                edges
+                    .iter()
+                    .all(|(l, r)| !is_left.contains(l) || !is_right.contains(r))
+            );
+        }
+    }
+}
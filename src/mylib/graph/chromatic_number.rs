@@ -0,0 +1,131 @@
+use crate::data_structure::bitset::bitset::BitSet;
+use crate::math::modint_u64::modint_u64::ModInt2305843009213693951;
+use cargo_snippet::snippet;
+
+#[allow(clippy::module_inception)]
+#[snippet(prefix = "use chromatic_number::*;", include = "bitset, modint_u64")]
+pub mod chromatic_number {
+    use super::BitSet;
+
+    type Mint = super::ModInt2305843009213693951;
+
+    /// 彩色数 (隣接する頂点が異なる色になるように塗り分けるのに必要な色の最小数) を求める
+    ///
+    /// `I(S)` を `S` に含まれる独立集合 (空集合を含む) の個数としたとき、
+    /// 包除原理により `Σ_S (-1)^(n-|S|) I(S)^k > 0` となる最小の `k` が彩色数になる。
+    /// この値を法 `2^61 - 1` で計算するため、ごく低い確率で誤った値を返しうる。
+    ///
+    /// # Arguments
+    /// * `adj` - `adj[v]` は頂点 `v` に隣接する頂点の集合 (無向グラフ、自己ループなし)
+    ///
+    /// # Returns
+    /// 彩色数。頂点数が 0 の場合は 0。
+    ///
+    /// # 計算量
+    /// O(2^n n) (n は頂点数)
+    pub fn chromatic_number(adj: &[BitSet]) -> usize {
+        let n = adj.len();
+        if n == 0 {
+            return 0;
+        }
+        // ind[s]: s に含まれる独立集合の個数
+        let mut ind = vec![Mint::new(0); 1 << n];
+        ind[0] = Mint::new(1);
+        for s in 1_usize..1 << n {
+            let v = s.trailing_zeros() as usize;
+            let rest = s & !(1 << v);
+            // v を使わない場合 + v を使う場合
+            ind[s] = ind[rest] + ind[rest & !adj[v].to_bit()];
+        }
+        let sign = (0_usize..1 << n)
+            .map(|s| {
+                if (n - s.count_ones() as usize) % 2 == 0 {
+                    Mint::new(1)
+                } else {
+                    -Mint::new(1)
+                }
+            })
+            .collect::<Vec<_>>();
+        let mut pow = ind.clone();
+        for k in 1..n {
+            let total = (0..1 << n).fold(Mint::new(0), |acc, s| acc + sign[s] * pow[s]);
+            if total != Mint::new(0) {
+                return k;
+            }
+            for s in 0..1 << n {
+                pow[s] *= ind[s];
+            }
+        }
+        n
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::chromatic_number::*;
+    use crate::data_structure::bitset::bitset::BitSet;
+
+    fn build_adj(n: usize, edges: &[(usize, usize)]) -> Vec<BitSet> {
+        let mut adj = vec![BitSet::empty(); n];
+        for &(u, v) in edges {
+            adj[u] = adj[u].inserted(v);
+            adj[v] = adj[v].inserted(u);
+        }
+        adj
+    }
+
+    #[test]
+    fn test_chromatic_number() {
+        assert_eq!(chromatic_number(&[]), 0);
+        assert_eq!(chromatic_number(&build_adj(3, &[])), 1);
+        // 偶閉路
+        assert_eq!(
+            chromatic_number(&build_adj(4, &[(0, 1), (1, 2), (2, 3), (3, 0)])),
+            2
+        );
+        // 奇閉路
+        assert_eq!(
+            chromatic_number(&build_adj(5, &[(0, 1), (1, 2), (2, 3), (3, 4), (4, 0)])),
+            3
+        );
+        // 完全グラフ K4
+        assert_eq!(
+            chromatic_number(&build_adj(
+                4,
+                &[(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)]
+            )),
+            4
+        );
+    }
+
+    #[test]
+    #[ignore]
+    fn test_chromatic_number_random() {
+        use itertools::Itertools;
+        use rand::prelude::*;
+
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let n = rng.random_range(1..=7);
+            let p = rng.random_range(0.0..1.0);
+            let mut edges = vec![];
+            for u in 0..n {
+                for v in u + 1..n {
+                    if rng.random_bool(p) {
+                        edges.push((u, v));
+                    }
+                }
+            }
+            // k 色での塗り分けを全探索する
+            let expected = (1..=n)
+                .find(|&k| {
+                    (0..n)
+                        .map(|_| 0..k)
+                        .multi_cartesian_product()
+                        .any(|color| edges.iter().all(|&(u, v)| color[u] != color[v]))
+                })
+                .unwrap();
+            assert_eq!(chromatic_number(&build_adj(n, &edges)), expected);
+        }
+    }
+}
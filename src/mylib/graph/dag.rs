@@ -0,0 +1,508 @@
+use cargo_snippet::snippet;
+
+use crate::graph::topo_sort::topo_sort;
+
+#[snippet(prefix = "use dag::*;", include = "topo_sort")]
+#[allow(clippy::module_inception)]
+pub mod dag {
+    use super::topo_sort;
+    use std::cmp::Reverse;
+    use std::collections::BinaryHeap;
+    use std::ops::Add;
+
+    fn collect_adj<F, It, E>(nv: usize, mut adj: F) -> Vec<Vec<E>>
+    where
+        F: FnMut(usize) -> It,
+        It: IntoIterator<Item = E>,
+    {
+        (0..nv).map(|u| adj(u).into_iter().collect()).collect()
+    }
+
+    /// 辞書順最小のトポロジカル順序を求める
+    ///
+    /// # Arguments
+    /// * `nv` - 頂点数
+    /// * `adj` - 頂点を受け取り、隣接する頂点のイテレータを返す `usize -> impl IntoIterator<Item = usize>` のクロージャー
+    ///
+    /// # Returns
+    /// 辞書順最小のトポロジカル順序。閉路がある場合は `None`。
+    ///
+    /// # 計算量
+    /// O(V log V + E)
+    pub fn topo_sort_lex_min<F, It>(nv: usize, adj: F) -> Option<Vec<usize>>
+    where
+        F: FnMut(usize) -> It,
+        It: IntoIterator<Item = usize>,
+    {
+        let adj = collect_adj(nv, adj);
+        let mut in_deg = vec![0; nv];
+        for edges in &adj {
+            for &v in edges {
+                in_deg[v] += 1;
+            }
+        }
+        let mut pq = (0..nv)
+            .filter(|&v| in_deg[v] == 0)
+            .map(Reverse)
+            .collect::<BinaryHeap<_>>();
+        let mut order = vec![];
+        while let Some(Reverse(u)) = pq.pop() {
+            order.push(u);
+            for &v in &adj[u] {
+                in_deg[v] -= 1;
+                if in_deg[v] == 0 {
+                    pq.push(Reverse(v));
+                }
+            }
+        }
+        (order.len() == nv).then_some(order)
+    }
+
+    /// トポロジカル順序が一意に定まるかどうかを判定する
+    ///
+    /// # Arguments
+    /// * `nv` - 頂点数
+    /// * `adj` - 頂点を受け取り、隣接する頂点のイテレータを返す `usize -> impl IntoIterator<Item = usize>` のクロージャー
+    ///
+    /// # Returns
+    /// トポロジカル順序がちょうど 1 つ存在するなら `true`。閉路がある場合は `false`。
+    ///
+    /// # 計算量
+    /// O(V + E)
+    pub fn has_unique_topo_order<F, It>(nv: usize, adj: F) -> bool
+    where
+        F: FnMut(usize) -> It,
+        It: IntoIterator<Item = usize>,
+    {
+        // 一意であることと、トポロジカル順で隣り合う頂点の間にすべて辺があることは同値
+        let adj = collect_adj(nv, adj);
+        let order = topo_sort(&adj);
+        if order.len() != nv {
+            return false;
+        }
+        order.windows(2).all(|w| adj[w[0]].contains(&w[1]))
+    }
+
+    /// トポロジカル順序の個数を数える (ビット DP)
+    ///
+    /// # Arguments
+    /// * `nv` - 頂点数 (20 程度まで)
+    /// * `adj` - 頂点を受け取り、隣接する頂点のイテレータを返す `usize -> impl IntoIterator<Item = usize>` のクロージャー
+    ///
+    /// # Returns
+    /// トポロジカル順序の個数。閉路がある場合は 0。
+    ///
+    /// # 計算量
+    /// O(2^V V + E)
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let adj = vec![vec![1], vec![], vec![]];
+    /// assert_eq!(count_topo_orders::<Mint, _, _>(3, |u| adj[u].iter().copied()), Mint::new(3));
+    /// ```
+    pub fn count_topo_orders<T, F, It>(nv: usize, mut adj: F) -> T
+    where
+        T: Copy + Add<Output = T> + From<u8>,
+        F: FnMut(usize) -> It,
+        It: IntoIterator<Item = usize>,
+    {
+        // pred[v]: v より前に置く必要がある頂点の集合
+        let mut pred = vec![0_usize; nv];
+        for u in 0..nv {
+            for v in adj(u) {
+                pred[v] |= 1 << u;
+            }
+        }
+        // dp[s]: 頂点集合 s を先頭に並べる方法の数
+        let mut dp = vec![T::from(0); 1 << nv];
+        dp[0] = T::from(1);
+        for s in 0..1 << nv {
+            for v in 0..nv {
+                if s >> v & 1 == 0 && pred[v] & s == pred[v] {
+                    dp[s | 1 << v] = dp[s | 1 << v] + dp[s];
+                }
+            }
+        }
+        dp[(1 << nv) - 1]
+    }
+
+    /// DAG の最長路を求める
+    ///
+    /// # Arguments
+    /// * `nv` - 頂点数
+    /// * `adj` - 頂点を受け取り、隣接する頂点とそのコストのペアのイテレータを返す `usize -> impl IntoIterator<Item = (usize, i64)>` のクロージャー
+    ///
+    /// # Returns
+    /// `(最長路の長さ, 最長路の頂点列)`。始点と終点は任意で、頂点 1 つからなるパス (長さ 0) も含む。
+    /// 閉路がある場合は `None`。頂点数が 0 の場合は `Some((0, vec![]))`。
+    ///
+    /// # 計算量
+    /// O(V + E)
+    pub fn dag_longest_path<F, It>(nv: usize, adj: F) -> Option<(i64, Vec<usize>)>
+    where
+        F: FnMut(usize) -> It,
+        It: IntoIterator<Item = (usize, i64)>,
+    {
+        let adj = collect_adj(nv, adj);
+        let order = topo_sort(
+            &adj.iter()
+                .map(|edges| edges.iter().map(|&(v, _)| v).collect())
+                .collect::<Vec<_>>(),
+        );
+        if order.len() != nv {
+            return None;
+        }
+        if nv == 0 {
+            return Some((0, vec![]));
+        }
+        // dp[v]: v で終わるパスの長さの最大値
+        let mut dp = vec![0; nv];
+        let mut prev = vec![None; nv];
+        for &u in &order {
+            for &(v, cost) in &adj[u] {
+                if dp[u] + cost > dp[v] || (prev[v].is_none() && dp[u] + cost == dp[v]) {
+                    dp[v] = dp[u] + cost;
+                    prev[v] = Some(u);
+                }
+            }
+        }
+        let last = (0..nv).rev().max_by_key(|&v| dp[v]).unwrap();
+        let mut path: Vec<_> = std::iter::successors(Some(last), |&v| prev[v]).collect();
+        path.reverse();
+        Some((dp[last], path))
+    }
+
+    /// 重みなし DAG の最長路 (辺の本数が最大のパス) を求める
+    ///
+    /// # Arguments
+    /// * `nv` - 頂点数
+    /// * `adj` - 頂点を受け取り、隣接する頂点のイテレータを返す `usize -> impl IntoIterator<Item = usize>` のクロージャー
+    ///
+    /// # Returns
+    /// `(最長路の辺の本数, 最長路の頂点列)`。閉路がある場合は `None`。頂点数が 0 の場合は `Some((0, vec![]))`。
+    ///
+    /// # 計算量
+    /// O(V + E)
+    pub fn dag_longest_path_unweighted<F, It>(nv: usize, mut adj: F) -> Option<(i64, Vec<usize>)>
+    where
+        F: FnMut(usize) -> It,
+        It: IntoIterator<Item = usize>,
+    {
+        dag_longest_path(nv, |u| adj(u).into_iter().map(|v| (v, 1)))
+    }
+}
+
+use crate::data_structure::ix::{Bounds, Ix};
+
+#[snippet(prefix = "use dag_ix::*;", include = "dag")]
+pub mod dag_ix {
+    use super::dag::{
+        count_topo_orders, dag_longest_path, has_unique_topo_order, topo_sort_lex_min,
+    };
+    use super::{Bounds, Ix};
+    use std::ops::Add;
+
+    /// Bounds を用いた任意の型 I: Ix に対して、辞書順最小のトポロジカル順序を求める
+    ///
+    /// 頂点の大小は `bounds` でのインデックスの大小で比較する。
+    ///
+    /// # Arguments
+    /// * `bounds` - 頂点のインデックス範囲
+    /// * `adj` - 頂点を受け取り、隣接する頂点のイテレータを返す `I -> impl IntoIterator<Item = I>` のクロージャー
+    ///
+    /// # Returns
+    /// 辞書順最小のトポロジカル順序。閉路がある場合は `None`。
+    ///
+    /// # 計算量
+    /// O(V log V + E)
+    pub fn topo_sort_lex_min_arbitrary<I, F, It>(bounds: Bounds<I>, mut adj: F) -> Option<Vec<I>>
+    where
+        I: Ix,
+        F: FnMut(I) -> It,
+        It: IntoIterator<Item = I>,
+    {
+        let nv = bounds.range_size();
+        let adj_usize = |u_idx: usize| {
+            let u = bounds.from_index(u_idx);
+            adj(u).into_iter().map(move |v| bounds.to_index(v))
+        };
+        let order = topo_sort_lex_min(nv, adj_usize)?;
+        Some(order.into_iter().map(|i| bounds.from_index(i)).collect())
+    }
+
+    /// Bounds を用いた任意の型 I: Ix に対して、トポロジカル順序が一意に定まるかどうかを判定する
+    ///
+    /// # Arguments
+    /// * `bounds` - 頂点のインデックス範囲
+    /// * `adj` - 頂点を受け取り、隣接する頂点のイテレータを返す `I -> impl IntoIterator<Item = I>` のクロージャー
+    ///
+    /// # Returns
+    /// トポロジカル順序がちょうど 1 つ存在するなら `true`。閉路がある場合は `false`。
+    ///
+    /// # 計算量
+    /// O(V + E)
+    pub fn has_unique_topo_order_arbitrary<I, F, It>(bounds: Bounds<I>, mut adj: F) -> bool
+    where
+        I: Ix,
+        F: FnMut(I) -> It,
+        It: IntoIterator<Item = I>,
+    {
+        let nv = bounds.range_size();
+        let adj_usize = |u_idx: usize| {
+            let u = bounds.from_index(u_idx);
+            adj(u).into_iter().map(move |v| bounds.to_index(v))
+        };
+        has_unique_topo_order(nv, adj_usize)
+    }
+
+    /// Bounds を用いた任意の型 I: Ix に対して、トポロジカル順序の個数を数える (ビット DP)
+    ///
+    /// # Arguments
+    /// * `bounds` - 頂点のインデックス範囲 (頂点数 20 程度まで)
+    /// * `adj` - 頂点を受け取り、隣接する頂点のイテレータを返す `I -> impl IntoIterator<Item = I>` のクロージャー
+    ///
+    /// # Returns
+    /// トポロジカル順序の個数。閉路がある場合は 0。
+    ///
+    /// # 計算量
+    /// O(2^V V + E)
+    pub fn count_topo_orders_arbitrary<T, I, F, It>(bounds: Bounds<I>, mut adj: F) -> T
+    where
+        T: Copy + Add<Output = T> + From<u8>,
+        I: Ix,
+        F: FnMut(I) -> It,
+        It: IntoIterator<Item = I>,
+    {
+        let nv = bounds.range_size();
+        let adj_usize = |u_idx: usize| {
+            let u = bounds.from_index(u_idx);
+            adj(u).into_iter().map(move |v| bounds.to_index(v))
+        };
+        count_topo_orders(nv, adj_usize)
+    }
+
+    /// Bounds を用いた任意の型 I: Ix に対して、DAG の最長路を求める
+    ///
+    /// # Arguments
+    /// * `bounds` - 頂点のインデックス範囲
+    /// * `adj` - 頂点を受け取り、隣接する頂点とそのコストのペアのイテレータを返す `I -> impl IntoIterator<Item = (I, i64)>` のクロージャー
+    ///
+    /// # Returns
+    /// `(最長路の長さ, 最長路の頂点列)`。閉路がある場合は `None`。
+    ///
+    /// # 計算量
+    /// O(V + E)
+    pub fn dag_longest_path_arbitrary<I, F, It>(
+        bounds: Bounds<I>,
+        mut adj: F,
+    ) -> Option<(i64, Vec<I>)>
+    where
+        I: Ix,
+        F: FnMut(I) -> It,
+        It: IntoIterator<Item = (I, i64)>,
+    {
+        let nv = bounds.range_size();
+        let adj_usize = |u_idx: usize| {
+            let u = bounds.from_index(u_idx);
+            adj(u)
+                .into_iter()
+                .map(move |(v, cost)| (bounds.to_index(v), cost))
+        };
+        let (len, path) = dag_longest_path(nv, adj_usize)?;
+        Some((
+            len,
+            path.into_iter().map(|i| bounds.from_index(i)).collect(),
+        ))
+    }
+
+    /// Bounds を用いた任意の型 I: Ix に対して、重みなし DAG の最長路 (辺の本数が最大のパス) を求める
+    ///
+    /// # Arguments
+    /// * `bounds` - 頂点のインデックス範囲
+    /// * `adj` - 頂点を受け取り、隣接する頂点のイテレータを返す `I -> impl IntoIterator<Item = I>` のクロージャー
+    ///
+    /// # Returns
+    /// `(最長路の辺の本数, 最長路の頂点列)`。閉路がある場合は `None`。
+    ///
+    /// # 計算量
+    /// O(V + E)
+    pub fn dag_longest_path_unweighted_arbitrary<I, F, It>(
+        bounds: Bounds<I>,
+        mut adj: F,
+    ) -> Option<(i64, Vec<I>)>
+    where
+        I: Ix,
+        F: FnMut(I) -> It,
+        It: IntoIterator<Item = I>,
+    {
+        dag_longest_path_arbitrary(bounds, |u| adj(u).into_iter().map(|v| (v, 1)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::dag::*;
+    use super::dag_ix::*;
+    use crate::data_structure::ix::Bounds;
+    use ac_library::ModInt998244353 as Mint;
+
+    #[test]
+    fn test_dag() {
+        // 0 → 1 → 3
+        // ↓       ↑
+        // 2 ──────┘
+        // 4
+        let adj = [
+            vec![(1, 1), (2, 5)],
+            vec![(3, 1)],
+            vec![(3, 1)],
+            vec![],
+            vec![],
+        ];
+        let adj_unweighted = adj
+            .iter()
+            .map(|es| es.iter().map(|&(v, _)| v).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let unweighted = |u: usize| adj_unweighted[u].iter().copied();
+
+        assert_eq!(topo_sort_lex_min(5, unweighted), Some(vec![0, 1, 2, 3, 4]));
+        assert!(!has_unique_topo_order(5, unweighted));
+        // 4 の位置が 5 通り × 1 と 2 の順序が 2 通り
+        assert_eq!(
+            count_topo_orders::<Mint, _, _>(5, unweighted),
+            Mint::new(10)
+        );
+        assert_eq!(
+            dag_longest_path(5, |u| adj[u].iter().copied()),
+            Some((6, vec![0, 2, 3]))
+        );
+        assert_eq!(
+            dag_longest_path_unweighted(5, unweighted),
+            Some((2, vec![0, 1, 3]))
+        );
+
+        // 一本道
+        let chain = [vec![1], vec![2], vec![]];
+        assert!(has_unique_topo_order(3, |u| chain[u].iter().copied()));
+        assert_eq!(
+            count_topo_orders::<usize, _, _>(3, |u| chain[u].iter().copied()),
+            1
+        );
+
+        // 閉路
+        let cycle = [vec![1], vec![0]];
+        assert_eq!(topo_sort_lex_min(2, |u| cycle[u].iter().copied()), None);
+        assert!(!has_unique_topo_order(2, |u| cycle[u].iter().copied()));
+        assert_eq!(
+            count_topo_orders::<usize, _, _>(2, |u| cycle[u].iter().copied()),
+            0
+        );
+        assert_eq!(
+            dag_longest_path_unweighted(2, |u| cycle[u].iter().copied()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_dag_arbitrary() {
+        // 文字を頂点とする: b → a, c → a
+        let bounds = Bounds::new('a', 'c');
+        let adj = |c: char| match c {
+            'b' | 'c' => vec!['a'],
+            _ => vec![],
+        };
+        assert_eq!(
+            topo_sort_lex_min_arbitrary(bounds, adj),
+            Some(vec!['b', 'c', 'a'])
+        );
+        assert!(!has_unique_topo_order_arbitrary(bounds, adj));
+        assert_eq!(
+            count_topo_orders_arbitrary::<usize, _, _, _>(bounds, adj),
+            2
+        );
+        assert_eq!(
+            dag_longest_path_unweighted_arbitrary(bounds, adj),
+            Some((1, vec!['b', 'a']))
+        );
+        assert_eq!(
+            dag_longest_path_arbitrary(bounds, |c| adj(c).into_iter().map(move |v| (v, -1))),
+            Some((0, vec!['a']))
+        );
+    }
+
+    #[test]
+    #[ignore]
+    fn test_dag_random() {
+        use itertools::Itertools;
+        use rand::prelude::*;
+
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..300 {
+            let nv = rng.random_range(1..=7);
+            // ランダムな置換の順に辺を張って DAG を作る。まれに逆向きの辺を入れて閉路を作る
+            let mut perm = (0..nv).collect::<Vec<_>>();
+            perm.shuffle(&mut rng);
+            let mut adj = vec![vec![]; nv];
+            for i in 0..nv {
+                for j in i + 1..nv {
+                    if rng.random_bool(0.3) {
+                        adj[perm[i]].push((perm[j], rng.random_range(-5..=5)));
+                    }
+                }
+            }
+            if rng.random_bool(0.1) && nv >= 2 {
+                adj[perm[nv - 1]].push((perm[0], 0));
+            }
+            let unweighted = |u: usize| adj[u].iter().map(|&(v, _)| v);
+
+            let orders = (0..nv)
+                .permutations(nv)
+                .filter(|order| {
+                    let mut pos = vec![0; nv];
+                    for (i, &v) in order.iter().enumerate() {
+                        pos[v] = i;
+                    }
+                    (0..nv).all(|u| adj[u].iter().all(|&(v, _)| pos[u] < pos[v]))
+                })
+                .collect::<Vec<_>>();
+
+            assert_eq!(topo_sort_lex_min(nv, unweighted), orders.first().cloned());
+            assert_eq!(has_unique_topo_order(nv, unweighted), orders.len() == 1);
+            assert_eq!(
+                count_topo_orders::<usize, _, _>(nv, unweighted),
+                orders.len()
+            );
+
+            // 最長路: 単純パスを全探索する (DAG なら全パスが単純)
+            fn dfs(u: usize, len: i64, adj: &[Vec<(usize, i64)>], best: &mut i64) {
+                *best = (*best).max(len);
+                for &(v, c) in &adj[u] {
+                    dfs(v, len + c, adj, best);
+                }
+            }
+            let res = dag_longest_path(nv, |u| adj[u].iter().copied());
+            if orders.is_empty() {
+                assert_eq!(res, None);
+                continue;
+            }
+            let mut best = i64::MIN;
+            for s in 0..nv {
+                dfs(s, 0, &adj, &mut best);
+            }
+            let (len, path) = res.unwrap();
+            assert_eq!(len, best);
+            let path_len = path
+                .windows(2)
+                .map(|w| {
+                    adj[w[0]]
+                        .iter()
+                        .filter(|&&(v, _)| v == w[1])
+                        .map(|&(_, c)| c)
+                        .max()
+                        .unwrap()
+                })
+                .sum::<i64>();
+            assert_eq!(path_len, len);
+        }
+    }
+}
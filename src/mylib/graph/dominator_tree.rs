@@ -0,0 +1,336 @@
+use cargo_snippet::snippet;
+
+use crate::graph::tree::euler_tour::euler_tour::EulerTour;
+
+#[allow(clippy::module_inception)]
+#[snippet(prefix = "use dominator_tree::*;", include = "euler_tour")]
+pub mod dominator_tree {
+    use super::EulerTour;
+
+    /// Lengauer-Tarjan 法で有向グラフの支配木 (各頂点の immediate dominator) を求める
+    ///
+    /// 頂点 `u` が頂点 `v` を支配するとは、`root` から `v` へのすべてのパスが `u` を通ることをいう。
+    /// `v` の immediate dominator は、`v` 以外で `v` を支配する頂点のうち `v` に最も近いもの。
+    ///
+    /// # Arguments
+    /// * `nv` - 頂点数
+    /// * `adj` - 頂点を受け取り、隣接する頂点 (行き先) のイテレータを返すクロージャー
+    /// * `root` - 始点
+    ///
+    /// # Returns
+    /// 各頂点の immediate dominator。`root` 自身と `root` から到達できない頂点は `None`。
+    ///
+    /// # 計算量
+    /// O((V + E) log V)
+    pub fn dominator_tree<F, It>(nv: usize, mut adj: F, root: usize) -> Vec<Option<usize>>
+    where
+        F: FnMut(usize) -> It,
+        It: IntoIterator<Item = usize>,
+    {
+        let adj = (0..nv)
+            .map(|v| adj(v).into_iter().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        // DFS で訪問順を求める。以降は訪問順の番号で計算する。
+        let mut ord = vec![usize::MAX; nv];
+        let mut vs = vec![];
+        let mut par = vec![];
+        {
+            let mut stack = vec![(root, usize::MAX)];
+            while let Some((v, p)) = stack.pop() {
+                if ord[v] != usize::MAX {
+                    continue;
+                }
+                ord[v] = vs.len();
+                vs.push(v);
+                par.push(p);
+                for &next in adj[v].iter().rev() {
+                    if ord[next] == usize::MAX {
+                        stack.push((next, ord[v]));
+                    }
+                }
+            }
+        }
+        let n = vs.len();
+
+        let mut rev_adj = vec![vec![]; n];
+        for &v in &vs {
+            for &next in &adj[v] {
+                rev_adj[ord[next]].push(ord[v]);
+            }
+        }
+
+        let mut sdom = (0..n).collect::<Vec<_>>();
+        let mut idom = (0..n).collect::<Vec<_>>();
+        let mut label = (0..n).collect::<Vec<_>>();
+        // 処理済みの頂点からなる森の親 (経路圧縮する)
+        let mut anc = vec![usize::MAX; n];
+        let mut bucket = vec![vec![]; n];
+
+        // v から森の根の直前までの頂点のうち、sdom が最小の頂点を返す
+        let eval = |v: usize, anc: &mut [usize], label: &mut [usize], sdom: &[usize]| -> usize {
+            if anc[v] == usize::MAX {
+                return v;
+            }
+            let mut path = vec![];
+            let mut x = v;
+            while anc[anc[x]] != usize::MAX {
+                path.push(x);
+                x = anc[x];
+            }
+            for &y in path.iter().rev() {
+                let a = anc[y];
+                if sdom[label[a]] < sdom[label[y]] {
+                    label[y] = label[a];
+                }
+                anc[y] = anc[a];
+            }
+            label[v]
+        };
+
+        for w in (1..n).rev() {
+            for &v in &rev_adj[w] {
+                let u = eval(v, &mut anc, &mut label, &sdom);
+                sdom[w] = sdom[w].min(sdom[u]);
+            }
+            bucket[sdom[w]].push(w);
+            anc[w] = par[w];
+            for v in std::mem::take(&mut bucket[par[w]]) {
+                let u = eval(v, &mut anc, &mut label, &sdom);
+                idom[v] = if sdom[u] == sdom[v] { sdom[v] } else { u };
+            }
+        }
+        for w in 1..n {
+            if idom[w] != sdom[w] {
+                idom[w] = idom[idom[w]];
+            }
+        }
+
+        let mut result = vec![None; nv];
+        for w in 1..n {
+            result[vs[w]] = Some(vs[idom[w]]);
+        }
+        result
+    }
+
+    /// 支配木に対して「`root` から `v` へのすべてのパスが `u` を通るか」を判定する
+    #[derive(Clone, Debug)]
+    pub struct Dominance {
+        root: usize,
+        idom: Vec<Option<usize>>,
+        euler_tour: EulerTour,
+    }
+
+    impl Dominance {
+        /// 支配木から構築する
+        ///
+        /// # Arguments
+        /// * `idom` - `dominator_tree` の結果
+        /// * `root` - 始点
+        ///
+        /// # 計算量
+        /// O(V)
+        pub fn new(idom: Vec<Option<usize>>, root: usize) -> Self {
+            // 到達できない頂点は root の子として扱い、判定時に除外する
+            let tree_parent = (0..idom.len())
+                .map(|v| idom[v].unwrap_or(root))
+                .collect::<Vec<_>>();
+            let euler_tour = EulerTour::new(&tree_parent);
+            Self {
+                root,
+                idom,
+                euler_tour,
+            }
+        }
+
+        /// 頂点 `v` が `root` から到達可能かどうか
+        ///
+        /// # 計算量
+        /// O(1)
+        pub fn is_reachable(&self, v: usize) -> bool {
+            v == self.root || self.idom[v].is_some()
+        }
+
+        /// `root` から `v` へのすべてのパスが `u` を通るかどうか (`u == v` の場合も `true`)
+        ///
+        /// `u` または `v` が `root` から到達できない場合は `false` を返す。
+        ///
+        /// # 計算量
+        /// O(1)
+        pub fn dominates(&self, u: usize, v: usize) -> bool {
+            self.is_reachable(u)
+                && self.is_reachable(v)
+                && self.euler_tour.in_time[u] <= self.euler_tour.in_time[v]
+                && self.euler_tour.out_time[v] <= self.euler_tour.out_time[u]
+        }
+    }
+}
+
+use crate::data_structure::ix::{Bounds, Ix, IxVec};
+
+#[snippet(prefix = "use dominator_tree_ix::*;", include = "dominator_tree")]
+pub mod dominator_tree_ix {
+    use super::dominator_tree::dominator_tree;
+    use super::{Bounds, Ix, IxVec};
+
+    /// Bounds を用いた任意の型 I: Ix に対する支配木 (各頂点の immediate dominator) を求める
+    ///
+    /// # Arguments
+    /// * `bounds` - 頂点のインデックス範囲
+    /// * `adj` - 頂点を受け取り、隣接する頂点 (行き先) のイテレータを返す `I -> impl IntoIterator<Item = I>` のクロージャー
+    /// * `root` - 始点
+    ///
+    /// # Returns
+    /// 各頂点の immediate dominator。`root` 自身と `root` から到達できない頂点は `None`。
+    ///
+    /// # 計算量
+    /// O((V + E) log V)
+    pub fn dominator_tree_arbitrary<I, F, It>(
+        bounds: Bounds<I>,
+        mut adj: F,
+        root: I,
+    ) -> IxVec<I, Option<I>>
+    where
+        I: Ix,
+        F: FnMut(I) -> It,
+        It: IntoIterator<Item = I>,
+    {
+        let nv = bounds.range_size();
+        let adj_usize = |u_idx: usize| {
+            let u = bounds.from_index(u_idx);
+            adj(u).into_iter().map(move |v| bounds.to_index(v))
+        };
+        let idom = dominator_tree(nv, adj_usize, bounds.to_index(root));
+        IxVec::from_vec(
+            bounds,
+            idom.into_iter()
+                .map(|p| p.map(|p| bounds.from_index(p)))
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::dominator_tree::*;
+    use super::dominator_tree_ix::*;
+    use crate::data_structure::ix::Bounds;
+
+    #[test]
+    fn test_dominator_tree() {
+        // 0 → 1 → 2 → 4 → 5
+        //     ↓       ↑
+        //     3 ------+
+        // 6 → 5 (0 から 6 には到達できない)
+        let adj = [
+            vec![1],
+            vec![2, 3],
+            vec![4],
+            vec![4],
+            vec![5],
+            vec![],
+            vec![5],
+        ];
+        let idom = dominator_tree(7, |v| adj[v].iter().copied(), 0);
+        assert_eq!(
+            idom,
+            vec![None, Some(0), Some(1), Some(1), Some(1), Some(4), None]
+        );
+
+        let dominance = Dominance::new(idom, 0);
+        assert!(dominance.dominates(1, 5));
+        assert!(dominance.dominates(4, 5));
+        assert!(dominance.dominates(5, 5));
+        assert!(!dominance.dominates(2, 4));
+        assert!(!dominance.dominates(3, 5));
+        assert!(!dominance.dominates(6, 5));
+        assert!(!dominance.dominates(0, 6));
+        assert!(!dominance.is_reachable(6));
+    }
+
+    #[test]
+    fn test_dominator_tree_arbitrary() {
+        // (0,0) → (0,1) → (0,2)
+        //   ↓               ↑
+        // (1,0) → (1,1) ----+
+        let bounds = Bounds::new((0, 0), (1, 2));
+        let adj = |v: (usize, usize)| match v {
+            (0, 0) => vec![(0, 1), (1, 0)],
+            (0, 1) => vec![(0, 2)],
+            (1, 0) => vec![(1, 1)],
+            (1, 1) => vec![(0, 2)],
+            _ => vec![],
+        };
+        let idom = dominator_tree_arbitrary(bounds, adj, (0, 0));
+        assert_eq!(idom[(0, 0)], None);
+        assert_eq!(idom[(0, 1)], Some((0, 0)));
+        assert_eq!(idom[(0, 2)], Some((0, 0)));
+        assert_eq!(idom[(1, 0)], Some((0, 0)));
+        assert_eq!(idom[(1, 1)], Some((1, 0)));
+        assert_eq!(idom[(1, 2)], None);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_dominator_tree_random() {
+        use crate::graph::bfs::bfs::bfs_reachable;
+        use rand::prelude::*;
+
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..300 {
+            let nv = rng.random_range(1..=10);
+            let adj = (0..nv)
+                .map(|_| {
+                    (0..nv)
+                        .filter(|_| rng.random_bool(0.25))
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+            let root = rng.random_range(0..nv);
+
+            let reachable = bfs_reachable(nv, |v| adj[v].iter().copied(), [root]);
+            // naive_dominates[u][v]: u を取り除くと root から v に到達できなくなるか
+            let naive_dominates = (0..nv)
+                .map(|u| {
+                    let reachable_without_u = if u == root {
+                        vec![false; nv]
+                    } else {
+                        bfs_reachable(
+                            nv,
+                            |v| adj[v].iter().copied().filter(move |&w| w != u),
+                            [root],
+                        )
+                    };
+                    (0..nv)
+                        .map(|v| {
+                            reachable[u] && reachable[v] && (u == v || !reachable_without_u[v])
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+
+            let idom = dominator_tree(nv, |v| adj[v].iter().copied(), root);
+            for v in 0..nv {
+                if v == root || !reachable[v] {
+                    assert_eq!(idom[v], None);
+                    continue;
+                }
+                // idom[v] は v の真の支配点のうち、他のすべての真の支配点に支配されるもの
+                let d = idom[v].unwrap();
+                assert!(d != v && naive_dominates[d][v]);
+                for u in 0..nv {
+                    if u != v && naive_dominates[u][v] {
+                        assert!(naive_dominates[u][d]);
+                    }
+                }
+            }
+
+            let dominance = Dominance::new(idom, root);
+            for u in 0..nv {
+                for v in 0..nv {
+                    assert_eq!(dominance.dominates(u, v), naive_dominates[u][v]);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,291 @@
+use cargo_snippet::snippet;
+
+#[allow(clippy::module_inception)]
+#[snippet(prefix = "use euler_trail::*;")]
+pub mod euler_trail {
+    /// Hierholzer 法で、`adj` の辺をすべてちょうど 1 回ずつ通る `start` からの路を求める。
+    /// すべての辺を使えなかった場合は `None` を返す。
+    ///
+    /// `adj[v]` は `(行き先, 辺番号)` のリスト。無向グラフの場合は両方向に同じ辺番号で入れる。
+    fn hierholzer(
+        adj: &[Vec<(usize, usize)>],
+        ne: usize,
+        start: usize,
+    ) -> Option<(Vec<usize>, Vec<usize>)> {
+        let mut ptr = vec![0; adj.len()];
+        let mut used = vec![false; ne];
+        let mut edge_ids = Vec::with_capacity(ne);
+        let mut vertices = Vec::with_capacity(ne + 1);
+
+        // (頂点, その頂点に入るのに使った辺番号)
+        let mut stack = vec![(start, usize::MAX)];
+        while let Some(&(v, e)) = stack.last() {
+            if ptr[v] < adj[v].len() {
+                let (next, id) = adj[v][ptr[v]];
+                ptr[v] += 1;
+                if used[id] {
+                    continue;
+                }
+                used[id] = true;
+                stack.push((next, id));
+            } else {
+                stack.pop();
+                vertices.push(v);
+                if e != usize::MAX {
+                    edge_ids.push(e);
+                }
+            }
+        }
+
+        if edge_ids.len() != ne {
+            // 辺のある頂点が連結でない
+            return None;
+        }
+        edge_ids.reverse();
+        vertices.reverse();
+        Some((edge_ids, vertices))
+    }
+
+    /// 有向グラフのオイラー路 (すべての辺をちょうど 1 回ずつ通る路) を求める
+    ///
+    /// オイラー閉路が存在する場合は閉路 (始点と終点が等しい) を返す。
+    ///
+    /// # Arguments
+    /// * `nv` - 頂点数 (1 以上)
+    /// * `edges` - 辺 `(from, to)` のリスト。多重辺や自己ループがあってもよい。
+    ///
+    /// # Returns
+    /// オイラー路が存在する場合は `Some((通る辺の edges でのインデックスの列, 通る頂点の列))`。
+    /// 頂点の列の長さは辺の列の長さ + 1 になる。辺がない場合は `Some((vec![], vec![0]))`。
+    /// オイラー路が存在しない場合は `None`。
+    ///
+    /// # 計算量
+    /// O(V + E)
+    pub fn euler_trail_directed(
+        nv: usize,
+        edges: &[(usize, usize)],
+    ) -> Option<(Vec<usize>, Vec<usize>)> {
+        let mut adj = vec![vec![]; nv];
+        // diff[v]: 出次数 - 入次数
+        let mut diff = vec![0_i64; nv];
+        for (id, &(u, v)) in edges.iter().enumerate() {
+            adj[u].push((v, id));
+            diff[u] += 1;
+            diff[v] -= 1;
+        }
+
+        if diff.iter().any(|d| d.abs() > 1) || diff.iter().filter(|&&d| d == 1).count() > 1 {
+            return None;
+        }
+        let start = (0..nv)
+            .find(|&v| diff[v] == 1)
+            .or_else(|| (0..nv).find(|&v| !adj[v].is_empty()))
+            .unwrap_or(0);
+
+        hierholzer(&adj, edges.len(), start)
+    }
+
+    /// 無向グラフのオイラー路 (すべての辺をちょうど 1 回ずつ通る路) を求める
+    ///
+    /// オイラー閉路が存在する場合は閉路 (始点と終点が等しい) を返す。
+    ///
+    /// # Arguments
+    /// * `nv` - 頂点数 (1 以上)
+    /// * `edges` - 辺 `(u, v)` のリスト。多重辺や自己ループがあってもよい。
+    ///
+    /// # Returns
+    /// オイラー路が存在する場合は `Some((通る辺の edges でのインデックスの列, 通る頂点の列))`。
+    /// 頂点の列の長さは辺の列の長さ + 1 になる。辺がない場合は `Some((vec![], vec![0]))`。
+    /// オイラー路が存在しない場合は `None`。
+    ///
+    /// # 計算量
+    /// O(V + E)
+    pub fn euler_trail_undirected(
+        nv: usize,
+        edges: &[(usize, usize)],
+    ) -> Option<(Vec<usize>, Vec<usize>)> {
+        let mut adj = vec![vec![]; nv];
+        for (id, &(u, v)) in edges.iter().enumerate() {
+            adj[u].push((v, id));
+            adj[v].push((u, id));
+        }
+
+        let odd_vertices = (0..nv)
+            .filter(|&v| adj[v].len() % 2 == 1)
+            .collect::<Vec<_>>();
+        if odd_vertices.len() > 2 {
+            return None;
+        }
+        let start = odd_vertices
+            .first()
+            .copied()
+            .or_else(|| (0..nv).find(|&v| !adj[v].is_empty()))
+            .unwrap_or(0);
+
+        hierholzer(&adj, edges.len(), start)
+    }
+
+    /// de Bruijn 列を求める
+    ///
+    /// 長さ `n` の `0..k` の列すべてを、巡回的な連続部分列としてちょうど 1 回ずつ含む長さ `k^n` の列を返す。
+    /// 巡回させずに使う場合は、末尾に先頭の `n - 1` 要素を付け足す。
+    ///
+    /// 長さ `n - 1` の列を頂点とし、各頂点から `k` 本の辺を出したグラフのオイラー閉路から構成する。
+    ///
+    /// # Arguments
+    /// * `k` - 文字の種類数 (1 以上)
+    /// * `n` - 部分列の長さ (1 以上)
+    ///
+    /// # 計算量
+    /// O(k^n)
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let seq = de_bruijn_sequence(2, 3);
+    /// assert_eq!(seq.len(), 8); // 例: [0, 0, 0, 1, 0, 1, 1, 1]
+    /// ```
+    pub fn de_bruijn_sequence(k: usize, n: usize) -> Vec<usize> {
+        assert!(k >= 1 && n >= 1);
+        let nv = k.pow(n as u32 - 1);
+        // 頂点 v から文字 c の辺 (辺番号 v * k + c) は頂点 (v * k + c) % nv に向かう
+        let edges = (0..nv * k).map(|id| (id / k, id % nv)).collect::<Vec<_>>();
+        let (edge_ids, _) = euler_trail_directed(nv, &edges).unwrap();
+        edge_ids.into_iter().map(|id| id % k).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::euler_trail::*;
+
+    fn is_valid_trail(
+        edges: &[(usize, usize)],
+        edge_ids: &[usize],
+        vertices: &[usize],
+        directed: bool,
+    ) -> bool {
+        let mut sorted_ids = edge_ids.to_vec();
+        sorted_ids.sort();
+        sorted_ids == (0..edges.len()).collect::<Vec<_>>()
+            && vertices.len() == edge_ids.len() + 1
+            && edge_ids.iter().enumerate().all(|(i, &id)| {
+                let (u, v) = edges[id];
+                (u, v) == (vertices[i], vertices[i + 1])
+                    || !directed && (v, u) == (vertices[i], vertices[i + 1])
+            })
+    }
+
+    #[test]
+    fn test_euler_trail_directed() {
+        // 0 → 1 → 2 → 0 → 3
+        let edges = [(0, 1), (1, 2), (2, 0), (0, 3)];
+        let (edge_ids, vertices) = euler_trail_directed(4, &edges).unwrap();
+        assert_eq!(edge_ids, vec![0, 1, 2, 3]);
+        assert_eq!(vertices, vec![0, 1, 2, 0, 3]);
+
+        // 閉路 (多重辺・自己ループあり)
+        let edges = [(0, 1), (1, 0), (0, 1), (1, 1), (1, 0)];
+        let (edge_ids, vertices) = euler_trail_directed(2, &edges).unwrap();
+        assert!(is_valid_trail(&edges, &edge_ids, &vertices, true));
+        assert_eq!(vertices.first(), vertices.last());
+
+        // 次数の条件を満たさない
+        assert_eq!(euler_trail_directed(3, &[(0, 1), (0, 2)]), None);
+        // 辺のある頂点が連結でない
+        assert_eq!(
+            euler_trail_directed(4, &[(0, 1), (1, 0), (2, 3), (3, 2)]),
+            None
+        );
+        // 辺がない
+        assert_eq!(euler_trail_directed(3, &[]), Some((vec![], vec![0])));
+    }
+
+    #[test]
+    fn test_euler_trail_undirected() {
+        // 0 - 1 - 2 - 0 - 3 (次数が奇数の頂点は 0, 3)
+        let edges = [(0, 1), (2, 1), (2, 0), (3, 0)];
+        let (edge_ids, vertices) = euler_trail_undirected(4, &edges).unwrap();
+        assert!(is_valid_trail(&edges, &edge_ids, &vertices, false));
+        assert_eq!(vertices.first(), Some(&0));
+        assert_eq!(vertices.last(), Some(&3));
+
+        // 次数が奇数の頂点が 4 つ
+        assert_eq!(euler_trail_undirected(4, &[(0, 1), (2, 3)]), None);
+        // 辺のある頂点が連結でない
+        assert_eq!(
+            euler_trail_undirected(6, &[(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3)]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_de_bruijn_sequence() {
+        for (k, n) in [(1, 1), (1, 3), (2, 1), (2, 3), (3, 2), (2, 5)] {
+            let seq = de_bruijn_sequence(k, n);
+            assert_eq!(seq.len(), k.pow(n as u32));
+            let mut words = (0..seq.len())
+                .map(|i| (0..n).map(|j| seq[(i + j) % seq.len()]).collect::<Vec<_>>())
+                .collect::<Vec<_>>();
+            words.sort();
+            words.dedup();
+            assert_eq!(words.len(), k.pow(n as u32));
+            assert!(seq.iter().all(|&c| c < k));
+        }
+    }
+
+    /// すべての辺をちょうど 1 回ずつ通る路が存在するかを全探索で求める
+    fn exists_trail_naive(nv: usize, edges: &[(usize, usize)], directed: bool) -> bool {
+        fn dfs(v: usize, edges: &[(usize, usize)], used: &mut [bool], directed: bool) -> bool {
+            if used.iter().all(|&b| b) {
+                return true;
+            }
+            for id in 0..edges.len() {
+                if used[id] {
+                    continue;
+                }
+                let (a, b) = edges[id];
+                let next = if a == v {
+                    b
+                } else if !directed && b == v {
+                    a
+                } else {
+                    continue;
+                };
+                used[id] = true;
+                if dfs(next, edges, used, directed) {
+                    return true;
+                }
+                used[id] = false;
+            }
+            false
+        }
+        (0..nv).any(|s| dfs(s, edges, &mut vec![false; edges.len()], directed))
+    }
+
+    #[test]
+    #[ignore]
+    fn test_euler_trail_random() {
+        use rand::prelude::*;
+
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..1000 {
+            let nv = rng.random_range(1..=5);
+            let ne = rng.random_range(0..=7);
+            let edges = (0..ne)
+                .map(|_| (rng.random_range(0..nv), rng.random_range(0..nv)))
+                .collect::<Vec<_>>();
+
+            for directed in [true, false] {
+                let res = if directed {
+                    euler_trail_directed(nv, &edges)
+                } else {
+                    euler_trail_undirected(nv, &edges)
+                };
+                assert_eq!(res.is_some(), exists_trail_naive(nv, &edges, directed));
+                if let Some((edge_ids, vertices)) = res {
+                    assert!(is_valid_trail(&edges, &edge_ids, &vertices, directed));
+                }
+            }
+        }
+    }
+}
@@ -1,8 +1,16 @@
+use crate::data_structure::sparse_table::disjoint_sparse_table::DisjointSparseTable;
+use crate::math::algebra::monoid::extend_acl_monoid::MonoidExtPow;
 use cargo_snippet::snippet;
 
-#[snippet(prefix = "use functional_graph::*;")]
+#[snippet(
+    prefix = "use functional_graph::*;",
+    include = "disjoint_sparse_table, extend_acl_monoid"
+)]
 #[allow(clippy::module_inception)]
 pub mod functional_graph {
+    use super::{DisjointSparseTable, MonoidExtPow};
+    use ac_library::Monoid;
+
     /// Functional Graph (各頂点の出次数が 1 の有向グラフ) の解析構造体
     ///
     /// 各連結成分はちょうど1つのサイクルを持ち、そのサイクルにいくつかの木が流れ込む構造をしています。
@@ -18,9 +26,13 @@ pub mod functional_graph {
         pos_in_cycle: Vec<usize>,
         /// 各連結成分ごとのサイクル長
         cycle_lens: Vec<usize>,
+        /// 各連結成分ごとのサイクル上の頂点列 (`cycles[cid][pos_in_cycle[v]] == v`)
+        cycles: Vec<Vec<usize>>,
         // 逆向きの木（サイクルを根とする森）での DFS 順
         in_time: Vec<usize>,
         out_time: Vec<usize>,
+        /// `by_depth[d]`: depth が d の頂点を in_time の昇順に並べたもの
+        by_depth: Vec<Vec<usize>>,
     }
 
     impl FunctionalGraph {
@@ -110,14 +122,25 @@ pub mod functional_graph {
                 }
             }
 
+            // 4. 深さごとに頂点を DFS 順に並べる (k 個先の頂点を二分探索で求めるため)
+            let mut order = (0..nv).collect::<Vec<_>>();
+            order.sort_unstable_by_key(|&v| in_time[v]);
+            let max_depth = depth.iter().copied().max().unwrap_or(0);
+            let mut by_depth = vec![vec![]; max_depth + 1];
+            for v in order {
+                by_depth[depth[v]].push(v);
+            }
+
             Self {
                 comp_id,
                 depth,
                 root_on_cycle,
                 pos_in_cycle,
                 cycle_lens,
+                cycles,
                 in_time,
                 out_time,
+                by_depth,
             }
         }
 
@@ -198,6 +221,190 @@ pub mod functional_graph {
         pub fn is_reachable(&self, u: usize, v: usize) -> bool {
             self.distance(u, v).is_some()
         }
+
+        /// 頂点 u から k 回進んだ先の頂点を返す
+        ///
+        /// ダブリングと異なり、追加のメモリは O(V) で済む。
+        ///
+        /// # 計算量
+        /// O(log V)
+        pub fn kth_next(&self, u: usize, k: usize) -> usize {
+            if k <= self.depth[u] {
+                // 逆向きの木で u の祖先のうち depth が depth[u] - k のもの。
+                // 同じ深さの頂点のうち in_time が in_time[u] 以下で最大のものが祖先になる
+                let cands = &self.by_depth[self.depth[u] - k];
+                let i = cands.partition_point(|&w| self.in_time[w] <= self.in_time[u]);
+                cands[i - 1]
+            } else {
+                let root = self.root_on_cycle[u];
+                let cycle = &self.cycles[self.comp_id[u]];
+                let rest = k - self.depth[u];
+                cycle[(self.pos_in_cycle[root] + rest % cycle.len()) % cycle.len()]
+            }
+        }
+
+        /// 頂点 u と v から同時に 1 歩ずつ進んだとき、初めて同じ頂点にいる時刻とその頂点を返す
+        ///
+        /// # Returns
+        /// `(時刻, 頂点)`。いつまでも出会わない場合は `None`。`u == v` の場合は `Some((0, u))`。
+        ///
+        /// # 計算量
+        /// O(log^2 V)
+        pub fn first_meeting(&self, u: usize, v: usize) -> Option<(usize, usize)> {
+            if self.comp_id[u] != self.comp_id[v] {
+                return None;
+            }
+            // 両方がサイクルに入った後は位置の差が変わらないので、その時点で出会っていなければ出会わない
+            let t = self.depth[u].max(self.depth[v]);
+            if self.kth_next(u, t) != self.kth_next(v, t) {
+                return None;
+            }
+            if u == v {
+                return Some((0, u));
+            }
+            // 一度出会うとその後はずっと同じ頂点にいるので、二分探索できる
+            let (mut ng, mut ok) = (0, t);
+            while ok - ng > 1 {
+                let mid = (ng + ok) / 2;
+                if self.kth_next(u, mid) == self.kth_next(v, mid) {
+                    ok = mid;
+                } else {
+                    ng = mid;
+                }
+            }
+            Some((ok, self.kth_next(u, ok)))
+        }
+    }
+
+    /// Functional Graph 上で、各頂点に置かれた値の k 歩分の総積を求める構造体
+    ///
+    /// 木の部分はサイクルを根とする森をパス分解し、各パスを進む向きに並べた列の Disjoint Sparse Table で求める。
+    /// サイクルの部分はサイクルを 2 周並べた列の Disjoint Sparse Table と、1 周分の積の繰り返し二乗で求める。
+    /// 通った順に掛けるので、値は非可換なモノイドでもよい。
+    #[derive(Clone)]
+    pub struct FunctionalGraphSum<M: Monoid> {
+        fg: FunctionalGraph,
+        next: Vec<usize>,
+        /// `head[u]`: u を含むパスの、最もサイクルに近い頂点
+        head: Vec<usize>,
+        /// `pos[u]`: `path_table` の列における u の位置。パス上を 1 歩進むと位置が 1 増える。
+        pos: Vec<usize>,
+        path_table: DisjointSparseTable<M>,
+        /// `cycle_tables[cid]`: サイクルを 2 周並べた列の Disjoint Sparse Table
+        cycle_tables: Vec<DisjointSparseTable<M>>,
+    }
+
+    impl<M: Monoid> FunctionalGraphSum<M> {
+        /// 構築する
+        ///
+        /// # Arguments
+        /// * `next` - 各頂点の遷移先 (0..nv-1)
+        /// * `vals` - 各頂点の値。頂点 u から 1 歩進むと `vals[u]` が右から掛けられる。
+        ///
+        /// # 計算量
+        /// O(V log V)
+        pub fn new(next: &[usize], vals: &[M::S]) -> Self {
+            assert_eq!(next.len(), vals.len());
+            let fg = FunctionalGraph::new(next);
+            let nv = next.len();
+
+            // 部分木のサイズを葉に近い順 (depth の降順) に求め、サイズ最大の子を heavy にする
+            let mut size = vec![1; nv];
+            let mut heavy = vec![None; nv];
+            for layer in fg.by_depth.iter().skip(1).rev() {
+                for &u in layer {
+                    let p = next[u];
+                    size[p] += size[u];
+                    if heavy[p].is_none_or(|h: usize| size[h] < size[u]) {
+                        heavy[p] = Some(u);
+                    }
+                }
+            }
+
+            // パスの先頭 (サイクル上の頂点または heavy でない子) から heavy な子をたどり、進む向きに並べる
+            let mut head = vec![0; nv];
+            let mut pos = vec![0; nv];
+            let mut seq = Vec::with_capacity(nv);
+            for layer in &fg.by_depth {
+                for &h in layer {
+                    if fg.depth[h] > 0 && heavy[next[h]] == Some(h) {
+                        continue;
+                    }
+                    let path = std::iter::successors(Some(h), |&v| heavy[v]).collect::<Vec<_>>();
+                    for &v in path.iter().rev() {
+                        head[v] = h;
+                        pos[v] = seq.len();
+                        seq.push(vals[v].clone());
+                    }
+                }
+            }
+            let path_table = DisjointSparseTable::new(&seq);
+
+            let cycle_tables = fg
+                .cycles
+                .iter()
+                .map(|cycle| {
+                    let doubled = cycle
+                        .iter()
+                        .chain(cycle)
+                        .map(|&v| vals[v].clone())
+                        .collect::<Vec<_>>();
+                    DisjointSparseTable::new(&doubled)
+                })
+                .collect();
+
+            Self {
+                fg,
+                next: next.to_vec(),
+                head,
+                pos,
+                path_table,
+                cycle_tables,
+            }
+        }
+
+        /// 内部の [`FunctionalGraph`] を返す
+        pub fn graph(&self) -> &FunctionalGraph {
+            &self.fg
+        }
+
+        /// 頂点 u から k 歩進む間に通る頂点 (u を含み、k 歩後の頂点を含まない) の値を通った順に掛けた積
+        /// `op(vals[u], vals[next[u]], ...)` を返す
+        ///
+        /// # 計算量
+        /// O(log V + log k)
+        pub fn sum(&self, u: usize, k: usize) -> M::S {
+            let fg = &self.fg;
+            let tree_steps = k.min(fg.depth[u]);
+
+            // 木の部分: パスごとに区間積を取る
+            let mut res = M::identity();
+            let mut v = u;
+            let mut rest = tree_steps;
+            while rest > 0 {
+                let h = self.head[v];
+                let len = (fg.depth[v] - fg.depth[h] + 1).min(rest);
+                let prod = self.path_table.prod(self.pos[v]..self.pos[v] + len);
+                res = M::binary_operation(&res, &prod);
+                rest -= len;
+                v = self.next[h];
+            }
+            if k == tree_steps {
+                return res;
+            }
+
+            // サイクルの部分: 1 周分の積を q 回掛けてから、残りの r 歩分を掛ける
+            let root = fg.root_on_cycle[u];
+            let cid = fg.comp_id[u];
+            let len = fg.cycle_lens[cid];
+            let table = &self.cycle_tables[cid];
+            let p = fg.pos_in_cycle[root];
+            let rest = k - tree_steps;
+            let (q, r) = (rest / len, rest % len);
+            let loops = M::pow(&table.prod(p..p + len), q);
+            let res = M::binary_operation(&res, &loops);
+            M::binary_operation(&res, &table.prod(p..p + r))
+        }
     }
 }
 
@@ -248,6 +455,139 @@ mod tests {
         assert_eq!(fg.distance(0, 2), None);
     }
 
+    #[test]
+    fn test_functional_graph_kth_next_and_meeting() {
+        // 0 -> 1 -> 2 -> 0 (cycle)
+        // 3 -> 1
+        // 4 -> 3
+        // 6 -> 0
+        // 5 -> 5 (self-loop cycle)
+        let next = vec![1, 2, 0, 1, 3, 5, 0];
+        let fg = FunctionalGraph::new(&next);
+        assert_eq!(fg.kth_next(4, 0), 4);
+        assert_eq!(fg.kth_next(4, 1), 3);
+        assert_eq!(fg.kth_next(4, 2), 1);
+        assert_eq!(fg.kth_next(4, 3), 2);
+        assert_eq!(fg.kth_next(4, 1_000_000_000_000_000_000), 0);
+        assert_eq!(fg.kth_next(5, usize::MAX), 5);
+
+        assert_eq!(fg.first_meeting(4, 4), Some((0, 4)));
+        // 3 -> 1 -> 2, 6 -> 0 -> 1: 時刻 2 で 2 と 1 なので出会わない
+        assert_eq!(fg.first_meeting(3, 6), None);
+        // 4 -> 3 -> 1, 6 -> 0 -> 1
+        assert_eq!(fg.first_meeting(4, 6), Some((2, 1)));
+        assert_eq!(fg.first_meeting(3, 0), Some((1, 1)));
+        assert_eq!(fg.first_meeting(0, 5), None);
+    }
+
+    #[test]
+    fn test_functional_graph_sum() {
+        use ac_library::Additive;
+
+        let next = vec![1, 2, 0, 1, 3, 5, 0];
+        let vals = vec![1, 10, 100, 1000, 10000, 7, 100000];
+        let fgs = FunctionalGraphSum::<Additive<i64>>::new(&next, &vals);
+        assert_eq!(fgs.sum(4, 0), 0);
+        assert_eq!(fgs.sum(4, 2), 11000);
+        // 4, 3, 1, 2, 0, 1
+        assert_eq!(fgs.sum(4, 6), 11121);
+        assert_eq!(fgs.sum(0, 3 * 1000 + 1), 111 * 1000 + 1);
+        assert_eq!(fgs.sum(5, 1_000_000_000_000), 7_000_000_000_000);
+        assert_eq!(fgs.graph().kth_next(6, 2), 1);
+    }
+
+    #[test]
+    fn test_functional_graph_sum_affine() {
+        use crate::math::algebra::monoid::extend_acl_monoid::MonoidExtPow;
+        use crate::math::algebra::monoid::monoid_affine::{AffineComposition, AffineTransform};
+        use ac_library::{ModInt998244353 as Mint, Monoid};
+        type M = AffineComposition<Mint>;
+
+        // 0 -> 1 -> 2 -> 0 (cycle), 4 -> 3 -> 1
+        let next = vec![1, 2, 0, 1, 3];
+        let vals = (0..5)
+            .map(|i| AffineTransform::new(Mint::new(i + 2), Mint::new(i)))
+            .collect::<Vec<_>>();
+        let fgs = FunctionalGraphSum::<M>::new(&next, &vals);
+        let naive = |u: usize, k: usize| {
+            let mut v = u;
+            let mut res = M::identity();
+            for _ in 0..k {
+                res = M::binary_operation(&res, &vals[v]);
+                v = next[v];
+            }
+            res
+        };
+        for u in 0..5 {
+            for k in 0..20 {
+                assert_eq!(fgs.sum(u, k), naive(u, k));
+            }
+        }
+        // op(f_4, f_3, f_1) = f_4 ∘ f_3 ∘ f_1
+        let expected = vals[4].composite(&vals[3]).composite(&vals[1]);
+        assert_eq!(fgs.sum(4, 3), expected);
+        assert_eq!(
+            fgs.sum(2, 3 * 1_000_000_000 + 1),
+            M::binary_operation(&M::pow(&naive(2, 3), 1_000_000_000), &vals[2])
+        );
+    }
+
+    #[test]
+    #[ignore]
+    fn test_functional_graph_queries_random() {
+        use crate::math::algebra::monoid::monoid_affine::{AffineComposition, AffineTransform};
+        use ac_library::{Additive, ModInt998244353 as Mint, Monoid};
+        use rand::prelude::*;
+        type M = AffineComposition<Mint>;
+        let mut rng = StdRng::seed_from_u64(42);
+
+        for _ in 0..100 {
+            let nv = rng.random_range(1..=30);
+            let next = (0..nv).map(|_| rng.random_range(0..nv)).collect::<Vec<_>>();
+            let vals = (0..nv)
+                .map(|_| rng.random_range(-100..=100))
+                .collect::<Vec<i64>>();
+            let fgs = FunctionalGraphSum::<Additive<i64>>::new(&next, &vals);
+            let fg = fgs.graph();
+            let affine_vals = (0..nv)
+                .map(|_| {
+                    AffineTransform::new(
+                        Mint::new(rng.random_range(0..10)),
+                        Mint::new(rng.random_range(0..10)),
+                    )
+                })
+                .collect::<Vec<_>>();
+            let fgs_affine = FunctionalGraphSum::<M>::new(&next, &affine_vals);
+
+            for u in 0..nv {
+                let mut curr = u;
+                let mut sum = 0;
+                let mut prod = M::identity();
+                for k in 0..3 * nv {
+                    assert_eq!(fg.kth_next(u, k), curr);
+                    assert_eq!(fgs.sum(u, k), sum);
+                    assert_eq!(fgs_affine.sum(u, k), prod);
+                    sum += vals[curr];
+                    prod = M::binary_operation(&prod, &affine_vals[curr]);
+                    curr = next[curr];
+                }
+            }
+
+            for u in 0..nv {
+                for v in 0..nv {
+                    let (mut a, mut b) = (u, v);
+                    let expected = (0..2 * nv).find_map(|t| {
+                        let res = (a == b).then_some((t, a));
+                        a = next[a];
+                        b = next[b];
+                        res
+                    });
+                    assert_eq!(fg.first_meeting(u, v), expected);
+                }
+            }
+        }
+    }
+
     #[test]
     #[ignore]
     fn test_functional_graph_random() {
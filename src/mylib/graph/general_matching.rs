@@ -0,0 +1,237 @@
+use cargo_snippet::snippet;
+
+#[allow(clippy::module_inception)]
+#[snippet(prefix = "use general_matching::*;")]
+pub mod general_matching {
+    use std::collections::VecDeque;
+
+    struct Blossom {
+        adj: Vec<Vec<usize>>,
+        mate: Vec<usize>,
+        parent: Vec<usize>,
+        base: Vec<usize>,
+        used: Vec<bool>,
+        in_blossom: Vec<bool>,
+    }
+
+    impl Blossom {
+        /// 交互木における a と b の共通祖先のうち、最も近い花の底
+        fn lca(&self, mut a: usize, mut b: usize) -> usize {
+            let mut visited = vec![false; self.adj.len()];
+            loop {
+                a = self.base[a];
+                visited[a] = true;
+                if self.mate[a] == usize::MAX {
+                    break;
+                }
+                a = self.parent[self.mate[a]];
+            }
+            loop {
+                b = self.base[b];
+                if visited[b] {
+                    return b;
+                }
+                b = self.parent[self.mate[b]];
+            }
+        }
+
+        /// v から花の底 b までの頂点に印をつけ、親を付け替える
+        fn mark_path(&mut self, mut v: usize, b: usize, mut child: usize) {
+            while self.base[v] != b {
+                self.in_blossom[self.base[v]] = true;
+                self.in_blossom[self.base[self.mate[v]]] = true;
+                self.parent[v] = child;
+                child = self.mate[v];
+                v = self.parent[self.mate[v]];
+            }
+        }
+
+        /// root からの増加路を探し、見つかった場合は増加路の終点を返す
+        fn find_path(&mut self, root: usize) -> Option<usize> {
+            let nv = self.adj.len();
+            self.used = vec![false; nv];
+            self.parent = vec![usize::MAX; nv];
+            self.base = (0..nv).collect();
+
+            self.used[root] = true;
+            let mut queue = VecDeque::from([root]);
+            while let Some(v) = queue.pop_front() {
+                for i in 0..self.adj[v].len() {
+                    let to = self.adj[v][i];
+                    if self.base[v] == self.base[to] || self.mate[v] == to {
+                        continue;
+                    }
+                    if to == root
+                        || self.mate[to] != usize::MAX && self.parent[self.mate[to]] != usize::MAX
+                    {
+                        // 奇閉路 (花) を縮約する
+                        let cur_base = self.lca(v, to);
+                        self.in_blossom = vec![false; nv];
+                        self.mark_path(v, cur_base, to);
+                        self.mark_path(to, cur_base, v);
+                        for x in 0..nv {
+                            if self.in_blossom[self.base[x]] {
+                                self.base[x] = cur_base;
+                                if !self.used[x] {
+                                    self.used[x] = true;
+                                    queue.push_back(x);
+                                }
+                            }
+                        }
+                    } else if self.parent[to] == usize::MAX {
+                        self.parent[to] = v;
+                        if self.mate[to] == usize::MAX {
+                            return Some(to);
+                        }
+                        self.used[self.mate[to]] = true;
+                        queue.push_back(self.mate[to]);
+                    }
+                }
+            }
+            None
+        }
+    }
+
+    /// Edmonds の花アルゴリズムで一般グラフの最大マッチングを求める
+    ///
+    /// # Arguments
+    /// * `nv` - 頂点数
+    /// * `edges` - 無向辺 `(u, v)` のリスト
+    ///
+    /// # Returns
+    /// 各頂点のマッチ相手。マッチしていない頂点は `None`。
+    ///
+    /// # 計算量
+    /// O(V^3)
+    pub fn general_matching(nv: usize, edges: &[(usize, usize)]) -> Vec<Option<usize>> {
+        let mut adj = vec![vec![]; nv];
+        for &(u, v) in edges {
+            if u != v {
+                adj[u].push(v);
+                adj[v].push(u);
+            }
+        }
+        let mut blossom = Blossom {
+            adj,
+            mate: vec![usize::MAX; nv],
+            parent: vec![],
+            base: vec![],
+            used: vec![],
+            in_blossom: vec![],
+        };
+
+        // 貪欲に初期マッチングを作っておく
+        for u in 0..nv {
+            if blossom.mate[u] != usize::MAX {
+                continue;
+            }
+            if let Some(&v) = blossom.adj[u]
+                .iter()
+                .find(|&&v| blossom.mate[v] == usize::MAX)
+            {
+                blossom.mate[u] = v;
+                blossom.mate[v] = u;
+            }
+        }
+
+        for root in 0..nv {
+            if blossom.mate[root] != usize::MAX {
+                continue;
+            }
+            if let Some(mut v) = blossom.find_path(root) {
+                // 増加路に沿ってマッチングを反転する
+                while v != usize::MAX {
+                    let pv = blossom.parent[v];
+                    let ppv = blossom.mate[pv];
+                    blossom.mate[v] = pv;
+                    blossom.mate[pv] = v;
+                    v = ppv;
+                }
+            }
+        }
+
+        blossom
+            .mate
+            .into_iter()
+            .map(|m| (m != usize::MAX).then_some(m))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::general_matching::*;
+
+    fn matching_size(mate: &[Option<usize>]) -> usize {
+        mate.iter().filter(|m| m.is_some()).count() / 2
+    }
+
+    #[test]
+    fn test_general_matching() {
+        // 5 頂点の奇閉路 + 1 頂点
+        // 0 - 1 - 2 - 3 - 4 - 0, 4 - 5
+        let edges = [(0, 1), (1, 2), (2, 3), (3, 4), (4, 0), (4, 5)];
+        let mate = general_matching(6, &edges);
+        assert_eq!(matching_size(&mate), 3);
+        for (u, m) in mate.iter().enumerate() {
+            if let Some(v) = *m {
+                assert_eq!(mate[v], Some(u));
+            }
+        }
+
+        // 三角形
+        let mate = general_matching(3, &[(0, 1), (1, 2), (2, 0)]);
+        assert_eq!(matching_size(&mate), 1);
+
+        // 辺なし
+        assert_eq!(general_matching(2, &[]), vec![None, None]);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_general_matching_random() {
+        use rand::prelude::*;
+
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..300 {
+            let nv = rng.random_range(1..=12);
+            let mut edges = vec![];
+            for u in 0..nv {
+                for v in u + 1..nv {
+                    if rng.random_bool(0.25) {
+                        edges.push((u, v));
+                    }
+                }
+            }
+
+            // dp[S]: 頂点集合 S の中での最大マッチング
+            let mut dp = vec![0_usize; 1 << nv];
+            for s in 1_usize..1 << nv {
+                let u = s.trailing_zeros() as usize;
+                let rest = s & !(1 << u);
+                dp[s] = dp[rest];
+                for &(a, b) in &edges {
+                    let other = if a == u {
+                        b
+                    } else if b == u {
+                        a
+                    } else {
+                        continue;
+                    };
+                    if rest >> other & 1 == 1 {
+                        dp[s] = dp[s].max(dp[rest & !(1 << other)] + 1);
+                    }
+                }
+            }
+
+            let mate = general_matching(nv, &edges);
+            assert_eq!(matching_size(&mate), dp[(1 << nv) - 1]);
+            for (u, m) in mate.iter().enumerate() {
+                if let Some(v) = *m {
+                    assert_eq!(mate[v], Some(u));
+                    assert!(edges.contains(&(u.min(v), u.max(v))));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,354 @@
+use crate::data_structure::dsu::grid_dsu::grid_dsu::GridDsu;
+use crate::data_structure::ix::Bounds;
+use crate::math::geometry::grid::grid_template::Grid;
+use crate::math::geometry::pos::pos::{DIR4_LIST, DIR8_LIST, Pos};
+use cargo_snippet::snippet;
+
+#[allow(clippy::module_inception)]
+#[snippet(
+    prefix = "use grid_graph::*;",
+    include = "Grid, pos, vec_vec_at, ix, pos_ix, grid_dsu"
+)]
+pub mod grid_graph {
+    use super::{Bounds, DIR4_LIST, DIR8_LIST, Grid, GridDsu, Pos};
+    use std::collections::HashMap;
+
+    /// ナイトの移動方向
+    pub const KNIGHT_LIST: [Pos; 8] = [
+        Pos { x: 1, y: 2 },
+        Pos { x: 2, y: 1 },
+        Pos { x: 2, y: -1 },
+        Pos { x: 1, y: -2 },
+        Pos { x: -1, y: -2 },
+        Pos { x: -2, y: -1 },
+        Pos { x: -2, y: 1 },
+        Pos { x: -1, y: 2 },
+    ];
+
+    /// グリッドをグラフとして扱うためのアダプタ
+    ///
+    /// 各メソッドは隣接頂点のイテレータを返すので、`bfs_arbitrary` / `bfs01_arbitrary` / `dijkstra_arbitrary` などに
+    /// `|p| gg.neighbors4(p)` のようなクロージャーとしてそのまま渡せる。頂点の範囲は [`GridGraph::bounds`] で得られる。
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let grid = Grid::new(vec!["S.#".chars().collect(), "..G".chars().collect()]);
+    /// let gg = GridGraph::new(&grid, |c| c != '#');
+    /// let start = grid.find_pos_of('S').unwrap();
+    /// let dist = bfs_arbitrary(gg.bounds(), |p| gg.neighbors4(p), [start]);
+    /// assert_eq!(dist[grid.find_pos_of('G').unwrap()], Some(3));
+    /// ```
+    #[derive(Clone, Debug)]
+    pub struct GridGraph {
+        grid: Grid,
+        passable: Vec<Vec<bool>>,
+    }
+
+    impl GridGraph {
+        /// グリッドと、通行可能なマスの文字を判定する関数から構築する
+        ///
+        /// # 計算量
+        /// O(HW)
+        pub fn new(grid: &Grid, is_passable: impl Fn(char) -> bool) -> Self {
+            let passable = grid
+                .grid
+                .iter()
+                .map(|row| row.iter().map(|&c| is_passable(c)).collect())
+                .collect();
+            Self {
+                grid: grid.clone(),
+                passable,
+            }
+        }
+
+        /// 元のグリッドを返す
+        pub fn grid(&self) -> &Grid {
+            &self.grid
+        }
+
+        /// 全マスを表す頂点の範囲を返す
+        pub fn bounds(&self) -> Bounds<Pos> {
+            Bounds::new(
+                Pos::new(0, 0),
+                Pos::new(self.grid.w as i64 - 1, self.grid.h as i64 - 1),
+            )
+        }
+
+        /// 各マスに `layers` 個の状態 (向き、使った回数など) を持たせたときの頂点の範囲を返す
+        pub fn bounds_layered(&self, layers: usize) -> Bounds<(Pos, usize)> {
+            let bounds = self.bounds();
+            Bounds::new((bounds.min, 0), (bounds.max, layers - 1))
+        }
+
+        /// `pos` がグリッド内の通行可能なマスかどうか
+        pub fn is_passable(&self, pos: Pos) -> bool {
+            self.grid.is_within(pos) && self.passable[pos.y as usize][pos.x as usize]
+        }
+
+        /// `pos` から `dirs` の各方向に 1 歩進んだ先のうち、通行可能なマスを返す
+        pub fn neighbors_by<'a>(
+            &'a self,
+            pos: Pos,
+            dirs: &'a [Pos],
+        ) -> impl Iterator<Item = Pos> + 'a {
+            dirs.iter()
+                .map(move |&d| pos + d)
+                .filter(move |&next| self.is_passable(next))
+        }
+
+        /// 上下左右の通行可能なマスを返す
+        pub fn neighbors4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+            self.neighbors_by(pos, &DIR4_LIST)
+        }
+
+        /// 周囲 8 マスのうち通行可能なマスを返す
+        pub fn neighbors8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+            self.neighbors_by(pos, &DIR8_LIST)
+        }
+
+        /// ナイトの移動先のうち通行可能なマスを返す
+        pub fn knight_neighbors(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+            self.neighbors_by(pos, &KNIGHT_LIST)
+        }
+
+        /// 上下左右の各方向に、通行不可能なマス (またはグリッドの外) の手前まで滑った先のマスを返す
+        ///
+        /// 1 マスも動けない方向は含めない。
+        ///
+        /// # 計算量
+        /// O(H + W)
+        pub fn slide_neighbors(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+            DIR4_LIST.iter().filter_map(move |&d| {
+                let mut cur = pos;
+                while self.is_passable(cur + d) {
+                    cur += d;
+                }
+                (cur != pos).then_some(cur)
+            })
+        }
+
+        /// 上下左右のマスと、`cost(from, to)` で与えられる移動コストの組を返す
+        ///
+        /// マスごとのコストは `|_, to| cost_of[to]` のように指定する。
+        pub fn weighted_neighbors4<'a>(
+            &'a self,
+            pos: Pos,
+            cost: impl Fn(Pos, Pos) -> i64 + 'a,
+        ) -> impl Iterator<Item = (Pos, i64)> + 'a {
+            self.neighbors4(pos)
+                .map(move |next| (next, cost(pos, next)))
+        }
+
+        /// テレポーター (同じ文字のマス同士を行き来できる) のグループを求める
+        ///
+        /// # Arguments
+        /// * `is_teleporter` - テレポーターのマスの文字かどうかを判定する関数
+        ///
+        /// # Returns
+        /// 文字ごとの、その文字が書かれた通行可能なマスのリスト
+        pub fn teleporters(&self, is_teleporter: impl Fn(char) -> bool) -> Teleporters {
+            let mut groups: HashMap<char, Vec<Pos>> = HashMap::new();
+            for pos in self.grid.all_pos_iter() {
+                let c = self.grid[pos];
+                if is_teleporter(c) && self.is_passable(pos) {
+                    groups.entry(c).or_default().push(pos);
+                }
+            }
+            Teleporters { groups }
+        }
+
+        /// 状態 `(pos, dir)` (dir は `DIR4_LIST` の添字) について、
+        /// 向いている方向へ 1 歩進む (コスト 0) か、その場で向きを変える (コスト 1) 遷移を返す
+        ///
+        /// 「曲がった回数の最小値」を `bfs01_arbitrary` と [`GridGraph::bounds_layered`]`(4)` で求めるのに使う。
+        pub fn turn_neighbors(
+            &self,
+            (pos, dir): (Pos, usize),
+        ) -> impl Iterator<Item = ((Pos, usize), i64)> + '_ {
+            let forward = pos + DIR4_LIST[dir];
+            let straight = self.is_passable(forward).then_some(((forward, dir), 0));
+            let turns = (0..4)
+                .filter(move |&d| d != dir)
+                .map(move |d| ((pos, d), 1));
+            straight.into_iter().chain(turns)
+        }
+
+        /// 状態 `(pos, k)` (k は壊した壁の個数) について、上下左右への遷移を返す
+        ///
+        /// 通行可能なマスへは k のまま、グリッド内の通行不可能なマスへは `k < max_k` なら k + 1 で移動する。
+        /// [`GridGraph::bounds_layered`]`(max_k + 1)` と組み合わせて使う。
+        pub fn wall_break_neighbors(
+            &self,
+            (pos, k): (Pos, usize),
+            max_k: usize,
+        ) -> impl Iterator<Item = (Pos, usize)> + '_ {
+            DIR4_LIST.iter().filter_map(move |&d| {
+                let next = pos + d;
+                if self.is_passable(next) {
+                    Some((next, k))
+                } else if self.grid.is_within(next) && k < max_k {
+                    Some((next, k + 1))
+                } else {
+                    None
+                }
+            })
+        }
+
+        /// 上下左右に隣接する通行可能なマス同士をマージした [`GridDsu`] を返す
+        ///
+        /// # 計算量
+        /// O(HW α(HW))
+        pub fn to_dsu(&self) -> GridDsu {
+            let mut dsu = GridDsu::new(self.grid.h, self.grid.w);
+            for pos in self.grid.all_pos_iter() {
+                if !self.is_passable(pos) {
+                    continue;
+                }
+                // 右と下だけ見れば十分
+                for d in [Pos::new(1, 0), Pos::new(0, 1)] {
+                    if self.is_passable(pos + d) {
+                        dsu.merge(pos, pos + d);
+                    }
+                }
+            }
+            dsu
+        }
+    }
+
+    /// 同じ文字のマス同士を行き来できるテレポーター
+    #[derive(Clone, Debug)]
+    pub struct Teleporters {
+        groups: HashMap<char, Vec<Pos>>,
+    }
+
+    impl Teleporters {
+        /// 文字 `c` のテレポーターのマスのリストを返す
+        pub fn group(&self, c: char) -> &[Pos] {
+            self.groups.get(&c).map_or(&[], |g| g.as_slice())
+        }
+
+        /// `pos` (文字 `c` のマス) からテレポートできるマス (自分自身を除く) を返す
+        ///
+        /// すべてのマスからこれを辺として使うと辺の数がグループの大きさの 2 乗になることに注意。
+        /// 各文字を 1 回だけ使えばよい問題では、使った文字を記録して 2 回目以降は空にするとよい。
+        pub fn neighbors(&self, pos: Pos, c: char) -> impl Iterator<Item = Pos> + '_ {
+            self.group(c).iter().copied().filter(move |&p| p != pos)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::grid_graph::*;
+    use crate::graph::bfs::bfs_ix::bfs_arbitrary;
+    use crate::graph::bfs01::bfs01_ix::bfs01_arbitrary;
+    use crate::graph::dijkstra::dijkstra_ix::dijkstra_arbitrary;
+    use crate::math::geometry::grid::grid_template::Grid;
+    use crate::math::geometry::pos::pos::Pos;
+
+    fn make_grid(rows: &[&str]) -> Grid {
+        Grid::new(rows.iter().map(|row| row.chars().collect()).collect())
+    }
+
+    #[test]
+    fn test_grid_graph_neighbors() {
+        let grid = make_grid(&["S.#", "..G"]);
+        let gg = GridGraph::new(&grid, |c| c != '#');
+        let start = grid.find_pos_of('S').unwrap();
+        let goal = grid.find_pos_of('G').unwrap();
+
+        let dist = bfs_arbitrary(gg.bounds(), |p| gg.neighbors4(p), [start]);
+        assert_eq!(dist[goal], Some(3));
+        let dist = bfs_arbitrary(gg.bounds(), |p| gg.neighbors8(p), [start]);
+        assert_eq!(dist[goal], Some(2));
+        assert_eq!(dist[Pos::new(2, 0)], None);
+
+        let mut knight = gg.knight_neighbors(Pos::new(0, 0)).collect::<Vec<_>>();
+        knight.sort();
+        assert_eq!(knight, vec![Pos::new(2, 1)]);
+
+        let costs = gg
+            .weighted_neighbors4(start, |_, to| if to.x == 1 { 10 } else { 1 })
+            .collect::<Vec<_>>();
+        assert_eq!(costs.len(), 2);
+        assert!(costs.contains(&(Pos::new(1, 0), 10)));
+        assert!(costs.contains(&(Pos::new(0, 1), 1)));
+        let dist = dijkstra_arbitrary(
+            gg.bounds(),
+            |p| gg.weighted_neighbors4(p, |_, to| if to.x == 1 { 10 } else { 1 }),
+            [start],
+        );
+        assert_eq!(dist[goal], Some(12));
+    }
+
+    #[test]
+    fn test_grid_graph_slide() {
+        // 氷の上を滑る
+        let grid = make_grid(&["S...", ".#..", "...#"]);
+        let gg = GridGraph::new(&grid, |c| c != '#');
+        let mut slides = gg.slide_neighbors(Pos::new(0, 0)).collect::<Vec<_>>();
+        slides.sort();
+        assert_eq!(slides, vec![Pos::new(0, 2), Pos::new(3, 0)]);
+        let dist = bfs_arbitrary(gg.bounds(), |p| gg.slide_neighbors(p), [Pos::new(0, 0)]);
+        // (0, 0) -> (0, 2) -> (2, 2)
+        assert_eq!(dist[Pos::new(2, 2)], Some(2));
+        assert_eq!(dist[Pos::new(1, 0)], None);
+    }
+
+    #[test]
+    fn test_grid_graph_teleporters() {
+        let grid = make_grid(&["Sa#a", "###.", "b.#G"]);
+        let gg = GridGraph::new(&grid, |c| c != '#');
+        let tp = gg.teleporters(|c| c.is_ascii_lowercase());
+        assert_eq!(tp.group('a'), &[Pos::new(1, 0), Pos::new(3, 0)]);
+        assert_eq!(tp.group('z'), &[]);
+
+        let dist = bfs_arbitrary(
+            gg.bounds(),
+            |p| {
+                let c = grid[p];
+                gg.neighbors4(p).chain(tp.neighbors(p, c))
+            },
+            [grid.find_pos_of('S').unwrap()],
+        );
+        // S -> a -> a -> . -> G
+        assert_eq!(dist[grid.find_pos_of('G').unwrap()], Some(4));
+        assert_eq!(dist[Pos::new(0, 2)], None);
+    }
+
+    #[test]
+    fn test_grid_graph_layered() {
+        let grid = make_grid(&["S..", "##.", "G.."]);
+        let gg = GridGraph::new(&grid, |c| c != '#');
+        let start = grid.find_pos_of('S').unwrap();
+        let goal = grid.find_pos_of('G').unwrap();
+
+        // 曲がる回数の最小値 (最初の向きは自由)
+        let dist = bfs01_arbitrary(
+            gg.bounds_layered(4),
+            |s| gg.turn_neighbors(s),
+            (0..4).map(|d| (start, d)),
+        );
+        let min_turns = (0..4).filter_map(|d| dist[(goal, d)]).min();
+        assert_eq!(min_turns, Some(2));
+
+        // 壁を 1 回まで壊してよい
+        let dist = bfs_arbitrary(
+            gg.bounds_layered(2),
+            |s| gg.wall_break_neighbors(s, 1),
+            [(start, 0)],
+        );
+        assert_eq!(dist[(goal, 0)], Some(6));
+        assert_eq!(dist[(goal, 1)], Some(2));
+    }
+
+    #[test]
+    fn test_grid_graph_to_dsu() {
+        let grid = make_grid(&["..#", "#.#", "#.."]);
+        let gg = GridGraph::new(&grid, |c| c == '.');
+        let mut dsu = gg.to_dsu();
+        assert!(dsu.same(Pos::new(0, 0), Pos::new(2, 2)));
+        assert_eq!(dsu.size(Pos::new(0, 0)), 5);
+        assert!(!dsu.same(Pos::new(2, 0), Pos::new(0, 0)));
+    }
+}
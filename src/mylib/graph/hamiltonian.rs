@@ -0,0 +1,204 @@
+use crate::data_structure::bitset::bitset::BitSet;
+use cargo_snippet::snippet;
+
+#[allow(clippy::module_inception)]
+#[snippet(prefix = "use hamiltonian::*;", include = "bitset")]
+pub mod hamiltonian {
+    use super::BitSet;
+
+    /// 逆向きの隣接集合を求める
+    fn reverse_adj(adj: &[BitSet]) -> Vec<usize> {
+        let n = adj.len();
+        let mut radj = vec![0; n];
+        for u in 0..n {
+            for v in adj[u].to_iter(n) {
+                radj[v] |= 1 << u;
+            }
+        }
+        radj
+    }
+
+    /// `ends[s]` (頂点集合 s をちょうど 1 回ずつ通るパスの終点の集合) から、終点 `last` のパスを復元する
+    fn restore(ends: &[usize], radj: &[usize], mut s: usize, mut last: usize) -> Vec<usize> {
+        let mut path = vec![last];
+        while s.count_ones() > 1 {
+            s &= !(1 << last);
+            last = (ends[s] & radj[last]).trailing_zeros() as usize;
+            path.push(last);
+        }
+        path.reverse();
+        path
+    }
+
+    /// ハミルトンパス (すべての頂点をちょうど 1 回ずつ通るパス) を求める
+    ///
+    /// `ends[S]` = 「頂点集合 S をちょうど 1 回ずつ通るパスの終点の集合」をビット DP で求める。
+    ///
+    /// # Arguments
+    /// * `adj` - `adj[u]` は辺 `u -> v` が存在する頂点 `v` の集合 (無向グラフの場合は両向きの辺を入れる)
+    ///
+    /// # Returns
+    /// ハミルトンパスの頂点列。存在しない場合は `None`。頂点数が 0 の場合は `Some(vec![])`。
+    ///
+    /// # 計算量
+    /// O(2^n n) (n は頂点数)
+    pub fn hamiltonian_path(adj: &[BitSet]) -> Option<Vec<usize>> {
+        let n = adj.len();
+        if n == 0 {
+            return Some(vec![]);
+        }
+        let radj = reverse_adj(adj);
+        let mut ends = vec![0_usize; 1 << n];
+        for v in 0..n {
+            ends[1 << v] = 1 << v;
+        }
+        for s in 1_usize..1 << n {
+            if s.count_ones() == 1 {
+                continue;
+            }
+            let mut rest = s;
+            while rest != 0 {
+                let v = rest.trailing_zeros() as usize;
+                rest &= rest - 1;
+                if ends[s & !(1 << v)] & radj[v] != 0 {
+                    ends[s] |= 1 << v;
+                }
+            }
+        }
+        let full = (1 << n) - 1;
+        if ends[full] == 0 {
+            return None;
+        }
+        let last = ends[full].trailing_zeros() as usize;
+        Some(restore(&ends, &radj, full, last))
+    }
+
+    /// ハミルトン閉路 (すべての頂点をちょうど 1 回ずつ通る閉路) を求める
+    ///
+    /// 頂点 0 を始点に固定して、`ends[S]` = 「頂点 0 から始まり頂点集合 S をちょうど 1 回ずつ通るパスの終点の集合」をビット DP で求める。
+    ///
+    /// # Arguments
+    /// * `adj` - `adj[u]` は辺 `u -> v` が存在する頂点 `v` の集合 (無向グラフの場合は両向きの辺を入れる)
+    ///
+    /// # Returns
+    /// 頂点 0 から始まるハミルトン閉路の頂点列 (最後の頂点から頂点 0 への辺で閉じる)。存在しない場合は `None`。
+    /// 頂点数が 1 の場合は自己ループ、2 の場合は `0 -> 1 -> 0` の辺があれば閉路とみなす
+    /// (無向グラフでは 1 本の辺を往復するものも閉路とみなすことに注意)。
+    ///
+    /// # 計算量
+    /// O(2^n n) (n は頂点数)
+    pub fn hamiltonian_cycle(adj: &[BitSet]) -> Option<Vec<usize>> {
+        let n = adj.len();
+        if n == 0 {
+            return None;
+        }
+        let radj = reverse_adj(adj);
+        let mut ends = vec![0_usize; 1 << n];
+        ends[1] = 1;
+        for s in (3_usize..1 << n).step_by(2) {
+            let mut rest = s & !1;
+            while rest != 0 {
+                let v = rest.trailing_zeros() as usize;
+                rest &= rest - 1;
+                if ends[s & !(1 << v)] & radj[v] != 0 {
+                    ends[s] |= 1 << v;
+                }
+            }
+        }
+        let full = (1 << n) - 1;
+        let closing = ends[full] & radj[0];
+        if closing == 0 {
+            return None;
+        }
+        let last = closing.trailing_zeros() as usize;
+        Some(restore(&ends, &radj, full, last))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::hamiltonian::*;
+    use crate::data_structure::bitset::bitset::BitSet;
+
+    fn build_adj(n: usize, edges: &[(usize, usize)]) -> Vec<BitSet> {
+        let mut adj = vec![BitSet::empty(); n];
+        for &(u, v) in edges {
+            adj[u] = adj[u].inserted(v);
+        }
+        adj
+    }
+
+    #[test]
+    fn test_hamiltonian() {
+        // 0 -> 1 -> 2 -> 3, 3 -> 1
+        let adj = build_adj(4, &[(0, 1), (1, 2), (2, 3), (3, 1)]);
+        assert_eq!(hamiltonian_path(&adj), Some(vec![0, 1, 2, 3]));
+        assert_eq!(hamiltonian_cycle(&adj), None);
+
+        // 3 -> 0 を追加すると閉路になる
+        let adj = build_adj(4, &[(0, 1), (1, 2), (2, 3), (3, 1), (3, 0)]);
+        assert_eq!(hamiltonian_cycle(&adj), Some(vec![0, 1, 2, 3]));
+
+        // 星型の無向グラフにはハミルトンパスがない
+        let adj = build_adj(4, &[(0, 1), (1, 0), (0, 2), (2, 0), (0, 3), (3, 0)]);
+        assert_eq!(hamiltonian_path(&adj), None);
+
+        assert_eq!(hamiltonian_path(&[]), Some(vec![]));
+        assert_eq!(hamiltonian_path(&build_adj(1, &[])), Some(vec![0]));
+        assert_eq!(hamiltonian_cycle(&build_adj(1, &[])), None);
+        assert_eq!(hamiltonian_cycle(&build_adj(1, &[(0, 0)])), Some(vec![0]));
+    }
+
+    #[test]
+    #[ignore]
+    fn test_hamiltonian_random() {
+        use itertools::Itertools;
+        use rand::prelude::*;
+
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..300 {
+            let n = rng.random_range(1..=7);
+            let p = rng.random_range(0.0..1.0);
+            let mut edges = vec![];
+            for u in 0..n {
+                for v in 0..n {
+                    if u != v && rng.random_bool(p) {
+                        edges.push((u, v));
+                    }
+                }
+            }
+            let adj = build_adj(n, &edges);
+            let is_path = |path: &[usize]| path.windows(2).all(|w| adj[w[0]].contains(w[1]));
+
+            let path_exists = (0..n).permutations(n).any(|perm| is_path(&perm));
+            match hamiltonian_path(&adj) {
+                Some(path) => {
+                    assert!(path_exists);
+                    assert_eq!(
+                        path.iter().copied().sorted().collect_vec(),
+                        (0..n).collect_vec()
+                    );
+                    assert!(is_path(&path));
+                }
+                None => assert!(!path_exists),
+            }
+
+            let cycle_exists = (0..n)
+                .permutations(n)
+                .any(|perm| perm[0] == 0 && is_path(&perm) && adj[perm[n - 1]].contains(0));
+            match hamiltonian_cycle(&adj) {
+                Some(cycle) => {
+                    assert!(cycle_exists);
+                    assert_eq!(cycle[0], 0);
+                    assert_eq!(
+                        cycle.iter().copied().sorted().collect_vec(),
+                        (0..n).collect_vec()
+                    );
+                    assert!(is_path(&cycle));
+                    assert!(adj[cycle[n - 1]].contains(0));
+                }
+                None => assert!(!cycle_exists),
+            }
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use cargo_snippet::snippet;
+
+#[allow(clippy::module_inception)]
+#[snippet(prefix = "use hungarian::*;")]
+pub mod hungarian {
+    /// ハンガリアン法で割当問題 (重み付き二部グラフの最小重み最大マッチング) を解く
+    ///
+    /// `n × m` のコスト行列に対して、`min(n, m)` 個の行と列を 1 対 1 に対応させたときのコストの和の最小値を求める。
+    /// 最大化したい場合はコストの符号を反転させる。
+    ///
+    /// # Arguments
+    /// * `cost` - `cost[i][j]` は行 `i` を列 `j` に割り当てるときのコスト。すべての行は同じ長さであること。
+    ///
+    /// # Returns
+    /// `(コストの和の最小値, 各行に割り当てた列)`。`n > m` の場合は割り当てられない行が `None` になる。
+    ///
+    /// # 計算量
+    /// O(n^2 m) (n ≤ m の場合。n > m の場合は転置して計算する)
+    pub fn hungarian(cost: &[Vec<i64>]) -> (i64, Vec<Option<usize>>) {
+        let n = cost.len();
+        if n == 0 {
+            return (0, vec![]);
+        }
+        let m = cost[0].len();
+        if n > m {
+            let transposed = (0..m)
+                .map(|j| (0..n).map(|i| cost[i][j]).collect::<Vec<_>>())
+                .collect::<Vec<_>>();
+            let (total, col_assign) = hungarian(&transposed);
+            let mut row_assign = vec![None; n];
+            for (j, i) in col_assign.into_iter().enumerate() {
+                row_assign[i.unwrap()] = Some(j);
+            }
+            return (total, row_assign);
+        }
+
+        // 1-indexed で計算する。列 0 は番兵。
+        // u, v: 行・列のポテンシャル, p[j]: 列 j に割り当てた行
+        let mut u = vec![0_i64; n + 1];
+        let mut v = vec![0_i64; m + 1];
+        let mut p = vec![0_usize; m + 1];
+        let mut way = vec![0_usize; m + 1];
+        for i in 1..=n {
+            p[0] = i;
+            let mut j0 = 0;
+            let mut min_v = vec![i64::MAX; m + 1];
+            let mut used = vec![false; m + 1];
+            loop {
+                used[j0] = true;
+                let i0 = p[j0];
+                let mut delta = i64::MAX;
+                let mut j1 = 0;
+                for j in 1..=m {
+                    if used[j] {
+                        continue;
+                    }
+                    let cur = cost[i0 - 1][j - 1] - u[i0] - v[j];
+                    if cur < min_v[j] {
+                        min_v[j] = cur;
+                        way[j] = j0;
+                    }
+                    if min_v[j] < delta {
+                        delta = min_v[j];
+                        j1 = j;
+                    }
+                }
+                for j in 0..=m {
+                    if used[j] {
+                        u[p[j]] += delta;
+                        v[j] -= delta;
+                    } else {
+                        min_v[j] -= delta;
+                    }
+                }
+                j0 = j1;
+                if p[j0] == 0 {
+                    break;
+                }
+            }
+            // 増加路に沿って割り当てを更新する
+            loop {
+                let j1 = way[j0];
+                p[j0] = p[j1];
+                j0 = j1;
+                if j0 == 0 {
+                    break;
+                }
+            }
+        }
+
+        let mut row_assign = vec![None; n];
+        for j in 1..=m {
+            if p[j] != 0 {
+                row_assign[p[j] - 1] = Some(j - 1);
+            }
+        }
+        let total = (0..n).map(|i| cost[i][row_assign[i].unwrap()]).sum();
+        (total, row_assign)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::hungarian::*;
+
+    #[test]
+    fn test_hungarian() {
+        let cost = vec![vec![4, 1, 3], vec![2, 0, 5], vec![3, 2, 2]];
+        assert_eq!(hungarian(&cost), (5, vec![Some(1), Some(0), Some(2)]));
+
+        // 横長
+        let cost = vec![vec![5, 1, 9, 9], vec![1, 5, 9, 9]];
+        assert_eq!(hungarian(&cost), (2, vec![Some(1), Some(0)]));
+
+        // 縦長
+        let cost = vec![vec![5, 1], vec![9, 9], vec![1, 5]];
+        assert_eq!(hungarian(&cost), (2, vec![Some(1), None, Some(0)]));
+
+        // 負のコスト (最大化)
+        let cost = vec![vec![-3, -1], vec![-2, -4]];
+        assert_eq!(hungarian(&cost), (-7, vec![Some(0), Some(1)]));
+
+        assert_eq!(hungarian(&[]), (0, vec![]));
+    }
+
+    #[test]
+    #[ignore]
+    fn test_hungarian_random() {
+        use itertools::Itertools;
+        use rand::prelude::*;
+
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..300 {
+            let n = rng.random_range(1..=5);
+            let m = rng.random_range(1..=5);
+            let cost = (0..n)
+                .map(|_| {
+                    (0..m)
+                        .map(|_| rng.random_range(-20..=20))
+                        .collect::<Vec<i64>>()
+                })
+                .collect::<Vec<_>>();
+
+            let expected = if n <= m {
+                (0..m)
+                    .permutations(n)
+                    .map(|cols| (0..n).map(|i| cost[i][cols[i]]).sum::<i64>())
+                    .min()
+                    .unwrap()
+            } else {
+                (0..n)
+                    .permutations(m)
+                    .map(|rows| (0..m).map(|j| cost[rows[j]][j]).sum::<i64>())
+                    .min()
+                    .unwrap()
+            };
+
+            let (total, assign) = hungarian(&cost);
+            assert_eq!(total, expected);
+
+            // 割り当てが 1 対 1 で、コストの和が total になっている
+            let cols = assign.iter().flatten().copied().collect::<Vec<_>>();
+            assert_eq!(cols.len(), n.min(m));
+            assert!(cols.iter().all_unique());
+            let sum = (0..n)
+                .filter_map(|i| assign[i].map(|j| cost[i][j]))
+                .sum::<i64>();
+            assert_eq!(sum, total);
+        }
+    }
+}
@@ -0,0 +1,318 @@
+use cargo_snippet::snippet;
+
+use crate::graph::dijkstra::dijkstra::dijkstra_with_restore;
+
+#[allow(clippy::module_inception)]
+#[snippet(prefix = "use k_shortest_paths::*;", include = "dijkstra")]
+pub mod k_shortest_paths {
+    use super::dijkstra_with_restore;
+    use std::cmp::Reverse;
+    use std::collections::{BTreeSet, BinaryHeap, HashSet};
+
+    /// `s` から `t` へのウォーク (同じ頂点や辺を何度通ってもよい) の長さを短い順に `k` 個求める
+    ///
+    /// 各頂点を `k` 回まで確定させるダイクストラ法。
+    ///
+    /// # Arguments
+    /// * `nv` - 頂点数
+    /// * `adj` - 頂点を受け取り、隣接する頂点とそのコストのペアのイテレータを返す `usize -> impl IntoIterator<Item = (usize, i64)>` のクロージャー。コストは非負
+    /// * `s` - 始点
+    /// * `t` - 終点
+    /// * `k` - 求める個数
+    ///
+    /// # Returns
+    /// ウォークの長さを昇順に並べたもの。ウォークが `k` 個未満の場合はすべて返す。
+    ///
+    /// # 計算量
+    /// O(k E log (kE))
+    pub fn k_shortest_walks<F, It>(nv: usize, mut adj: F, s: usize, t: usize, k: usize) -> Vec<i64>
+    where
+        F: FnMut(usize) -> It,
+        It: IntoIterator<Item = (usize, i64)>,
+    {
+        let mut cnt = vec![0; nv];
+        let mut res = vec![];
+        let mut pq = BinaryHeap::new();
+        pq.push(Reverse((0, s)));
+        while let Some(Reverse((d, u))) = pq.pop() {
+            if cnt[u] >= k {
+                continue;
+            }
+            cnt[u] += 1;
+            if u == t {
+                res.push(d);
+                if res.len() == k {
+                    break;
+                }
+            }
+            for (v, cost) in adj(u) {
+                assert!(cost >= 0, "cost must be non-negative");
+                if cnt[v] < k {
+                    pq.push(Reverse((d + cost, v)));
+                }
+            }
+        }
+        res
+    }
+
+    /// Yen のアルゴリズムで `s` から `t` への単純パス (同じ頂点を 2 回以上通らないパス) を短い順に `k` 個求める
+    ///
+    /// 多重辺がある場合は、頂点列が同じパスは 1 つとみなす (最もコストの小さい辺を使う)。
+    ///
+    /// # Arguments
+    /// * `nv` - 頂点数
+    /// * `adj` - 頂点を受け取り、隣接する頂点とそのコストのペアのイテレータを返す `usize -> impl IntoIterator<Item = (usize, i64)>` のクロージャー。コストは非負
+    /// * `s` - 始点
+    /// * `t` - 終点
+    /// * `k` - 求める個数
+    ///
+    /// # Returns
+    /// `(パスの長さ, パスの頂点列)` を長さの昇順に並べたもの。単純パスが `k` 個未満の場合はすべて返す。
+    ///
+    /// # 計算量
+    /// O(kV (V + E) log V)
+    pub fn k_shortest_simple_paths<F, It>(
+        nv: usize,
+        mut adj: F,
+        s: usize,
+        t: usize,
+        k: usize,
+    ) -> Vec<(i64, Vec<usize>)>
+    where
+        F: FnMut(usize) -> It,
+        It: IntoIterator<Item = (usize, i64)>,
+    {
+        let adj = (0..nv)
+            .map(|u| adj(u).into_iter().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let edge_cost = |u: usize, v: usize| {
+            adj[u]
+                .iter()
+                .filter(|&&(to, _)| to == v)
+                .map(|&(_, c)| c)
+                .min()
+                .unwrap()
+        };
+        let path_cost =
+            |path: &[usize]| path.windows(2).map(|w| edge_cost(w[0], w[1])).sum::<i64>();
+
+        // 除外する頂点と辺を指定して、from から t への最短路を求める
+        let shortest = |from: usize,
+                        removed_vertices: &[bool],
+                        removed_edges: &HashSet<(usize, usize)>|
+         -> Option<Vec<usize>> {
+            let res = dijkstra_with_restore(
+                nv,
+                |u| {
+                    adj[u].iter().copied().filter(move |&(v, _)| {
+                        !removed_vertices[v] && !removed_edges.contains(&(u, v))
+                    })
+                },
+                [from],
+            );
+            res.restore(t)
+        };
+
+        let mut found: Vec<(i64, Vec<usize>)> = vec![];
+        let Some(first) = shortest(s, &vec![false; nv], &HashSet::new()) else {
+            return found;
+        };
+        let mut candidates = BTreeSet::new();
+        candidates.insert((path_cost(&first), first));
+
+        while found.len() < k {
+            let Some(next) = candidates.pop_first() else {
+                break;
+            };
+            found.push(next);
+            let prev_path = &found.last().unwrap().1;
+
+            for i in 0..prev_path.len() - 1 {
+                let spur = prev_path[i];
+                let root = &prev_path[..=i];
+
+                let mut removed_edges = HashSet::new();
+                for (_, path) in &found {
+                    if path.len() > i + 1 && &path[..=i] == root {
+                        removed_edges.insert((path[i], path[i + 1]));
+                    }
+                }
+                let mut removed_vertices = vec![false; nv];
+                for &v in &root[..i] {
+                    removed_vertices[v] = true;
+                }
+
+                if let Some(spur_path) = shortest(spur, &removed_vertices, &removed_edges) {
+                    let mut path = root[..i].to_vec();
+                    path.extend(spur_path);
+                    let cost = path_cost(&path);
+                    candidates.insert((cost, path));
+                }
+            }
+        }
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::k_shortest_paths::*;
+
+    #[test]
+    fn test_k_shortest_walks() {
+        // 0 -(1)-> 1 -(1)-> 2
+        // 0 -(3)-> 2
+        // 2 -(1)-> 0
+        let adj = [vec![(1, 1), (2, 3)], vec![(2, 1)], vec![(0, 1)]];
+        let res = k_shortest_walks(3, |u| adj[u].iter().copied(), 0, 2, 5);
+        // 0-1-2, 0-2, 0-1-2-0-1-2, 0-1-2-0-2 / 0-2-0-1-2, 0-2-0-2
+        assert_eq!(res, vec![2, 3, 5, 6, 6]);
+
+        // 到達不可能
+        let res = k_shortest_walks(
+            3,
+            |u| adj[u].iter().copied().filter(|&(v, _)| v != 2),
+            0,
+            2,
+            3,
+        );
+        assert_eq!(res, vec![]);
+
+        // s == t
+        let res = k_shortest_walks(3, |u| adj[u].iter().copied(), 0, 0, 2);
+        assert_eq!(res, vec![0, 3]);
+    }
+
+    #[test]
+    fn test_k_shortest_simple_paths() {
+        //     1
+        //   /   \
+        // 0 - 2 - 3
+        //   \   /
+        //     4
+        let adj = [
+            vec![(1, 1), (2, 2), (4, 3)],
+            vec![(3, 1)],
+            vec![(3, 1), (1, 0)],
+            vec![],
+            vec![(3, 1)],
+        ];
+        let res = k_shortest_simple_paths(5, |u| adj[u].iter().copied(), 0, 3, 10);
+        assert_eq!(
+            res,
+            vec![
+                (2, vec![0, 1, 3]),
+                (3, vec![0, 2, 3]),
+                (3, vec![0, 2, 1, 3]),
+                (4, vec![0, 4, 3]),
+            ]
+        );
+
+        let res = k_shortest_simple_paths(5, |u| adj[u].iter().copied(), 0, 3, 2);
+        assert_eq!(res.len(), 2);
+
+        // 到達不可能
+        let res = k_shortest_simple_paths(5, |u| adj[u].iter().copied(), 3, 0, 2);
+        assert_eq!(res, vec![]);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_k_shortest_paths_random() {
+        use rand::prelude::*;
+
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let nv = rng.random_range(1..=6);
+            let mut adj = vec![vec![]; nv];
+            for u in 0..nv {
+                for v in 0..nv {
+                    if u != v && rng.random_bool(0.4) {
+                        adj[u].push((v, rng.random_range(0..=5)));
+                    }
+                }
+            }
+            let s = rng.random_range(0..nv);
+            let t = rng.random_range(0..nv);
+            let k = rng.random_range(1..=8);
+
+            // 単純パスを全列挙する
+            fn dfs(
+                u: usize,
+                t: usize,
+                adj: &[Vec<(usize, i64)>],
+                path: &mut Vec<usize>,
+                cost: i64,
+                paths: &mut Vec<i64>,
+            ) {
+                if u == t {
+                    paths.push(cost);
+                    return;
+                }
+                for &(v, c) in &adj[u] {
+                    if !path.contains(&v) {
+                        path.push(v);
+                        dfs(v, t, adj, path, cost + c, paths);
+                        path.pop();
+                    }
+                }
+            }
+            let mut expected = vec![];
+            dfs(s, t, &adj, &mut vec![s], 0, &mut expected);
+            expected.sort();
+            expected.truncate(k);
+
+            let res = k_shortest_simple_paths(nv, |u| adj[u].iter().copied(), s, t, k);
+            assert_eq!(res.iter().map(|(c, _)| *c).collect::<Vec<_>>(), expected);
+            for (c, path) in &res {
+                assert_eq!(path.first(), Some(&s));
+                assert_eq!(path.last(), Some(&t));
+                let mut sorted = path.clone();
+                sorted.sort();
+                sorted.dedup();
+                assert_eq!(sorted.len(), path.len());
+                let len = path
+                    .windows(2)
+                    .map(|w| adj[w[0]].iter().find(|e| e.0 == w[1]).unwrap().1)
+                    .sum::<i64>();
+                assert_eq!(len, *c);
+            }
+
+            // ウォーク: 長さ max_len 以下のウォークを DP で数えて比較する。
+            // コスト 0 の辺があると数が無限になりうるので、正のコストの辺だけのグラフで比較する。
+            let max_len = 12;
+            // dp[l][v]: 長さちょうど l で s から v へのウォークの数
+            let mut dp = vec![vec![0_usize; nv]; max_len + 1];
+            dp[0][s] = 1;
+            for l in 0..=max_len {
+                for u in 0..nv {
+                    for &(v, c) in &adj[u] {
+                        if c > 0 && l + c as usize <= max_len {
+                            dp[l + c as usize][v] += dp[l][u];
+                        }
+                    }
+                }
+            }
+            let mut expected_walks = vec![];
+            for l in 0..=max_len {
+                for _ in 0..dp[l][t] {
+                    expected_walks.push(l as i64);
+                }
+            }
+            expected_walks.truncate(k);
+
+            let walks = k_shortest_walks(
+                nv,
+                |u| adj[u].iter().copied().filter(|&(_, c)| c > 0),
+                s,
+                t,
+                k,
+            );
+            let walks_within = walks
+                .into_iter()
+                .filter(|&d| d <= max_len as i64)
+                .collect::<Vec<_>>();
+            assert_eq!(walks_within, expected_walks);
+        }
+    }
+}
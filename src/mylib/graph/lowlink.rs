@@ -4,7 +4,9 @@ use cargo_snippet::snippet;
 #[allow(clippy::module_inception)]
 #[snippet(prefix = "use lowlink::*;")]
 pub mod lowlink {
-    /// LowLink (無向グラフの橋、関節点の検出)
+    /// LowLink (無向グラフの橋、関節点の検出、二重辺連結成分分解、二重頂点連結成分分解)
+    ///
+    /// 単純グラフを想定している (多重辺があると正しく動かない)。
     ///
     /// # 計算量
     /// O(V + E)
@@ -18,6 +20,30 @@ pub mod lowlink {
         pub bridges: Vec<(usize, usize)>,
         /// 関節点のリスト。昇順にソートされている。
         pub articulation_points: Vec<usize>,
+        /// 各頂点が属する二重辺連結成分の番号 (0-indexed)
+        pub two_edge_cc_id: Vec<usize>,
+        /// 二重辺連結成分の数
+        pub num_two_edge_cc: usize,
+        /// 二重頂点連結成分 (ブロック) ごとの辺 (u, v) のリスト。常に u < v となるように正規化されている。
+        /// 孤立点はどのブロックにも属さない。
+        pub biconnected_components: Vec<Vec<(usize, usize)>>,
+    }
+
+    /// DFS 中の状態
+    struct DfsState<'a> {
+        adj: &'a [Vec<usize>],
+        k: usize,
+        ord: Vec<usize>,
+        low: Vec<usize>,
+        bridges: Vec<(usize, usize)>,
+        articulation_points: Vec<usize>,
+        /// 二重辺連結成分が未確定の頂点のスタック
+        vertex_stack: Vec<usize>,
+        two_edge_cc_id: Vec<usize>,
+        num_two_edge_cc: usize,
+        /// ブロックが未確定の辺のスタック
+        edge_stack: Vec<(usize, usize)>,
+        biconnected_components: Vec<Vec<(usize, usize)>>,
     }
 
     impl LowLink {
@@ -35,12 +61,6 @@ pub mod lowlink {
             F: FnMut(usize) -> It,
             It: IntoIterator<Item = usize>,
         {
-            let mut ord = vec![usize::MAX; nv];
-            let mut low = vec![usize::MAX; nv];
-            let mut bridges = Vec::new();
-            let mut articulation_points = Vec::new();
-            let mut k = 0;
-
             // adj_fn を何度も呼ぶので、事前にグラフを構築したほうが効率的かもしれないが、
             // ここでは汎用性を重視してクロージャを受け取る形を維持する。
             // ただし、再帰呼び出しの中でクロージャを呼ぶのは難しいため、
@@ -59,21 +79,43 @@ pub mod lowlink {
                 adj
             };
 
+            let mut state = DfsState {
+                adj: &adj,
+                k: 0,
+                ord: vec![usize::MAX; nv],
+                low: vec![usize::MAX; nv],
+                bridges: Vec::new(),
+                articulation_points: Vec::new(),
+                vertex_stack: Vec::new(),
+                two_edge_cc_id: vec![usize::MAX; nv],
+                num_two_edge_cc: 0,
+                edge_stack: Vec::new(),
+                biconnected_components: Vec::new(),
+            };
+
             for i in 0..nv {
-                if ord[i] == usize::MAX {
-                    Self::dfs(
-                        i,
-                        usize::MAX,
-                        &adj,
-                        &mut k,
-                        &mut ord,
-                        &mut low,
-                        &mut bridges,
-                        &mut articulation_points,
-                    );
+                if state.ord[i] == usize::MAX {
+                    Self::dfs(i, usize::MAX, &mut state);
+                    // 根を含む二重辺連結成分を確定させる
+                    let id = state.num_two_edge_cc;
+                    for v in state.vertex_stack.drain(..) {
+                        state.two_edge_cc_id[v] = id;
+                    }
+                    state.num_two_edge_cc += 1;
                 }
             }
 
+            let DfsState {
+                ord,
+                low,
+                mut bridges,
+                mut articulation_points,
+                two_edge_cc_id,
+                num_two_edge_cc,
+                biconnected_components,
+                ..
+            } = state;
+
             bridges.sort();
             articulation_points.sort();
             articulation_points.dedup();
@@ -83,57 +125,67 @@ pub mod lowlink {
                 low,
                 bridges,
                 articulation_points,
+                two_edge_cc_id,
+                num_two_edge_cc,
+                biconnected_components,
             }
         }
 
-        /// DFS を行い、ord, low, bridges, articulation_points を計算する
+        /// DFS を行い、ord, low, 橋, 関節点, 二重辺連結成分, ブロックを計算する
         ///
         /// # Arguments
         /// * `u` - 現在の頂点
         /// * `p` - 親頂点
-        /// * `adj` - 隣接リスト
-        /// * `k` - 訪問順序のカウンター
-        /// * `ord` - 各頂点の訪問順序
-        /// * `low` - 各頂点の lowlink 値
-        /// * `bridges` - 橋のリスト
-        /// * `articulation_points` - 関節点のリスト
-        #[allow(clippy::too_many_arguments)]
-        fn dfs(
-            u: usize,
-            p: usize,
-            adj: &[Vec<usize>],
-            k: &mut usize,
-            ord: &mut Vec<usize>,
-            low: &mut Vec<usize>,
-            bridges: &mut Vec<(usize, usize)>,
-            articulation_points: &mut Vec<usize>,
-        ) {
-            ord[u] = *k;
-            low[u] = *k;
-            *k += 1;
+        /// * `st` - DFS 中の状態
+        fn dfs(u: usize, p: usize, st: &mut DfsState) {
+            st.ord[u] = st.k;
+            st.low[u] = st.k;
+            st.k += 1;
+            st.vertex_stack.push(u);
 
             let mut is_articulation = false;
             let mut child_count = 0;
 
-            for &v in &adj[u] {
+            for &v in &st.adj[u] {
                 if v == p {
                     continue;
                 }
-                if ord[v] != usize::MAX {
-                    low[u] = low[u].min(ord[v]);
+                if st.ord[v] != usize::MAX {
+                    st.low[u] = st.low[u].min(st.ord[v]);
+                    if st.ord[v] < st.ord[u] {
+                        // 後退辺 (子孫側から見たときのみ積む)
+                        st.edge_stack.push((u.min(v), u.max(v)));
+                    }
                 } else {
                     child_count += 1;
-                    Self::dfs(v, u, adj, k, ord, low, bridges, articulation_points);
-                    low[u] = low[u].min(low[v]);
-                    if p != usize::MAX && low[v] >= ord[u] {
-                        is_articulation = true;
+                    st.edge_stack.push((u.min(v), u.max(v)));
+                    Self::dfs(v, u, st);
+                    st.low[u] = st.low[u].min(st.low[v]);
+                    if st.low[v] >= st.ord[u] {
+                        if p != usize::MAX {
+                            is_articulation = true;
+                        }
+                        // 辺 u-v までがひとつのブロックになる
+                        let mut block = vec![];
+                        while let Some(e) = st.edge_stack.pop() {
+                            block.push(e);
+                            if e == (u.min(v), u.max(v)) {
+                                break;
+                            }
+                        }
+                        st.biconnected_components.push(block);
                     }
-                    if low[v] > ord[u] {
-                        if u < v {
-                            bridges.push((u, v));
-                        } else {
-                            bridges.push((v, u));
+                    if st.low[v] > st.ord[u] {
+                        st.bridges.push((u.min(v), u.max(v)));
+                        // v 以降に訪問した頂点がひとつの二重辺連結成分になる
+                        let id = st.num_two_edge_cc;
+                        while let Some(w) = st.vertex_stack.pop() {
+                            st.two_edge_cc_id[w] = id;
+                            if w == v {
+                                break;
+                            }
                         }
+                        st.num_two_edge_cc += 1;
                     }
                 }
             }
@@ -143,9 +195,80 @@ pub mod lowlink {
             }
 
             if is_articulation {
-                articulation_points.push(u);
+                st.articulation_points.push(u);
             }
         }
+
+        /// 二重辺連結成分ごとの頂点のリストを返す
+        ///
+        /// # Returns
+        /// `groups[i]` は番号 `i` の二重辺連結成分に属する頂点のリスト (昇順)
+        ///
+        /// # 計算量
+        /// O(V)
+        pub fn two_edge_cc_groups(&self) -> Vec<Vec<usize>> {
+            let mut groups = vec![vec![]; self.num_two_edge_cc];
+            for (v, &id) in self.two_edge_cc_id.iter().enumerate() {
+                groups[id].push(v);
+            }
+            groups
+        }
+
+        /// 橋木 (二重辺連結成分を縮約したグラフ) の隣接リストを返す
+        ///
+        /// 頂点番号は `two_edge_cc_id` の番号に対応する。元のグラフが連結なら木、そうでなければ森になる。
+        ///
+        /// # 計算量
+        /// O(V)
+        pub fn bridge_tree(&self) -> Vec<Vec<usize>> {
+            let mut tree = vec![vec![]; self.num_two_edge_cc];
+            for &(u, v) in &self.bridges {
+                let cu = self.two_edge_cc_id[u];
+                let cv = self.two_edge_cc_id[v];
+                tree[cu].push(cv);
+                tree[cv].push(cu);
+            }
+            tree
+        }
+
+        /// 二重頂点連結成分 (ブロック) ごとの頂点のリストを返す
+        ///
+        /// 孤立点はどのブロックにも属さない。
+        ///
+        /// # 計算量
+        /// O(V + E)
+        pub fn biconnected_component_vertices(&self) -> Vec<Vec<usize>> {
+            self.biconnected_components
+                .iter()
+                .map(|block| {
+                    let mut vs = block.iter().flat_map(|&(u, v)| [u, v]).collect::<Vec<_>>();
+                    vs.sort();
+                    vs.dedup();
+                    vs
+                })
+                .collect()
+        }
+
+        /// Block-Cut 木の隣接リストを返す
+        ///
+        /// 頂点 `0..nv` は元のグラフの頂点、頂点 `nv + i` は `biconnected_components[i]` のブロックを表す。
+        /// 元の頂点とそれを含むブロックの間に辺を張る。
+        /// 元のグラフが連結 (かつ 2 頂点以上) なら木、そうでなければ森になる。
+        ///
+        /// # 計算量
+        /// O(V + E)
+        pub fn block_cut_tree(&self) -> Vec<Vec<usize>> {
+            let nv = self.ord.len();
+            let blocks = self.biconnected_component_vertices();
+            let mut tree = vec![vec![]; nv + blocks.len()];
+            for (i, block) in blocks.iter().enumerate() {
+                for &v in block {
+                    tree[v].push(nv + i);
+                    tree[nv + i].push(v);
+                }
+            }
+            tree
+        }
     }
 }
 
@@ -165,6 +288,57 @@ pub mod lowlink_ix {
         pub bridges: Vec<(I, I)>,
         /// 関節点のリスト。
         pub articulation_points: Vec<I>,
+        /// 各頂点が属する二重辺連結成分の番号 (0-indexed)
+        pub two_edge_cc_id: IxVec<I, usize>,
+        /// 二重辺連結成分の数
+        pub num_two_edge_cc: usize,
+        /// 二重頂点連結成分 (ブロック) ごとの辺 (u, v) のリスト。
+        pub biconnected_components: Vec<Vec<(I, I)>>,
+        bounds: Bounds<I>,
+    }
+
+    impl<I: Ix> LowLinkIxResult<I> {
+        /// 橋木 (二重辺連結成分を縮約したグラフ) の隣接リストを返す
+        ///
+        /// 頂点番号は `two_edge_cc_id` の番号に対応する。元のグラフが連結なら木、そうでなければ森になる。
+        ///
+        /// # 計算量
+        /// O(V)
+        pub fn bridge_tree(&self) -> Vec<Vec<usize>> {
+            let mut tree = vec![vec![]; self.num_two_edge_cc];
+            for &(u, v) in &self.bridges {
+                let cu = self.two_edge_cc_id[u];
+                let cv = self.two_edge_cc_id[v];
+                tree[cu].push(cv);
+                tree[cv].push(cu);
+            }
+            tree
+        }
+
+        /// Block-Cut 木の隣接リストを返す
+        ///
+        /// 頂点 `bounds.to_index(v)` は元のグラフの頂点 `v`、頂点 `nv + i` は `biconnected_components[i]` のブロックを表す。
+        /// 元の頂点とそれを含むブロックの間に辺を張る。
+        ///
+        /// # 計算量
+        /// O(V + E)
+        pub fn block_cut_tree(&self) -> Vec<Vec<usize>> {
+            let nv = self.bounds.range_size();
+            let mut tree = vec![vec![]; nv + self.biconnected_components.len()];
+            for (i, block) in self.biconnected_components.iter().enumerate() {
+                let mut vs = block
+                    .iter()
+                    .flat_map(|&(u, v)| [self.bounds.to_index(u), self.bounds.to_index(v)])
+                    .collect::<Vec<_>>();
+                vs.sort();
+                vs.dedup();
+                for v in vs {
+                    tree[v].push(nv + i);
+                    tree[nv + i].push(v);
+                }
+            }
+            tree
+        }
     }
 
     /// Bounds を用いた任意の型 I に対する LowLink (無向グラフ)
@@ -206,6 +380,19 @@ pub mod lowlink_ix {
                 .into_iter()
                 .map(|u| bounds.from_index(u))
                 .collect(),
+            two_edge_cc_id: IxVec::from_vec(bounds, res.two_edge_cc_id),
+            num_two_edge_cc: res.num_two_edge_cc,
+            biconnected_components: res
+                .biconnected_components
+                .into_iter()
+                .map(|block| {
+                    block
+                        .into_iter()
+                        .map(|(u, v)| (bounds.from_index(u), bounds.from_index(v)))
+                        .collect()
+                })
+                .collect(),
+            bounds,
         }
     }
 }
@@ -215,6 +402,7 @@ mod tests {
     use super::lowlink::*;
     use super::lowlink_ix::*;
     use crate::data_structure::ix::Bounds;
+    use crate::graph::graph::make_adj_from_undirected;
 
     #[test]
     fn test_lowlink_basic() {
@@ -245,6 +433,67 @@ mod tests {
         assert_eq!(res.articulation_points, vec![1]);
     }
 
+    #[test]
+    fn test_lowlink_components() {
+        // 0       4
+        // | \   / |
+        // |  2 - 3 |
+        // | /   \ |
+        // 1       5
+        //
+        // 6 (独立)
+        let edges = [(0, 1), (0, 2), (1, 2), (2, 3), (3, 4), (3, 5), (4, 5)];
+        let adj = make_adj_from_undirected(7, &edges);
+
+        let res = LowLink::new(7, |u| adj[u].iter().copied());
+        assert_eq!(res.bridges, vec![(2, 3)]);
+        assert_eq!(res.articulation_points, vec![2, 3]);
+
+        // 二重辺連結成分
+        assert_eq!(res.num_two_edge_cc, 3);
+        let mut groups = res.two_edge_cc_groups();
+        groups.sort();
+        assert_eq!(groups, vec![vec![0, 1, 2], vec![3, 4, 5], vec![6]]);
+        let id = &res.two_edge_cc_id;
+        assert_eq!(id[0], id[1]);
+        assert_ne!(id[2], id[3]);
+
+        // 橋木
+        let bridge_tree = res.bridge_tree();
+        assert_eq!(bridge_tree.len(), 3);
+        assert_eq!(bridge_tree[id[2]], vec![id[3]]);
+        assert_eq!(bridge_tree[id[3]], vec![id[2]]);
+        assert!(bridge_tree[id[6]].is_empty());
+
+        // 二重頂点連結成分
+        let mut blocks = res
+            .biconnected_components
+            .iter()
+            .map(|block| {
+                let mut block = block.clone();
+                block.sort();
+                block
+            })
+            .collect::<Vec<_>>();
+        blocks.sort();
+        assert_eq!(
+            blocks,
+            vec![
+                vec![(0, 1), (0, 2), (1, 2)],
+                vec![(2, 3)],
+                vec![(3, 4), (3, 5), (4, 5)]
+            ]
+        );
+
+        // Block-Cut 木: 関節点 2, 3 だけが複数のブロックに隣接する
+        let block_cut_tree = res.block_cut_tree();
+        assert_eq!(block_cut_tree.len(), 7 + 3);
+        let degrees = (0..7).map(|v| block_cut_tree[v].len()).collect::<Vec<_>>();
+        assert_eq!(degrees, vec![1, 1, 2, 2, 1, 1, 0]);
+        let num_edges = block_cut_tree.iter().map(|a| a.len()).sum::<usize>() / 2;
+        assert_eq!(num_edges, 8);
+    }
+
     #[test]
     fn test_lowlink_arbitrary() {
         let bounds = Bounds::new((0, 0), (1, 2)); // 2x3 グリッド
@@ -291,7 +540,7 @@ mod tests {
         // インデックスがソートされている（u < v かつペア間でソート）ため、
         // 座標は「見た目的」にはソートされていないかもしれないが、ソートされたインデックスに対応する。
 
-        let mut bridges = res.bridges;
+        let mut bridges = res.bridges.clone();
         // 比較のために正規化（ペア内をソートし、その後ベクトル全体をソート）
         bridges.iter_mut().for_each(|(u, v)| {
             if u > v {
@@ -305,6 +554,14 @@ mod tests {
 
         // 関節点: (0,1) が中心。
         assert_eq!(res.articulation_points, vec![(0, 1)]);
+
+        // 木なので、各頂点がそれぞれ二重辺連結成分になり、各辺がそれぞれブロックになる。
+        assert_eq!(res.num_two_edge_cc, 6);
+        assert_ne!(res.two_edge_cc_id[(0, 0)], res.two_edge_cc_id[(0, 1)]);
+        assert_eq!(res.bridge_tree().len(), 6);
+        assert_eq!(res.biconnected_components.len(), 3);
+        assert_eq!(res.block_cut_tree().len(), 6 + 3);
+        assert_eq!(res.block_cut_tree()[bounds.to_index((0, 1))].len(), 3);
     }
 
     fn find_bridges_brute_force(nv: usize, edges: &[(usize, usize)]) -> Vec<(usize, usize)> {
@@ -377,11 +634,7 @@ mod tests {
                 .filter(|&(u, v)| u < v && rng.random_bool(0.2))
                 .collect();
 
-            let adj = edges.iter().fold(vec![vec![]; nv], |mut acc, &(u, v)| {
-                acc[u].push(v);
-                acc[v].push(u);
-                acc
-            });
+            let adj = make_adj_from_undirected(nv, &edges);
 
             let res = LowLink::new(nv, |u| adj[u].iter().copied());
 
@@ -395,4 +648,80 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    #[ignore]
+    fn test_lowlink_components_random() {
+        use crate::data_structure::dsu::dsu_core::DsuCore;
+        use itertools::iproduct;
+        use rand::prelude::*;
+
+        let mut rng = StdRng::seed_from_u64(42);
+
+        for _ in 0..100 {
+            let nv = rng.random_range(1..=12);
+            let edges: Vec<_> = iproduct!(0..nv, 0..nv)
+                .filter(|&(u, v)| u < v && rng.random_bool(0.3))
+                .collect();
+
+            let adj = make_adj_from_undirected(nv, &edges);
+
+            let res = LowLink::new(nv, |u| adj[u].iter().copied());
+
+            // 二重辺連結成分: 橋以外の辺で連結か
+            let mut dsu = DsuCore::new(nv);
+            for &e in &edges {
+                if !res.bridges.contains(&e) {
+                    dsu.merge(e.0, e.1);
+                }
+            }
+            for (u, v) in iproduct!(0..nv, 0..nv) {
+                assert_eq!(
+                    res.two_edge_cc_id[u] == res.two_edge_cc_id[v],
+                    dsu.same(u, v)
+                );
+            }
+            assert_eq!(res.num_two_edge_cc, dsu.count_group());
+
+            // 橋木は森になる
+            let bridge_tree = res.bridge_tree();
+            let num_edges = bridge_tree.iter().map(|a| a.len()).sum::<usize>() / 2;
+            assert_eq!(num_edges, res.bridges.len());
+            let mut dsu_orig = DsuCore::new(nv);
+            for &(u, v) in &edges {
+                dsu_orig.merge(u, v);
+            }
+            assert_eq!(res.num_two_edge_cc - num_edges, dsu_orig.count_group());
+
+            // 二重頂点連結成分: 辺を分割していて、各ブロックは関節点を持たない連結グラフ
+            let mut all_edges = res.biconnected_components.concat();
+            all_edges.sort();
+            assert_eq!(all_edges, edges);
+            for block in &res.biconnected_components {
+                assert!(find_articulation_points_brute_force(nv, block).is_empty());
+                let mut dsu_block = DsuCore::new(nv);
+                for &(u, v) in block {
+                    dsu_block.merge(u, v);
+                }
+                assert_eq!(dsu_block.size(block[0].0), {
+                    let mut vs = block.iter().flat_map(|&(u, v)| [u, v]).collect::<Vec<_>>();
+                    vs.sort();
+                    vs.dedup();
+                    vs.len()
+                });
+            }
+
+            // Block-Cut 木は森になる (ブロックの極大性)
+            let block_cut_tree = res.block_cut_tree();
+            let n_bct = block_cut_tree.len();
+            let mut dsu_bct = DsuCore::new(n_bct);
+            for u in 0..n_bct {
+                for &v in &block_cut_tree[u] {
+                    if u < v {
+                        assert!(dsu_bct.merge(u, v).is_some(), "Block-Cut tree has a cycle");
+                    }
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,327 @@
+use cargo_snippet::snippet;
+
+use crate::data_structure::dsu::dsu_core::dsu_core::DsuCore;
+
+#[allow(clippy::module_inception)]
+#[snippet(prefix = "use mst::*;", include = "dsu_core")]
+pub mod mst {
+    use super::DsuCore;
+
+    /// Kruskal 法で最小全域木を求める
+    ///
+    /// # Arguments
+    /// * `nv` - 頂点数
+    /// * `edges` - 辺 `(u, v, cost)` のリスト
+    ///
+    /// # Returns
+    /// グラフが連結な場合は `Some((最小全域木のコスト, 採用した辺の edges でのインデックスのリスト))`。連結でない場合は `None`。
+    ///
+    /// # 計算量
+    /// O(E log E)
+    pub fn kruskal(nv: usize, edges: &[(usize, usize, i64)]) -> Option<(i64, Vec<usize>)> {
+        let mut order = (0..edges.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&i| edges[i].2);
+
+        let mut dsu = DsuCore::new(nv);
+        let mut total = 0;
+        let mut used = Vec::with_capacity(nv.saturating_sub(1));
+        for i in order {
+            let (u, v, cost) = edges[i];
+            if dsu.merge(u, v).is_some() {
+                total += cost;
+                used.push(i);
+            }
+        }
+        (dsu.count_group() <= 1).then_some((total, used))
+    }
+
+    /// Prim 法で密グラフの最小全域木を求める
+    ///
+    /// 完全グラフのように辺の数が O(V^2) のときに使う。
+    ///
+    /// # Arguments
+    /// * `nv` - 頂点数
+    /// * `cost` - `cost(u, v)` は辺 `u-v` のコスト。辺がない場合は `None`。
+    ///
+    /// # Returns
+    /// グラフが連結な場合は `Some((最小全域木のコスト, 採用した辺 (u, v) のリスト))`。連結でない場合は `None`。
+    ///
+    /// # 計算量
+    /// O(V^2)
+    pub fn prim_dense<F>(nv: usize, mut cost: F) -> Option<(i64, Vec<(usize, usize)>)>
+    where
+        F: FnMut(usize, usize) -> Option<i64>,
+    {
+        if nv == 0 {
+            return Some((0, vec![]));
+        }
+        // min_cost[v]: 木から v への辺の最小コストとその木側の端点
+        let mut min_cost: Vec<Option<(i64, usize)>> = vec![None; nv];
+        let mut in_tree = vec![false; nv];
+        let mut total = 0;
+        let mut used = Vec::with_capacity(nv - 1);
+
+        let mut current = 0;
+        in_tree[0] = true;
+        for _ in 1..nv {
+            for v in 0..nv {
+                if in_tree[v] {
+                    continue;
+                }
+                if let Some(c) = cost(current, v) {
+                    if min_cost[v].is_none_or(|(best, _)| c < best) {
+                        min_cost[v] = Some((c, current));
+                    }
+                }
+            }
+            let (next, (c, from)) = (0..nv)
+                .filter(|&v| !in_tree[v])
+                .filter_map(|v| min_cost[v].map(|x| (v, x)))
+                .min_by_key(|&(_, (c, _))| c)?;
+            in_tree[next] = true;
+            total += c;
+            used.push((from, next));
+            current = next;
+        }
+        Some((total, used))
+    }
+
+    /// Borůvka 法で最小全域木を求める
+    ///
+    /// 辺が陽に与えられない (例: 辺 `i-j` のコストが `|a_i - a_j|`) グラフで、
+    /// 「各頂点から別の連結成分への最小コストの辺」を高速に求められる場合に使う。
+    ///
+    /// # Arguments
+    /// * `nv` - 頂点数
+    /// * `min_edge` - 各頂点が属する連結成分の番号 `comp` を受け取り、各頂点 `v` について
+    ///   `comp[u] != comp[v]` を満たす辺 `v-u` のうちコスト最小のもの `(cost, u)` を返すクロージャー。
+    ///   そのような辺がない場合は `None` とする。
+    ///
+    /// # Returns
+    /// グラフが連結な場合は `Some((最小全域木のコスト, 採用した辺 (u, v) のリスト))`。連結でない場合は `None`。
+    ///
+    /// # 計算量
+    /// O(T log V) (T は `min_edge` 1 回あたりの計算量)
+    pub fn boruvka<F>(nv: usize, mut min_edge: F) -> Option<(i64, Vec<(usize, usize)>)>
+    where
+        F: FnMut(&[usize]) -> Vec<Option<(i64, usize)>>,
+    {
+        let mut dsu = DsuCore::new(nv);
+        let mut total = 0;
+        let mut used = Vec::with_capacity(nv.saturating_sub(1));
+        while dsu.count_group() > 1 {
+            let comp = (0..nv).map(|v| dsu.leader(v)).collect::<Vec<_>>();
+            let candidates = min_edge(&comp);
+
+            // 各連結成分から出る最小コストの辺
+            let mut best: Vec<Option<(i64, usize, usize)>> = vec![None; nv];
+            for (v, cand) in candidates.into_iter().enumerate() {
+                if let Some((c, u)) = cand {
+                    let b = &mut best[comp[v]];
+                    if b.is_none_or(|(bc, _, _)| c < bc) {
+                        *b = Some((c, v, u));
+                    }
+                }
+            }
+
+            let mut merged = false;
+            for (c, v, u) in best.into_iter().flatten() {
+                // 同じコストの辺で閉路ができる場合があるので、DSU で確認する
+                if dsu.merge(v, u).is_some() {
+                    total += c;
+                    used.push((v, u));
+                    merged = true;
+                }
+            }
+            if !merged {
+                return None;
+            }
+        }
+        Some((total, used))
+    }
+}
+
+use crate::math::geometry::pos::pos::Pos;
+
+#[snippet(prefix = "use manhattan_mst::*;", include = "mst, pos")]
+pub mod manhattan_mst {
+    use super::Pos;
+    use super::mst::kruskal;
+    use std::collections::BTreeMap;
+
+    /// マンハッタン距離を辺のコストとする完全グラフの最小全域木の候補となる辺を O(N) 本求める
+    ///
+    /// # Returns
+    /// 辺 `(i, j, cost)` のリスト。この辺集合の最小全域木が元の完全グラフの最小全域木になる。
+    ///
+    /// # 計算量
+    /// O(N log N)
+    pub fn manhattan_mst_edges(ps: &[Pos]) -> Vec<(usize, usize, i64)> {
+        let mut ps = ps.to_vec();
+        let mut id = (0..ps.len()).collect::<Vec<_>>();
+        let mut edges = vec![];
+        for k in 0..4 {
+            id.sort_by_key(|&i| ps[i].x + ps[i].y);
+            let mut sweep: BTreeMap<i64, usize> = BTreeMap::new();
+            for &i in &id {
+                while let Some((&key, &j)) = sweep.range(-ps[i].y..).next() {
+                    let d = ps[i] - ps[j];
+                    if d.y > d.x {
+                        break;
+                    }
+                    edges.push((i, j, d.x + d.y));
+                    sweep.remove(&key);
+                }
+                sweep.insert(-ps[i].y, i);
+            }
+            for p in &mut ps {
+                if k % 2 == 1 {
+                    p.x = -p.x;
+                } else {
+                    std::mem::swap(&mut p.x, &mut p.y);
+                }
+            }
+        }
+        edges
+    }
+
+    /// マンハッタン距離を辺のコストとする完全グラフの最小全域木を求める
+    ///
+    /// # Returns
+    /// `(最小全域木のコスト, 採用した辺 (i, j) のリスト)`
+    ///
+    /// # 計算量
+    /// O(N log N)
+    pub fn manhattan_mst(ps: &[Pos]) -> (i64, Vec<(usize, usize)>) {
+        let edges = manhattan_mst_edges(ps);
+        let (total, used) = kruskal(ps.len(), &edges).unwrap();
+        (
+            total,
+            used.iter().map(|&i| (edges[i].0, edges[i].1)).collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::manhattan_mst::*;
+    use super::mst::*;
+    use crate::math::geometry::pos::pos::Pos;
+    use rand::prelude::*;
+
+    fn is_spanning_tree(nv: usize, edges: &[(usize, usize)]) -> bool {
+        let mut dsu = ac_library::Dsu::new(nv);
+        edges.len() + 1 == nv.max(1)
+            && edges
+                .iter()
+                .all(|&(u, v)| !dsu.same(u, v) && dsu.merge(u, v) < nv)
+    }
+
+    #[test]
+    fn test_kruskal() {
+        // 0 -1- 1 -2- 2
+        // |    /     /
+        // 4   5     3
+        // |  /     /
+        // 3 -------
+        let edges = [(0, 1, 1), (1, 2, 2), (2, 3, 3), (0, 3, 4), (1, 3, 5)];
+        let (cost, used) = kruskal(4, &edges).unwrap();
+        assert_eq!(cost, 6);
+        assert_eq!(used, vec![0, 1, 2]);
+
+        // 連結でない
+        assert_eq!(kruskal(3, &[(0, 1, 1)]), None);
+        // 頂点が 1 つ
+        assert_eq!(kruskal(1, &[]), Some((0, vec![])));
+    }
+
+    #[test]
+    fn test_prim_dense() {
+        let xs = [1_i64, 10, 3, 7];
+        let (cost, used) = prim_dense(4, |i, j| Some((xs[i] - xs[j]).abs())).unwrap();
+        assert_eq!(cost, 9);
+        assert!(is_spanning_tree(4, &used));
+
+        // 連結でない
+        assert_eq!(prim_dense(3, |i, j| (i + j == 1).then_some(1)), None);
+    }
+
+    #[test]
+    fn test_boruvka() {
+        // 辺 i-j のコストが |a_i - a_j| の完全グラフ
+        let xs = [5_i64, 1, 9, 3, 3];
+        let n = xs.len();
+        let (cost, used) = boruvka(n, |comp| {
+            (0..n)
+                .map(|i| {
+                    (0..n)
+                        .filter(|&j| comp[i] != comp[j])
+                        .map(|j| ((xs[i] - xs[j]).abs(), j))
+                        .min()
+                })
+                .collect()
+        })
+        .unwrap();
+        assert_eq!(cost, 8);
+        assert!(is_spanning_tree(n, &used));
+    }
+
+    #[test]
+    fn test_manhattan_mst() {
+        let ps = [
+            Pos::new(0, 0),
+            Pos::new(2, 1),
+            Pos::new(-1, 3),
+            Pos::new(5, 5),
+        ];
+        let (cost, used) = manhattan_mst(&ps);
+        assert_eq!(cost, 3 + 4 + 7);
+        assert!(is_spanning_tree(ps.len(), &used));
+    }
+
+    #[test]
+    #[ignore]
+    fn test_mst_random() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let n = rng.random_range(1..=15);
+            let ps = (0..n)
+                .map(|_| Pos::new(rng.random_range(-10..=10), rng.random_range(-10..=10)))
+                .collect::<Vec<_>>();
+            let all_edges = (0..n)
+                .flat_map(|i| (i + 1..n).map(move |j| (i, j)))
+                .map(|(i, j)| (i, j, ps[i].l1_dist(ps[j])))
+                .collect::<Vec<_>>();
+            let (expected, used) = kruskal(n, &all_edges).unwrap();
+            assert!(is_spanning_tree(
+                n,
+                &used
+                    .iter()
+                    .map(|&i| (all_edges[i].0, all_edges[i].1))
+                    .collect::<Vec<_>>()
+            ));
+
+            let (cost, used) = prim_dense(n, |i, j| Some(ps[i].l1_dist(ps[j]))).unwrap();
+            assert_eq!(cost, expected);
+            assert!(is_spanning_tree(n, &used));
+
+            let (cost, used) = boruvka(n, |comp| {
+                (0..n)
+                    .map(|i| {
+                        (0..n)
+                            .filter(|&j| comp[i] != comp[j])
+                            .map(|j| (ps[i].l1_dist(ps[j]), j))
+                            .min()
+                    })
+                    .collect()
+            })
+            .unwrap();
+            assert_eq!(cost, expected);
+            assert!(is_spanning_tree(n, &used));
+
+            let (cost, used) = manhattan_mst(&ps);
+            assert_eq!(cost, expected);
+            assert!(is_spanning_tree(n, &used));
+        }
+    }
+}
@@ -0,0 +1,278 @@
+use cargo_snippet::snippet;
+
+use crate::graph::dijkstra::dijkstra::dijkstra;
+
+#[allow(clippy::module_inception)]
+#[snippet(prefix = "use shortest_path_dag::*;", include = "dijkstra")]
+pub mod shortest_path_dag {
+    use super::dijkstra;
+    use std::ops::Add;
+
+    /// 最短路 DAG (最短路に含まれうる辺だけからなるグラフ)
+    #[derive(Clone, Debug)]
+    pub struct ShortestPathDag {
+        /// 各頂点への最短距離。到達不可能な場合は `None`。
+        pub dist: Vec<Option<i64>>,
+        /// `dag[u]`: `dist[u] + cost == dist[v]` を満たす辺 `u -> v` の行き先 `v` のリスト
+        pub dag: Vec<Vec<usize>>,
+        /// 到達可能な頂点を DAG のトポロジカル順に並べたもの
+        pub order: Vec<usize>,
+    }
+
+    impl ShortestPathDag {
+        /// ダイクストラ法で最短距離を求め、最短路 DAG を構築する
+        ///
+        /// # Arguments
+        /// * `nv` - 頂点数
+        /// * `adj` - 頂点を受け取り、隣接する頂点とそのコストのペアのイテレータを返す `usize -> impl IntoIterator<Item = (usize, i64)>` のクロージャー。
+        ///   コストは非負で、コスト 0 の閉路はないこと。
+        /// * `init` - 始点となる頂点集合のイテレータ。1点のみの場合は `[v]` のように指定する
+        ///
+        /// # 計算量
+        /// O(V + E log V)
+        pub fn new<F, It>(nv: usize, mut adj: F, init: impl IntoIterator<Item = usize>) -> Self
+        where
+            F: FnMut(usize) -> It,
+            It: IntoIterator<Item = (usize, i64)>,
+        {
+            let adj = (0..nv)
+                .map(|u| adj(u).into_iter().collect::<Vec<_>>())
+                .collect::<Vec<_>>();
+            let dist = dijkstra(nv, |u| adj[u].iter().copied(), init);
+
+            let mut dag = vec![vec![]; nv];
+            let mut in_deg = vec![0; nv];
+            for u in 0..nv {
+                let Some(du) = dist[u] else { continue };
+                for &(v, cost) in &adj[u] {
+                    if dist[v] == Some(du + cost) {
+                        dag[u].push(v);
+                        in_deg[v] += 1;
+                    }
+                }
+            }
+
+            // コスト 0 の辺があっても正しい順序になるように、トポロジカルソートする
+            let mut order = (0..nv)
+                .filter(|&v| dist[v].is_some() && in_deg[v] == 0)
+                .collect::<Vec<_>>();
+            let mut i = 0;
+            while i < order.len() {
+                let u = order[i];
+                i += 1;
+                for &v in &dag[u] {
+                    in_deg[v] -= 1;
+                    if in_deg[v] == 0 {
+                        order.push(v);
+                    }
+                }
+            }
+
+            Self { dist, dag, order }
+        }
+
+        /// 頂点 `t` へのいずれかの最短路に含まれる辺 `(u, v)` のリスト
+        ///
+        /// # 計算量
+        /// O(V + E)
+        pub fn edges_to(&self, t: usize) -> Vec<(usize, usize)> {
+            let on_path = self.vertices_to(t);
+            let mut edges = vec![];
+            for &u in &self.order {
+                if !on_path[u] {
+                    continue;
+                }
+                for &v in &self.dag[u] {
+                    if on_path[v] {
+                        edges.push((u, v));
+                    }
+                }
+            }
+            edges
+        }
+
+        /// 各頂点が頂点 `t` へのいずれかの最短路に含まれるかどうか
+        ///
+        /// # 計算量
+        /// O(V + E)
+        pub fn vertices_to(&self, t: usize) -> Vec<bool> {
+            let nv = self.dist.len();
+            let mut on_path = vec![false; nv];
+            if self.dist[t].is_none() {
+                return on_path;
+            }
+            on_path[t] = true;
+            for &u in self.order.iter().rev() {
+                if self.dag[u].iter().any(|&v| on_path[v]) {
+                    on_path[u] = true;
+                }
+            }
+            on_path
+        }
+
+        /// 各頂点への最短路の数を数える
+        ///
+        /// # Returns
+        /// 各頂点への最短路の数。到達不可能な頂点は 0。
+        ///
+        /// # 計算量
+        /// O(V + E)
+        pub fn count<T>(&self, init: impl IntoIterator<Item = usize>) -> Vec<T>
+        where
+            T: Copy + Add<Output = T> + From<u8>,
+        {
+            let mut cnt = vec![T::from(0); self.dist.len()];
+            for s in init {
+                cnt[s] = T::from(1);
+            }
+            for &u in &self.order {
+                for &v in &self.dag[u] {
+                    cnt[v] = cnt[v] + cnt[u];
+                }
+            }
+            cnt
+        }
+    }
+
+    /// 重み付きグラフの隣接関数 `usize -> impl IntoIterator<Item = (usize, i64)>`
+    ///
+    /// 隣接関数の型を `impl WeightedAdjFn` で受け取ることで、呼び出し側で型引数を `dijkstra_count::<T>` のように
+    /// 数える型だけ指定できるようにする。
+    pub trait WeightedAdjFn: FnMut(usize) -> <Self as WeightedAdjFn>::Iter {
+        type Iter: IntoIterator<Item = (usize, i64)>;
+    }
+
+    impl<F, It> WeightedAdjFn for F
+    where
+        F: FnMut(usize) -> It,
+        It: IntoIterator<Item = (usize, i64)>,
+    {
+        type Iter = It;
+    }
+
+    /// ダイクストラ法で各頂点への最短距離と最短路の数を求める
+    ///
+    /// # Arguments
+    /// * `nv` - 頂点数
+    /// * `adj` - 頂点を受け取り、隣接する頂点とそのコストのペアのイテレータを返す `usize -> impl IntoIterator<Item = (usize, i64)>` のクロージャー。
+    ///   コストは非負で、コスト 0 の閉路はないこと。
+    /// * `init` - 始点となる頂点集合のイテレータ。1点のみの場合は `[v]` のように指定する
+    ///
+    /// # Returns
+    /// `(各頂点への最短距離, 各頂点への最短路の数)`。到達不可能な頂点は `(None, 0)`。
+    ///
+    /// # 計算量
+    /// O(V + E log V)
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let adj = vec![vec![(1, 1), (2, 1)], vec![(3, 1)], vec![(3, 1)], vec![]];
+    /// let (dist, cnt) = dijkstra_count::<Mint>(4, |u| adj[u].iter().copied(), [0]);
+    /// assert_eq!(dist[3], Some(2));
+    /// assert_eq!(cnt[3], Mint::new(2));
+    /// ```
+    pub fn dijkstra_count<T>(
+        nv: usize,
+        adj: impl WeightedAdjFn,
+        init: impl IntoIterator<Item = usize>,
+    ) -> (Vec<Option<i64>>, Vec<T>)
+    where
+        T: Copy + Add<Output = T> + From<u8>,
+    {
+        let init = init.into_iter().collect::<Vec<_>>();
+        let dag = ShortestPathDag::new(nv, adj, init.iter().copied());
+        let cnt = dag.count(init);
+        (dag.dist, cnt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::shortest_path_dag::*;
+    use ac_library::ModInt998244353 as Mint;
+
+    #[test]
+    fn test_dijkstra_count() {
+        //   1
+        //  / \
+        // 0   3 - 4
+        //  \ /
+        //   2
+        // 0 -> 3 の直通辺 (コスト 3) は最短路ではない
+        let adj = [
+            vec![(1, 1), (2, 1), (3, 3)],
+            vec![(3, 1)],
+            vec![(3, 1)],
+            vec![(4, 0)],
+            vec![],
+            vec![],
+        ];
+        let (dist, cnt) = dijkstra_count::<Mint>(6, |u| adj[u].iter().copied(), [0]);
+        assert_eq!(
+            dist,
+            vec![Some(0), Some(1), Some(1), Some(2), Some(2), None]
+        );
+        assert_eq!(cnt, [1, 1, 1, 2, 2, 0].map(Mint::new).to_vec());
+
+        let dag = ShortestPathDag::new(6, |u| adj[u].iter().copied(), [0]);
+        assert_eq!(dag.edges_to(3), vec![(0, 1), (0, 2), (1, 3), (2, 3)]);
+        assert_eq!(dag.edges_to(1), vec![(0, 1)]);
+        assert_eq!(dag.edges_to(5), vec![]);
+        assert_eq!(
+            dag.vertices_to(4),
+            vec![true, true, true, true, true, false]
+        );
+    }
+
+    #[test]
+    #[ignore]
+    fn test_dijkstra_count_random() {
+        use rand::prelude::*;
+
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..300 {
+            let nv = rng.random_range(1..=7);
+            // コスト 0 の閉路ができないように、コスト 0 の辺は u < v のみにする
+            let mut adj = vec![vec![]; nv];
+            for u in 0..nv {
+                for v in 0..nv {
+                    if u != v && rng.random_bool(0.4) {
+                        let cost = rng.random_range(if u < v { 0 } else { 1 }..=3);
+                        adj[u].push((v, cost));
+                    }
+                }
+            }
+
+            let (dist, cnt) = dijkstra_count::<i64>(nv, |u| adj[u].iter().copied(), [0]);
+
+            // 単純パスを全探索して数える
+            fn dfs(
+                u: usize,
+                d: i64,
+                adj: &[Vec<(usize, i64)>],
+                visited: &mut Vec<bool>,
+                paths: &mut Vec<Vec<i64>>,
+            ) {
+                paths[u].push(d);
+                for &(v, c) in &adj[u] {
+                    if !visited[v] {
+                        visited[v] = true;
+                        dfs(v, d + c, adj, visited, paths);
+                        visited[v] = false;
+                    }
+                }
+            }
+            let mut paths = vec![vec![]; nv];
+            let mut visited = vec![false; nv];
+            visited[0] = true;
+            dfs(0, 0, &adj, &mut visited, &mut paths);
+
+            for v in 0..nv {
+                let min = paths[v].iter().min().copied();
+                assert_eq!(dist[v], min);
+                let expected = paths[v].iter().filter(|&&d| Some(d) == min).count() as i64;
+                assert_eq!(cnt[v], expected);
+            }
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use cargo_snippet::snippet;
+
+#[allow(clippy::module_inception)]
+#[snippet(prefix = "use steiner_tree::*;")]
+pub mod steiner_tree {
+    use std::cmp::Reverse;
+    use std::collections::BinaryHeap;
+
+    /// 最小シュタイナー木 (指定した頂点集合をすべて含む木のうち、辺のコストの和が最小のもの) のコストを求める
+    ///
+    /// `dp[S][v]` = 「ターミナルの部分集合 S と頂点 v を含む木のコストの最小値」を、
+    /// 部分集合の統合とダイクストラ法による延長を交互に行って求める。
+    ///
+    /// # Arguments
+    /// * `adj` - `adj[u]` は `(v, cost)` のリスト (無向グラフの場合は両向きの辺を入れる)。コストは非負。
+    /// * `terminals` - 木に含める頂点のリスト
+    ///
+    /// # Returns
+    /// 最小シュタイナー木のコスト。ターミナルが連結でない場合は `None`。ターミナルが空の場合は `Some(0)`。
+    ///
+    /// # 計算量
+    /// O(3^k V + 2^k (V + E) log V) (k はターミナルの数)
+    pub fn steiner_tree(adj: &[Vec<(usize, i64)>], terminals: &[usize]) -> Option<i64> {
+        let nv = adj.len();
+        let k = terminals.len();
+        if k == 0 {
+            return Some(0);
+        }
+        const INF: i64 = i64::MAX;
+        let mut dp = vec![vec![INF; nv]; 1 << k];
+        for (i, &t) in terminals.iter().enumerate() {
+            dp[1 << i][t] = 0;
+        }
+        for s in 1_usize..1 << k {
+            // s を 2 つの空でない部分集合に分けて統合する
+            let mut sub = (s - 1) & s;
+            while sub > 0 {
+                // (sub, s ^ sub) と (s ^ sub, sub) は同じなので片方だけ
+                if sub < s ^ sub {
+                    for v in 0..nv {
+                        let (a, b) = (dp[sub][v], dp[s ^ sub][v]);
+                        if a != INF && b != INF {
+                            dp[s][v] = dp[s][v].min(a + b);
+                        }
+                    }
+                }
+                sub = (sub - 1) & s;
+            }
+
+            // ダイクストラ法で木を延長する
+            let dist = &mut dp[s];
+            let mut pq = (0..nv)
+                .filter(|&v| dist[v] != INF)
+                .map(|v| Reverse((dist[v], v)))
+                .collect::<BinaryHeap<_>>();
+            while let Some(Reverse((d, u))) = pq.pop() {
+                if dist[u] < d {
+                    continue;
+                }
+                for &(v, cost) in &adj[u] {
+                    if d + cost < dist[v] {
+                        dist[v] = d + cost;
+                        pq.push(Reverse((dist[v], v)));
+                    }
+                }
+            }
+        }
+        let d = dp[(1 << k) - 1][terminals[0]];
+        (d != INF).then_some(d)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::steiner_tree::*;
+    use crate::graph::graph::make_adj_from_weighted_undirected;
+
+    #[test]
+    fn test_steiner_tree() {
+        //     1
+        //   / | \
+        //  0  4  2
+        //   \ | /
+        //     3
+        // 中心の 4 を経由すると安い
+        let adj = make_adj_from_weighted_undirected(
+            5,
+            &[
+                (0, 1, 3),
+                (1, 2, 3),
+                (0, 3, 3),
+                (3, 2, 3),
+                (0, 4, 2),
+                (1, 4, 2),
+                (2, 4, 2),
+                (3, 4, 2),
+            ],
+        );
+        assert_eq!(steiner_tree(&adj, &[0, 1, 2]), Some(6));
+        assert_eq!(steiner_tree(&adj, &[0, 1]), Some(3));
+        assert_eq!(steiner_tree(&adj, &[2]), Some(0));
+        assert_eq!(steiner_tree(&adj, &[]), Some(0));
+
+        let adj = make_adj_from_weighted_undirected(3, &[(0, 1, 1)]);
+        assert_eq!(steiner_tree(&adj, &[0, 2]), None);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_steiner_tree_random() {
+        use crate::data_structure::dsu::dsu_core::dsu_core::DsuCore;
+        use rand::prelude::*;
+
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..300 {
+            let n = rng.random_range(1..=6);
+            let mut edges = vec![];
+            for u in 0..n {
+                for v in u + 1..n {
+                    if rng.random_bool(0.5) {
+                        edges.push((u, v, rng.random_range(0..=10)));
+                    }
+                }
+            }
+            let mut terminals = (0..n).filter(|_| rng.random_bool(0.5)).collect::<Vec<_>>();
+            terminals.shuffle(&mut rng);
+
+            // 辺の部分集合を全探索して、ターミナルがすべて連結になる最小コストを求める
+            let m = edges.len();
+            let expected = (0_usize..1 << m)
+                .filter_map(|mask| {
+                    let mut dsu = DsuCore::new(n);
+                    let mut cost = 0;
+                    for (i, &(u, v, c)) in edges.iter().enumerate() {
+                        if mask >> i & 1 == 1 {
+                            dsu.merge(u, v);
+                            cost += c;
+                        }
+                    }
+                    terminals
+                        .iter()
+                        .all(|&t| dsu.same(t, terminals[0]))
+                        .then_some(cost)
+                })
+                .min();
+            assert_eq!(
+                steiner_tree(&make_adj_from_weighted_undirected(n, &edges), &terminals),
+                expected
+            );
+        }
+    }
+}
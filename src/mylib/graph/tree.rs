@@ -1,8 +1,13 @@
+pub mod auxiliary_tree;
+pub mod centroid_decomposition;
 pub mod euler_tour;
+pub mod hld;
 pub mod lca;
 pub mod lca_doubling;
 pub mod lca_path_aggregate;
+pub mod link_cut_tree;
 pub mod reroot;
 #[allow(clippy::module_inception)]
 pub mod tree;
 pub mod tree_diameter;
+pub mod tree_isomorphism;
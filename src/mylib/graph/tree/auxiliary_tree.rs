@@ -0,0 +1,181 @@
+use cargo_snippet::snippet;
+
+use crate::graph::tree::lca::lca::Lca;
+
+#[allow(clippy::module_inception)]
+#[snippet(prefix = "use auxiliary_tree::*;", include = "lca")]
+pub mod auxiliary_tree {
+    use super::Lca;
+
+    /// Auxiliary Tree (虚树)
+    ///
+    /// 指定した頂点集合とそれらの LCA だけからなる、元の木の祖先・子孫関係を保った木を構築する。
+    pub struct AuxiliaryTree;
+
+    impl AuxiliaryTree {
+        /// 指定した頂点集合の Auxiliary Tree を構築する
+        ///
+        /// # Arguments
+        /// * `lca` - 元の木の `Lca`
+        /// * `vertices` - 頂点集合 (重複があってもよい)
+        ///
+        /// # Returns
+        /// `(頂点のリスト, 親のインデックス, 親との間の辺の長さ)`
+        /// * 頂点のリストは元の木での行きがけ順に並んでいて、先頭が Auxiliary Tree の根になる。
+        ///   指定した頂点とそれらのペアの LCA すべてを含み、長さは `2k - 1` 以下 (`k` は指定した頂点の種類数)。
+        /// * `parent[i]` は `i` 番目の頂点の親の頂点リストでのインデックス。根は `None`。
+        /// * `edge_lengths[i]` は `i` 番目の頂点と親の元の木での距離。根は 0。
+        ///
+        /// # 計算量
+        /// O(k log k log V)
+        ///
+        /// # Examples
+        /// ```ignore
+        /// let lca = Lca::new(&adj, 0);
+        /// let (vs, parent, edge_lengths) = AuxiliaryTree::build(&lca, &[3, 5, 6]);
+        /// // 子から順に処理して DP をする
+        /// for i in (1..vs.len()).rev() {
+        ///     let p = parent[i].unwrap();
+        ///     dp[p] += dp[i] + edge_lengths[i];
+        /// }
+        /// ```
+        pub fn build(lca: &Lca, vertices: &[usize]) -> (Vec<usize>, Vec<Option<usize>>, Vec<i64>) {
+            if vertices.is_empty() {
+                return (vec![], vec![], vec![]);
+            }
+            let mut vs = vertices.to_vec();
+            vs.sort_by_key(|&v| lca.in_time(v));
+            vs.dedup();
+            // 行きがけ順で隣り合う頂点の LCA を加えると、すべてのペアの LCA を含む
+            for i in 0..vs.len() - 1 {
+                vs.push(lca.lca(vs[i], vs[i + 1]));
+            }
+            vs.sort_by_key(|&v| lca.in_time(v));
+            vs.dedup();
+
+            let mut parent = vec![None; vs.len()];
+            let mut edge_lengths = vec![0; vs.len()];
+            // 根から現在の頂点までのパス上の頂点のインデックス
+            let mut stack: Vec<usize> = vec![0];
+            for i in 1..vs.len() {
+                while let Some(&top) = stack.last() {
+                    if lca.lca(vs[top], vs[i]) == vs[top] {
+                        break;
+                    }
+                    stack.pop();
+                }
+                let p = *stack.last().unwrap();
+                parent[i] = Some(p);
+                edge_lengths[i] = lca.depth(vs[i]) - lca.depth(vs[p]);
+                stack.push(i);
+            }
+            (vs, parent, edge_lengths)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::auxiliary_tree::*;
+    use crate::graph::graph::make_adj_from_undirected;
+    use crate::graph::tree::lca::lca::Lca;
+
+    #[test]
+    fn test_auxiliary_tree() {
+        // 0
+        // ├ 1
+        // │ ├ 3
+        // │ │ └ 6
+        // │ └ 4
+        // │   ├ 7
+        // │   ├ 8
+        // │   └ 9
+        // └ 2
+        //   └ 5
+        //     ├ 10
+        //     └ 11
+        let tree_parent = [0, 0, 0, 1, 1, 2, 3, 4, 4, 4, 5, 5];
+        let n = tree_parent.len();
+        let edges = (1..n).map(|v| (tree_parent[v], v)).collect::<Vec<_>>();
+        let adj = make_adj_from_undirected(n, &edges);
+        let lca = Lca::new(&adj, 0);
+
+        let (vs, parent, edge_lengths) = AuxiliaryTree::build(&lca, &[6, 8, 9, 6]);
+        assert_eq!(vs, vec![1, 6, 4, 8, 9]);
+        assert_eq!(parent, vec![None, Some(0), Some(0), Some(2), Some(2)]);
+        assert_eq!(edge_lengths, vec![0, 2, 1, 1, 1]);
+
+        let (vs, parent, edge_lengths) = AuxiliaryTree::build(&lca, &[10, 7]);
+        assert_eq!(vs, vec![0, 7, 10]);
+        assert_eq!(parent, vec![None, Some(0), Some(0)]);
+        assert_eq!(edge_lengths, vec![0, 3, 3]);
+
+        let (vs, parent, edge_lengths) = AuxiliaryTree::build(&lca, &[5]);
+        assert_eq!(vs, vec![5]);
+        assert_eq!(parent, vec![None]);
+        assert_eq!(edge_lengths, vec![0]);
+
+        assert_eq!(AuxiliaryTree::build(&lca, &[]), (vec![], vec![], vec![]));
+    }
+
+    #[test]
+    #[ignore]
+    fn test_auxiliary_tree_random() {
+        use rand::prelude::*;
+
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let n = rng.random_range(1..=30);
+            let tree_parent = (0..n)
+                .map(|v| if v == 0 { 0 } else { rng.random_range(0..v) })
+                .collect::<Vec<_>>();
+            let edges = (1..n).map(|v| (tree_parent[v], v)).collect::<Vec<_>>();
+            let adj = make_adj_from_undirected(n, &edges);
+            let lca = Lca::new(&adj, 0);
+            let is_ancestor = |a: usize, mut v: usize| loop {
+                if v == a {
+                    return true;
+                }
+                if v == 0 {
+                    return false;
+                }
+                v = tree_parent[v];
+            };
+
+            let k = rng.random_range(1..=n);
+            let marked = (0..k).map(|_| rng.random_range(0..n)).collect::<Vec<_>>();
+            let (vs, parent, edge_lengths) = AuxiliaryTree::build(&lca, &marked);
+
+            let mut distinct = marked.clone();
+            distinct.sort();
+            distinct.dedup();
+            assert!(vs.len() < 2 * distinct.len());
+            // 指定した頂点とすべてのペアの LCA を含み、それ以外は含まない
+            let mut expected = vec![];
+            for &a in &distinct {
+                for &b in &distinct {
+                    expected.push(lca.lca(a, b));
+                }
+            }
+            expected.sort();
+            expected.dedup();
+            let mut sorted_vs = vs.clone();
+            sorted_vs.sort();
+            assert_eq!(sorted_vs, expected);
+
+            // 親は元の木での祖先のうち、vs に含まれる最も近いもの
+            assert_eq!(parent[0], None);
+            for i in 1..vs.len() {
+                let p = parent[i].unwrap();
+                assert!(p < i);
+                assert!(is_ancestor(vs[p], vs[i]) && vs[p] != vs[i]);
+                assert_eq!(edge_lengths[i], lca.dist(vs[p], vs[i]));
+                let closest = (0..vs.len())
+                    .filter(|&j| j != i && is_ancestor(vs[j], vs[i]))
+                    .max_by_key(|&j| lca.depth(vs[j]))
+                    .unwrap();
+                assert_eq!(p, closest);
+            }
+        }
+    }
+}
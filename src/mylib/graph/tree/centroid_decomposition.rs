@@ -0,0 +1,402 @@
+use cargo_snippet::snippet;
+
+#[allow(clippy::module_inception)]
+#[snippet(prefix = "use centroid_decomposition::*;")]
+pub mod centroid_decomposition {
+    /// 重心分解 (Centroid Decomposition)
+    #[derive(Clone, Debug)]
+    pub struct CentroidDecomposition {
+        pub root: usize,                // 重心木の根 (最初に選ばれる重心)
+        pub parent: Vec<Option<usize>>, // parent[v]: 重心木における v の親
+        pub depth: Vec<usize>,          // depth[v]: 重心木における v の深さ (根は 0)
+        pub order: Vec<usize>,          // 重心として選ばれた順 (重心木の BFS 順)
+    }
+
+    impl CentroidDecomposition {
+        /// 重心分解を行う。
+        ///
+        /// # Arguments
+        /// * `adj` - 木の隣接リスト (無向グラフ)
+        ///
+        /// # 計算量
+        /// O(V log V)
+        pub fn new(adj: &[Vec<usize>]) -> Self {
+            let nv = adj.len();
+            assert!(nv > 0);
+            let mut parent = vec![None; nv];
+            let mut depth = vec![usize::MAX; nv]; // usize::MAX: まだ重心として選ばれていない
+            let mut order = Vec::with_capacity(nv);
+
+            let mut size = vec![0; nv];
+            let mut bfs_parent = vec![usize::MAX; nv];
+            let mut stack = vec![(0, None)]; // (成分内の頂点, 重心木での親)
+            while let Some((start, centroid_parent)) = stack.pop() {
+                // 成分の頂点を BFS で列挙する
+                let mut component = vec![start];
+                bfs_parent[start] = usize::MAX;
+                let mut i = 0;
+                while i < component.len() {
+                    let u = component[i];
+                    i += 1;
+                    for &v in &adj[u] {
+                        if v != bfs_parent[u] && depth[v] == usize::MAX {
+                            bfs_parent[v] = u;
+                            component.push(v);
+                        }
+                    }
+                }
+                for &u in component.iter().rev() {
+                    size[u] = 1;
+                    for &v in &adj[u] {
+                        if v != bfs_parent[u] && depth[v] == usize::MAX {
+                            size[u] += size[v];
+                        }
+                    }
+                }
+
+                // 重心を探す: 根から部分木サイズが半分を超える子へ降りていく
+                let total = component.len();
+                let mut centroid = start;
+                'search: loop {
+                    for &v in &adj[centroid] {
+                        if v != bfs_parent[centroid]
+                            && depth[v] == usize::MAX
+                            && size[v] * 2 > total
+                        {
+                            centroid = v;
+                            continue 'search;
+                        }
+                    }
+                    break;
+                }
+
+                parent[centroid] = centroid_parent;
+                depth[centroid] = centroid_parent.map_or(0, |p| depth[p] + 1);
+                order.push(centroid);
+                for &v in &adj[centroid] {
+                    if depth[v] == usize::MAX {
+                        stack.push((v, Some(centroid)));
+                    }
+                }
+            }
+
+            // stack (DFS) で処理したので、深さ順に並べ直す
+            order.sort_by_key(|&v| depth[v]);
+
+            CentroidDecomposition {
+                root: order[0],
+                parent,
+                depth,
+                order,
+            }
+        }
+
+        /// 各重心 `c` について、`c` を取り除いてできる各成分 (重心木における `c` の子の成分) の頂点を列挙し、`f` を呼ぶ。
+        ///
+        /// `f(c, components)` の `components[i]` は i 番目の成分に含まれる頂点と `c` からの距離 (辺の数) の組 `(v, dist)` のリストで、BFS 順 (距離の昇順) に並んでいる。
+        /// 重心 `c` 自身は `components` に含まれない。
+        ///
+        /// 重心は重心木の根に近い順に訪問する。
+        ///
+        /// # Arguments
+        /// * `adj` - 重心分解に使った木の隣接リスト
+        /// * `f` - 各重心に対して呼ばれるクロージャー
+        ///
+        /// # 計算量
+        /// O(V log V) (`f` の計算量を除く)
+        ///
+        /// # Examples
+        /// ```ignore
+        /// // 距離がちょうど k のパスの数を数える
+        /// let cd = CentroidDecomposition::new(&adj);
+        /// let mut ans = 0;
+        /// cd.for_each_component(&adj, |_c, components| {
+        ///     let mut cnt = vec![0; k + 1];
+        ///     cnt[0] = 1; // 重心自身
+        ///     for comp in components {
+        ///         for &(_, d) in comp {
+        ///             if d <= k { ans += cnt[k - d]; }
+        ///         }
+        ///         for &(_, d) in comp {
+        ///             if d <= k { cnt[d] += 1; }
+        ///         }
+        ///     }
+        /// });
+        /// ```
+        pub fn for_each_component<F>(&self, adj: &[Vec<usize>], mut f: F)
+        where
+            F: FnMut(usize, &[Vec<(usize, usize)>]),
+        {
+            let nv = adj.len();
+            let mut bfs_parent = vec![usize::MAX; nv];
+            for &c in &self.order {
+                let d = self.depth[c];
+                let components = adj[c]
+                    .iter()
+                    .copied()
+                    .filter(|&w| self.depth[w] > d)
+                    .map(|w| {
+                        let mut comp = vec![(w, 1)];
+                        bfs_parent[w] = c;
+                        let mut i = 0;
+                        while i < comp.len() {
+                            let (u, du) = comp[i];
+                            i += 1;
+                            for &v in &adj[u] {
+                                if v != bfs_parent[u] && self.depth[v] > d {
+                                    bfs_parent[v] = u;
+                                    comp.push((v, du + 1));
+                                }
+                            }
+                        }
+                        comp
+                    })
+                    .collect::<Vec<_>>();
+                f(c, &components);
+            }
+        }
+
+        /// 各頂点 `v` について、重心木での祖先 (`v` 自身を含む) と `v` からの距離の組 `(c, dist)` のリストを求める。
+        ///
+        /// 返り値を `anc` とすると、`anc[v]` は重心木の根に近い順に並んでいる。
+        ///
+        /// # 計算量
+        /// O(V log V)
+        pub fn ancestors_with_dist(&self, adj: &[Vec<usize>]) -> Vec<Vec<(usize, usize)>> {
+            let mut anc = vec![vec![]; adj.len()];
+            self.for_each_component(adj, |c, components| {
+                anc[c].push((c, 0));
+                for comp in components {
+                    for &(v, d) in comp {
+                        anc[v].push((c, d));
+                    }
+                }
+            });
+            anc
+        }
+    }
+
+    /// 重心分解を用いて「頂点に印をつける」「最も近い印のついた頂点までの距離を求める」を処理する。
+    ///
+    /// 印を外す操作には対応していない。
+    #[derive(Clone, Debug)]
+    pub struct NearestMarkedVertex {
+        ancestors: Vec<Vec<(usize, usize)>>, // ancestors[v]: 重心木での祖先と v からの距離
+        nearest: Vec<Option<usize>>, // nearest[c]: 重心 c の成分内で印のついた頂点までの最短距離
+    }
+
+    impl NearestMarkedVertex {
+        /// 印のついた頂点がない状態で構築する。
+        ///
+        /// # Arguments
+        /// * `adj` - 木の隣接リスト (無向グラフ)
+        ///
+        /// # 計算量
+        /// O(V log V)
+        pub fn new(adj: &[Vec<usize>]) -> Self {
+            let cd = CentroidDecomposition::new(adj);
+            NearestMarkedVertex {
+                ancestors: cd.ancestors_with_dist(adj),
+                nearest: vec![None; adj.len()],
+            }
+        }
+
+        /// 頂点 `v` に印をつける
+        ///
+        /// # 計算量
+        /// O(log V)
+        pub fn mark(&mut self, v: usize) {
+            for &(c, d) in &self.ancestors[v] {
+                if self.nearest[c].is_none_or(|cur| d < cur) {
+                    self.nearest[c] = Some(d);
+                }
+            }
+        }
+
+        /// 頂点 `v` から最も近い印のついた頂点までの距離を返す。印のついた頂点がない場合は `None`
+        ///
+        /// # 計算量
+        /// O(log V)
+        pub fn nearest_dist(&self, v: usize) -> Option<usize> {
+            self.ancestors[v]
+                .iter()
+                .filter_map(|&(c, d)| self.nearest[c].map(|x| x + d))
+                .min()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::centroid_decomposition::*;
+    use crate::graph::graph::make_adj_from_undirected;
+    use rand::prelude::*;
+    use std::collections::VecDeque;
+
+    fn dist_from(adj: &[Vec<usize>], s: usize) -> Vec<usize> {
+        let mut dist = vec![usize::MAX; adj.len()];
+        dist[s] = 0;
+        let mut q = VecDeque::from([s]);
+        while let Some(u) = q.pop_front() {
+            for &v in &adj[u] {
+                if dist[v] == usize::MAX {
+                    dist[v] = dist[u] + 1;
+                    q.push_back(v);
+                }
+            }
+        }
+        dist
+    }
+
+    fn random_tree(rng: &mut StdRng, n: usize) -> Vec<Vec<usize>> {
+        let edges = (1..n)
+            .map(|v| (rng.random_range(0..v), v))
+            .collect::<Vec<_>>();
+        make_adj_from_undirected(n, &edges)
+    }
+
+    #[test]
+    fn test_centroid_decomposition_path() {
+        // 0 - 1 - 2 - 3 - 4 - 5 - 6
+        let adj = make_adj_from_undirected(7, &[(0, 1), (1, 2), (2, 3), (3, 4), (4, 5), (5, 6)]);
+        let cd = CentroidDecomposition::new(&adj);
+        assert_eq!(cd.root, 3);
+        assert_eq!(cd.parent[3], None);
+        assert_eq!(cd.parent[1], Some(3));
+        assert_eq!(cd.parent[5], Some(3));
+        assert_eq!(cd.parent[0], Some(1));
+        assert_eq!(cd.parent[6], Some(5));
+        assert_eq!(cd.depth, vec![2, 1, 2, 0, 2, 1, 2]);
+        assert_eq!(cd.order[0], 3);
+    }
+
+    #[test]
+    fn test_for_each_component() {
+        // 0 - 1 - 2
+        //     |
+        //     3 - 4
+        let adj = make_adj_from_undirected(5, &[(0, 1), (1, 2), (1, 3), (3, 4)]);
+        let cd = CentroidDecomposition::new(&adj);
+        assert_eq!(cd.root, 1);
+        let mut visited = vec![];
+        cd.for_each_component(&adj, |c, components| {
+            let mut components = components.to_vec();
+            components.sort();
+            visited.push((c, components));
+        });
+        assert_eq!(
+            visited[0],
+            (1, vec![vec![(0, 1)], vec![(2, 1)], vec![(3, 1), (4, 2)]])
+        );
+        assert_eq!(visited.len(), 5);
+    }
+
+    /// 長さ k のパスの個数を重心分解で数える
+    fn count_paths_of_length_k(adj: &[Vec<usize>], k: usize) -> usize {
+        let cd = CentroidDecomposition::new(adj);
+        let mut ans = 0_usize;
+        cd.for_each_component(adj, |_c, components| {
+            let mut cnt = vec![0_usize; k + 1];
+            cnt[0] = 1;
+            for comp in components {
+                for &(_, d) in comp {
+                    if d <= k {
+                        ans += cnt[k - d];
+                    }
+                }
+                for &(_, d) in comp {
+                    if d <= k {
+                        cnt[d] += 1;
+                    }
+                }
+            }
+        });
+        ans
+    }
+
+    #[test]
+    fn test_count_paths_of_length_k() {
+        // 0 - 1 - 2
+        //     |
+        //     3 - 4
+        let adj = make_adj_from_undirected(5, &[(0, 1), (1, 2), (1, 3), (3, 4)]);
+        assert_eq!(count_paths_of_length_k(&adj, 1), 4);
+        assert_eq!(count_paths_of_length_k(&adj, 2), 4);
+        assert_eq!(count_paths_of_length_k(&adj, 3), 2);
+        assert_eq!(count_paths_of_length_k(&adj, 4), 0);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_count_paths_of_length_k_random() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..50 {
+            let n = rng.random_range(1..=30);
+            let adj = random_tree(&mut rng, n);
+            let k = rng.random_range(1..=5);
+
+            let naive = (0..n)
+                .map(|u| {
+                    let dist = dist_from(&adj, u);
+                    (u + 1..n).filter(|&v| dist[v] == k).count()
+                })
+                .sum::<usize>();
+            assert_eq!(count_paths_of_length_k(&adj, k), naive);
+        }
+    }
+
+    #[test]
+    fn test_nearest_marked_vertex() {
+        // 0 - 1 - 2 - 3 - 4
+        let adj = make_adj_from_undirected(5, &[(0, 1), (1, 2), (2, 3), (3, 4)]);
+        let mut nm = NearestMarkedVertex::new(&adj);
+        assert_eq!(nm.nearest_dist(0), None);
+        nm.mark(4);
+        assert_eq!(nm.nearest_dist(0), Some(4));
+        nm.mark(1);
+        assert_eq!(nm.nearest_dist(0), Some(1));
+        assert_eq!(nm.nearest_dist(3), Some(1));
+        assert_eq!(nm.nearest_dist(1), Some(0));
+    }
+
+    #[test]
+    #[ignore]
+    fn test_centroid_decomposition_random() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let n = rng.random_range(1..=40);
+            let adj = random_tree(&mut rng, n);
+            let cd = CentroidDecomposition::new(&adj);
+
+            // 重心木の深さは O(log V)
+            let max_depth = *cd.depth.iter().max().unwrap();
+            assert!(1 << max_depth <= n);
+
+            // 各成分のサイズは重心を含む成分の半分以下
+            cd.for_each_component(&adj, |c, components| {
+                let total = 1 + components.iter().map(|comp| comp.len()).sum::<usize>();
+                for comp in components {
+                    assert!(comp.len() * 2 <= total);
+                    for &(v, _) in comp {
+                        assert!(cd.depth[v] > cd.depth[c]);
+                    }
+                }
+            });
+
+            // NearestMarkedVertex
+            let mut nm = NearestMarkedVertex::new(&adj);
+            let mut marked = vec![];
+            for _ in 0..20 {
+                let v = rng.random_range(0..n);
+                if rng.random_bool(0.3) {
+                    nm.mark(v);
+                    marked.push(v);
+                } else {
+                    let dist = dist_from(&adj, v);
+                    let expected = marked.iter().map(|&m| dist[m]).min();
+                    assert_eq!(nm.nearest_dist(v), expected);
+                }
+            }
+        }
+    }
+}
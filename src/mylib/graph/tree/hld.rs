@@ -0,0 +1,1013 @@
+use cargo_snippet::snippet;
+
+#[allow(clippy::module_inception)]
+#[snippet(prefix = "use hld::*;")]
+pub mod hld {
+    use std::ops::Range;
+
+    /// HL 分解 (Heavy-Light Decomposition)
+    ///
+    /// 各頂点を列上の位置 `index(v)` に対応付ける。
+    /// heavy path は列上で連続する区間になり、部分木も連続する区間になる。
+    #[derive(Clone, Debug)]
+    pub struct Hld {
+        parent: Vec<usize>, // parent[v]: v の親 (根の場合は自身)
+        depth: Vec<usize>,  // depth[v]: 根から v までの辺の数
+        head: Vec<usize>,   // head[v]: v が属する heavy path の先頭 (根に最も近い頂点)
+        in_time: Vec<usize>,
+        out_time: Vec<usize>,
+        order: Vec<usize>, // order[i]: 列上の位置 i にある頂点 (in_time の逆写像)
+    }
+
+    impl Hld {
+        /// HL 分解を構築する。
+        ///
+        /// # Arguments
+        /// * `adj` - 木の隣接リスト (無向グラフまたは、親から子への有向グラフ)
+        /// * `root` - 根の頂点番号
+        ///
+        /// # 計算量
+        /// O(V)
+        pub fn new(adj: &[Vec<usize>], root: usize) -> Self {
+            let nv = adj.len();
+
+            // BFS 順に親と深さを求める
+            let mut parent = vec![root; nv];
+            let mut depth = vec![0; nv];
+            let mut bfs_order = Vec::with_capacity(nv);
+            let mut visited = vec![false; nv];
+            visited[root] = true;
+            bfs_order.push(root);
+            let mut i = 0;
+            while i < bfs_order.len() {
+                let u = bfs_order[i];
+                i += 1;
+                for &v in &adj[u] {
+                    if !visited[v] {
+                        visited[v] = true;
+                        parent[v] = u;
+                        depth[v] = depth[u] + 1;
+                        bfs_order.push(v);
+                    }
+                }
+            }
+
+            // 部分木のサイズと heavy child を求める
+            let mut size = vec![1; nv];
+            for &v in bfs_order.iter().skip(1).rev() {
+                size[parent[v]] += size[v];
+            }
+            let mut children = vec![vec![]; nv];
+            for &v in bfs_order.iter().skip(1) {
+                children[parent[v]].push(v);
+            }
+            for ch in &mut children {
+                // heavy child を先頭に置く
+                if let Some(pos) = (0..ch.len()).max_by_key(|&i| size[ch[i]]) {
+                    ch.swap(0, pos);
+                }
+            }
+
+            // heavy child を優先して DFS し、位置を割り当てる
+            let mut head = vec![root; nv];
+            let mut in_time = vec![0; nv];
+            let mut out_time = vec![0; nv];
+            let mut order = Vec::with_capacity(nv);
+            let mut stack = vec![(root, false)];
+            while let Some((u, is_out)) = stack.pop() {
+                if is_out {
+                    out_time[u] = order.len();
+                    continue;
+                }
+                in_time[u] = order.len();
+                order.push(u);
+                stack.push((u, true));
+                for (i, &v) in children[u].iter().enumerate().rev() {
+                    head[v] = if i == 0 { head[u] } else { v };
+                    stack.push((v, false));
+                }
+            }
+
+            Hld {
+                parent,
+                depth,
+                head,
+                in_time,
+                out_time,
+                order,
+            }
+        }
+
+        /// 頂点数を返す
+        #[allow(clippy::len_without_is_empty)]
+        pub fn len(&self) -> usize {
+            self.order.len()
+        }
+
+        /// 頂点 `v` の列上の位置を返す
+        ///
+        /// # 計算量
+        /// O(1)
+        pub fn index(&self, v: usize) -> usize {
+            self.in_time[v]
+        }
+
+        /// 列上の位置 `i` にある頂点を返す
+        ///
+        /// # 計算量
+        /// O(1)
+        pub fn vertex(&self, i: usize) -> usize {
+            self.order[i]
+        }
+
+        /// 辺 `u-v` の列上の位置 (子側の頂点の位置) を返す
+        ///
+        /// # 計算量
+        /// O(1)
+        pub fn edge_index(&self, u: usize, v: usize) -> usize {
+            if self.parent[v] == u {
+                self.in_time[v]
+            } else {
+                assert_eq!(self.parent[u], v, "u-v is not an edge of the tree");
+                self.in_time[u]
+            }
+        }
+
+        /// 頂点 `v` の親を返す。`v` が根の場合は `None`
+        pub fn parent(&self, v: usize) -> Option<usize> {
+            (self.parent[v] != v).then_some(self.parent[v])
+        }
+
+        /// 根から頂点 `v` までの辺の数を返す
+        pub fn depth(&self, v: usize) -> usize {
+            self.depth[v]
+        }
+
+        /// u と v の LCA を求める
+        ///
+        /// # 計算量
+        /// O(log V)
+        pub fn lca(&self, u: usize, v: usize) -> usize {
+            let mut u = u;
+            let mut v = v;
+            while self.head[u] != self.head[v] {
+                if self.depth[self.head[u]] > self.depth[self.head[v]] {
+                    u = self.parent[self.head[u]];
+                } else {
+                    v = self.parent[self.head[v]];
+                }
+            }
+            if self.depth[u] < self.depth[v] { u } else { v }
+        }
+
+        /// 頂点 u と v の距離 (辺の数) を求める
+        ///
+        /// # 計算量
+        /// O(log V)
+        pub fn dist(&self, u: usize, v: usize) -> usize {
+            self.depth[u] + self.depth[v] - 2 * self.depth[self.lca(u, v)]
+        }
+
+        /// 頂点 `v` の `k` 個上の祖先を返す。存在しない場合は `None`
+        ///
+        /// # 計算量
+        /// O(log V)
+        pub fn kth_ancestor(&self, v: usize, k: usize) -> Option<usize> {
+            if k > self.depth[v] {
+                return None;
+            }
+            let target_depth = self.depth[v] - k;
+            let mut v = v;
+            while self.depth[self.head[v]] > target_depth {
+                v = self.parent[self.head[v]];
+            }
+            Some(self.order[self.in_time[v] - (self.depth[v] - target_depth)])
+        }
+
+        fn path_ranges_impl(&self, u: usize, v: usize, edge: bool) -> Vec<(Range<usize>, bool)> {
+            let mut u = u;
+            let mut v = v;
+            let mut up = vec![]; // u 側 (列の逆向きにたどる)
+            let mut down = vec![]; // v 側 (列の順向きにたどる)
+            while self.head[u] != self.head[v] {
+                if self.depth[self.head[u]] > self.depth[self.head[v]] {
+                    up.push((self.in_time[self.head[u]]..self.in_time[u] + 1, true));
+                    u = self.parent[self.head[u]];
+                } else {
+                    down.push((self.in_time[self.head[v]]..self.in_time[v] + 1, false));
+                    v = self.parent[self.head[v]];
+                }
+            }
+            let e = edge as usize;
+            if self.depth[u] >= self.depth[v] {
+                if self.in_time[v] + e < self.in_time[u] + 1 {
+                    up.push((self.in_time[v] + e..self.in_time[u] + 1, true));
+                }
+            } else {
+                down.push((self.in_time[u] + e..self.in_time[v] + 1, false));
+            }
+            up.extend(down.into_iter().rev());
+            up
+        }
+
+        /// u から v へのパス上の頂点を、列上の区間の列に分解する。
+        ///
+        /// # Returns
+        /// u から v へたどる順に並んだ `(range, reversed)` のリスト。
+        /// `reversed == true` の区間は、列上のインデックスの降順にたどることを表す。
+        ///
+        /// # 計算量
+        /// O(log V)
+        pub fn path_ranges(&self, u: usize, v: usize) -> Vec<(Range<usize>, bool)> {
+            self.path_ranges_impl(u, v, false)
+        }
+
+        /// u から v へのパス上の辺を、列上の区間の列に分解する。
+        ///
+        /// 辺 `(親 p, 子 c)` は位置 `index(c)` に対応する。
+        ///
+        /// # Returns
+        /// u から v へたどる順に並んだ `(range, reversed)` のリスト。
+        /// `reversed == true` の区間は、列上のインデックスの降順にたどることを表す。
+        ///
+        /// # 計算量
+        /// O(log V)
+        pub fn edge_path_ranges(&self, u: usize, v: usize) -> Vec<(Range<usize>, bool)> {
+            self.path_ranges_impl(u, v, true)
+        }
+
+        /// 頂点 `v` の部分木に含まれる頂点に対応する列上の区間を返す
+        ///
+        /// # 計算量
+        /// O(1)
+        pub fn subtree_range(&self, v: usize) -> Range<usize> {
+            self.in_time[v]..self.out_time[v]
+        }
+
+        /// 頂点 `v` の部分木に含まれる辺に対応する列上の区間を返す
+        ///
+        /// # 計算量
+        /// O(1)
+        pub fn edge_subtree_range(&self, v: usize) -> Range<usize> {
+            self.in_time[v] + 1..self.out_time[v]
+        }
+    }
+}
+
+use crate::graph::tree::hld::hld::Hld;
+
+#[snippet(prefix = "use hld_segtree::*;", include = "hld")]
+pub mod hld_segtree {
+    use super::Hld;
+    use ac_library::{Monoid, Segtree};
+    use std::convert::Infallible;
+    use std::marker::PhantomData;
+    use std::ops::Range;
+
+    /// 二項演算の引数を入れ替えたモノイド
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    pub struct ReversedMonoid<M>(Infallible, PhantomData<fn() -> M>);
+    impl<M: Monoid> Monoid for ReversedMonoid<M> {
+        type S = M::S;
+        fn identity() -> Self::S {
+            M::identity()
+        }
+        fn binary_operation(a: &Self::S, b: &Self::S) -> Self::S {
+            M::binary_operation(b, a)
+        }
+    }
+
+    /// HL 分解上のセグメント木。頂点重みと辺重みのどちらにも対応する。
+    ///
+    /// 非可換なモノイドでも `path_prod(u, v)` は u から v へたどる順に積を取る。
+    #[derive(Clone)]
+    pub struct HldSegtree<M: Monoid> {
+        hld: Hld,
+        edge: bool,
+        segtree: Segtree<M>,
+        segtree_rev: Segtree<ReversedMonoid<M>>,
+    }
+
+    impl<M: Monoid> HldSegtree<M> {
+        fn from_hld(hld: Hld, edge: bool, xs: Vec<M::S>) -> Self {
+            HldSegtree {
+                hld,
+                edge,
+                segtree: Segtree::from(xs.clone()),
+                segtree_rev: Segtree::from(xs),
+            }
+        }
+
+        /// 頂点重みの HL 分解セグメント木を構築する。
+        ///
+        /// # Arguments
+        /// * `adj` - 木の隣接リスト (無向グラフまたは、親から子への有向グラフ)
+        /// * `root` - 根の頂点番号
+        /// * `xs` - `xs[v]` は頂点 `v` の重み
+        ///
+        /// # 計算量
+        /// O(V)
+        pub fn new(adj: &[Vec<usize>], root: usize, xs: &[M::S]) -> Self {
+            let hld = Hld::new(adj, root);
+            let seq = (0..hld.len())
+                .map(|i| xs[hld.vertex(i)].clone())
+                .collect::<Vec<_>>();
+            Self::from_hld(hld, false, seq)
+        }
+
+        /// 辺重みの HL 分解セグメント木を構築する。
+        ///
+        /// # Arguments
+        /// * `adj` - 木の隣接リスト (無向グラフ、または親から子への有向グラフ)。`adj[u]` は `(v, w)` のリスト。ただし、`v` は隣接ノード、`w` は辺 `u-v` の重み。
+        /// * `root` - 根の頂点番号
+        ///
+        /// # 計算量
+        /// O(V)
+        pub fn new_edge(adj: &[Vec<(usize, M::S)>], root: usize) -> Self {
+            let adj_vertex = adj
+                .iter()
+                .map(|es| es.iter().map(|(v, _)| *v).collect::<Vec<_>>())
+                .collect::<Vec<_>>();
+            let hld = Hld::new(&adj_vertex, root);
+            let mut seq = vec![M::identity(); hld.len()];
+            for (u, es) in adj.iter().enumerate() {
+                for (v, w) in es {
+                    if hld.parent(*v) == Some(u) {
+                        seq[hld.index(*v)] = w.clone();
+                    }
+                }
+            }
+            Self::from_hld(hld, true, seq)
+        }
+
+        /// 内部の HL 分解を返す
+        pub fn hld(&self) -> &Hld {
+            &self.hld
+        }
+
+        fn set_at(&mut self, i: usize, x: M::S) {
+            self.segtree.set(i, x.clone());
+            self.segtree_rev.set(i, x);
+        }
+
+        /// 頂点 `v` の重みを `x` にする (頂点重み)
+        ///
+        /// # 計算量
+        /// O(log V)
+        pub fn set(&mut self, v: usize, x: M::S) {
+            assert!(!self.edge, "use set_edge for edge-weighted HldSegtree");
+            self.set_at(self.hld.index(v), x);
+        }
+
+        /// 辺 `u-v` の重みを `x` にする (辺重み)
+        ///
+        /// # 計算量
+        /// O(log V)
+        pub fn set_edge(&mut self, u: usize, v: usize, x: M::S) {
+            assert!(self.edge, "use set for vertex-weighted HldSegtree");
+            self.set_at(self.hld.edge_index(u, v), x);
+        }
+
+        /// 頂点 `v` の重みを返す (頂点重み)
+        pub fn get(&self, v: usize) -> M::S {
+            assert!(!self.edge, "use get_edge for edge-weighted HldSegtree");
+            self.segtree.get(self.hld.index(v))
+        }
+
+        /// 辺 `u-v` の重みを返す (辺重み)
+        pub fn get_edge(&self, u: usize, v: usize) -> M::S {
+            assert!(self.edge, "use get for vertex-weighted HldSegtree");
+            self.segtree.get(self.hld.edge_index(u, v))
+        }
+
+        fn ranges(&self, u: usize, v: usize) -> Vec<(Range<usize>, bool)> {
+            if self.edge {
+                self.hld.edge_path_ranges(u, v)
+            } else {
+                self.hld.path_ranges(u, v)
+            }
+        }
+
+        /// u から v へのパス上の重みを、u 側から順に掛けた積を返す
+        ///
+        /// 辺重みの場合はパス上の辺、頂点重みの場合はパス上の頂点 (u, v を含む) の積となる。
+        ///
+        /// # 計算量
+        /// O(log^2 V)
+        pub fn path_prod(&self, u: usize, v: usize) -> M::S {
+            self.ranges(u, v)
+                .into_iter()
+                .fold(M::identity(), |acc, (range, reversed)| {
+                    let x = if reversed {
+                        self.segtree_rev.prod(range)
+                    } else {
+                        self.segtree.prod(range)
+                    };
+                    M::binary_operation(&acc, &x)
+                })
+        }
+
+        /// 頂点 `v` の部分木に含まれる重みの積を返す
+        ///
+        /// 積の順序は列上の順序となるため、可換なモノイドでの利用を想定している。
+        ///
+        /// # 計算量
+        /// O(log V)
+        pub fn subtree_prod(&self, v: usize) -> M::S {
+            if self.edge {
+                self.segtree.prod(self.hld.edge_subtree_range(v))
+            } else {
+                self.segtree.prod(self.hld.subtree_range(v))
+            }
+        }
+    }
+}
+
+#[snippet(prefix = "use hld_lazy_segtree::*;", include = "hld_segtree")]
+pub mod hld_lazy_segtree {
+    use super::Hld;
+    use super::hld_segtree::ReversedMonoid;
+    use ac_library::{LazySegtree, MapMonoid, Monoid};
+    use std::convert::Infallible;
+    use std::marker::PhantomData;
+    use std::ops::Range;
+
+    type S<F> = <<F as MapMonoid>::M as Monoid>::S;
+
+    /// `F::M` の二項演算の引数を入れ替えた作用付きモノイド
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    pub struct ReversedMapMonoid<F>(Infallible, PhantomData<fn() -> F>);
+    impl<F: MapMonoid> MapMonoid for ReversedMapMonoid<F> {
+        type M = ReversedMonoid<F::M>;
+        type F = F::F;
+        fn identity_map() -> Self::F {
+            F::identity_map()
+        }
+        fn mapping(f: &Self::F, x: &<Self::M as Monoid>::S) -> <Self::M as Monoid>::S {
+            F::mapping(f, x)
+        }
+        fn composition(f: &Self::F, g: &Self::F) -> Self::F {
+            F::composition(f, g)
+        }
+    }
+
+    /// HL 分解上の遅延セグメント木。頂点重みと辺重みのどちらにも対応する。
+    ///
+    /// 非可換なモノイドでも `path_prod(u, v)` は u から v へたどる順に積を取る。
+    pub struct HldLazySegtree<F: MapMonoid> {
+        hld: Hld,
+        edge: bool,
+        segtree: LazySegtree<F>,
+        segtree_rev: LazySegtree<ReversedMapMonoid<F>>,
+    }
+
+    impl<F: MapMonoid> HldLazySegtree<F> {
+        fn from_hld(hld: Hld, edge: bool, xs: Vec<S<F>>) -> Self {
+            HldLazySegtree {
+                hld,
+                edge,
+                segtree: LazySegtree::from(xs.clone()),
+                segtree_rev: LazySegtree::from(xs),
+            }
+        }
+
+        /// 頂点重みの HL 分解遅延セグメント木を構築する。
+        ///
+        /// # Arguments
+        /// * `adj` - 木の隣接リスト (無向グラフまたは、親から子への有向グラフ)
+        /// * `root` - 根の頂点番号
+        /// * `xs` - `xs[v]` は頂点 `v` の重み
+        ///
+        /// # 計算量
+        /// O(V)
+        pub fn new(adj: &[Vec<usize>], root: usize, xs: &[S<F>]) -> Self {
+            let hld = Hld::new(adj, root);
+            let seq = (0..hld.len())
+                .map(|i| xs[hld.vertex(i)].clone())
+                .collect::<Vec<_>>();
+            Self::from_hld(hld, false, seq)
+        }
+
+        /// 辺重みの HL 分解遅延セグメント木を構築する。
+        ///
+        /// # Arguments
+        /// * `adj` - 木の隣接リスト (無向グラフ、または親から子への有向グラフ)。`adj[u]` は `(v, w)` のリスト。ただし、`v` は隣接ノード、`w` は辺 `u-v` の重み。
+        /// * `root` - 根の頂点番号
+        ///
+        /// # 計算量
+        /// O(V)
+        pub fn new_edge(adj: &[Vec<(usize, S<F>)>], root: usize) -> Self {
+            let adj_vertex = adj
+                .iter()
+                .map(|es| es.iter().map(|(v, _)| *v).collect::<Vec<_>>())
+                .collect::<Vec<_>>();
+            let hld = Hld::new(&adj_vertex, root);
+            let mut seq = vec![F::M::identity(); hld.len()];
+            for (u, es) in adj.iter().enumerate() {
+                for (v, w) in es {
+                    if hld.parent(*v) == Some(u) {
+                        seq[hld.index(*v)] = w.clone();
+                    }
+                }
+            }
+            Self::from_hld(hld, true, seq)
+        }
+
+        /// 内部の HL 分解を返す
+        pub fn hld(&self) -> &Hld {
+            &self.hld
+        }
+
+        fn set_at(&mut self, i: usize, x: S<F>) {
+            self.segtree.set(i, x.clone());
+            self.segtree_rev.set(i, x);
+        }
+
+        /// 頂点 `v` の重みを `x` にする (頂点重み)
+        ///
+        /// # 計算量
+        /// O(log V)
+        pub fn set(&mut self, v: usize, x: S<F>) {
+            assert!(!self.edge, "use set_edge for edge-weighted HldLazySegtree");
+            self.set_at(self.hld.index(v), x);
+        }
+
+        /// 辺 `u-v` の重みを `x` にする (辺重み)
+        ///
+        /// # 計算量
+        /// O(log V)
+        pub fn set_edge(&mut self, u: usize, v: usize, x: S<F>) {
+            assert!(self.edge, "use set for vertex-weighted HldLazySegtree");
+            self.set_at(self.hld.edge_index(u, v), x);
+        }
+
+        /// 頂点 `v` の重みを返す (頂点重み)
+        pub fn get(&mut self, v: usize) -> S<F> {
+            assert!(!self.edge, "use get_edge for edge-weighted HldLazySegtree");
+            self.segtree.get(self.hld.index(v))
+        }
+
+        /// 辺 `u-v` の重みを返す (辺重み)
+        pub fn get_edge(&mut self, u: usize, v: usize) -> S<F> {
+            assert!(self.edge, "use get for vertex-weighted HldLazySegtree");
+            self.segtree.get(self.hld.edge_index(u, v))
+        }
+
+        fn ranges(&self, u: usize, v: usize) -> Vec<(Range<usize>, bool)> {
+            if self.edge {
+                self.hld.edge_path_ranges(u, v)
+            } else {
+                self.hld.path_ranges(u, v)
+            }
+        }
+
+        /// u から v へのパス上の重みを、u 側から順に掛けた積を返す
+        ///
+        /// 辺重みの場合はパス上の辺、頂点重みの場合はパス上の頂点 (u, v を含む) の積となる。
+        ///
+        /// # 計算量
+        /// O(log^2 V)
+        pub fn path_prod(&mut self, u: usize, v: usize) -> S<F> {
+            let mut acc = F::M::identity();
+            for (range, reversed) in self.ranges(u, v) {
+                let x = if reversed {
+                    self.segtree_rev.prod(range)
+                } else {
+                    self.segtree.prod(range)
+                };
+                acc = F::M::binary_operation(&acc, &x);
+            }
+            acc
+        }
+
+        /// u から v へのパス上の重みに `f` を作用させる
+        ///
+        /// # 計算量
+        /// O(log^2 V)
+        pub fn path_apply(&mut self, u: usize, v: usize, f: F::F) {
+            for (range, _) in self.ranges(u, v) {
+                self.segtree.apply_range(range.clone(), f.clone());
+                self.segtree_rev.apply_range(range, f.clone());
+            }
+        }
+
+        /// 頂点 `v` の部分木に含まれる重みの積を返す
+        ///
+        /// 積の順序は列上の順序となるため、可換なモノイドでの利用を想定している。
+        ///
+        /// # 計算量
+        /// O(log V)
+        pub fn subtree_prod(&mut self, v: usize) -> S<F> {
+            let range = if self.edge {
+                self.hld.edge_subtree_range(v)
+            } else {
+                self.hld.subtree_range(v)
+            };
+            self.segtree.prod(range)
+        }
+
+        /// 頂点 `v` の部分木に含まれる重みに `f` を作用させる
+        ///
+        /// # 計算量
+        /// O(log V)
+        pub fn subtree_apply(&mut self, v: usize, f: F::F) {
+            let range = if self.edge {
+                self.hld.edge_subtree_range(v)
+            } else {
+                self.hld.subtree_range(v)
+            };
+            self.segtree.apply_range(range.clone(), f.clone());
+            self.segtree_rev.apply_range(range, f);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::hld::Hld;
+    use super::hld_lazy_segtree::HldLazySegtree;
+    use super::hld_segtree::HldSegtree;
+    use crate::math::algebra::monoid::monoid_affine::{AffineComposition, AffineTransform};
+    use crate::data_structure::segtree_lib::lazy_segtree::range_add_range_sum::range_add_range_sum::{
+        RangeAddRangeSum, RangeSum,
+    };
+    use crate::graph::graph::make_adj_from_undirected;
+    use ac_library::{Additive, Monoid};
+    use rand::prelude::*;
+
+    /// 親の配列 (根は自分自身を親とする) から隣接リストを作る
+    fn adj_from_parent(tree_parent: &[usize]) -> Vec<Vec<usize>> {
+        let edges = (0..tree_parent.len())
+            .filter(|&v| tree_parent[v] != v)
+            .map(|v| (tree_parent[v], v))
+            .collect::<Vec<_>>();
+        make_adj_from_undirected(tree_parent.len(), &edges)
+    }
+
+    fn path_naive(tree_parent: &[usize], u: usize, v: usize) -> Vec<usize> {
+        let ancestors = |x: usize| {
+            std::iter::successors(Some(x), |&acc| {
+                (tree_parent[acc] != acc).then_some(tree_parent[acc])
+            })
+            .collect::<Vec<_>>()
+        };
+        let u_anc = ancestors(u);
+        let v_anc = ancestors(v);
+        let lca = *u_anc.iter().find(|x| v_anc.contains(x)).unwrap();
+        let mut path = u_anc
+            .iter()
+            .copied()
+            .take_while(|&x| x != lca)
+            .collect::<Vec<_>>();
+        path.push(lca);
+        let mut down = v_anc
+            .iter()
+            .copied()
+            .take_while(|&x| x != lca)
+            .collect::<Vec<_>>();
+        down.reverse();
+        path.extend(down);
+        path
+    }
+
+    #[test]
+    fn test_hld() {
+        // 0
+        // ├ 1
+        // │ ├ 3
+        // │ │ └ 6
+        // │ └ 4
+        // │   ├ 7
+        // │   ├ 8
+        // │   └ 9
+        // └ 2
+        //   └ 5
+        //     ├ 10
+        //     └ 11
+        let tree_parent = [0, 0, 0, 1, 1, 2, 3, 4, 4, 4, 5, 5];
+        let adj = adj_from_parent(&tree_parent);
+        let hld = Hld::new(&adj, 0);
+
+        assert_eq!(hld.lca(6, 9), 1);
+        assert_eq!(hld.lca(9, 10), 0);
+        assert_eq!(hld.lca(1, 6), 1);
+        assert_eq!(hld.dist(6, 9), 4);
+        assert_eq!(hld.parent(0), None);
+        assert_eq!(hld.parent(7), Some(4));
+        assert_eq!(hld.kth_ancestor(6, 3), Some(0));
+        assert_eq!(hld.kth_ancestor(6, 4), None);
+
+        // 部分木は列上で連続する
+        for v in 0..tree_parent.len() {
+            let range = hld.subtree_range(v);
+            let mut sub = range.map(|i| hld.vertex(i)).collect::<Vec<_>>();
+            sub.sort();
+            let mut expected = (0..tree_parent.len())
+                .filter(|&x| path_naive(&tree_parent, x, 0).contains(&v))
+                .collect::<Vec<_>>();
+            expected.sort();
+            assert_eq!(sub, expected);
+        }
+
+        // path_ranges を u から v の順に展開するとパスになる
+        for u in 0..tree_parent.len() {
+            for v in 0..tree_parent.len() {
+                let path = hld
+                    .path_ranges(u, v)
+                    .into_iter()
+                    .flat_map(|(range, reversed)| {
+                        let mut xs = range.map(|i| hld.vertex(i)).collect::<Vec<_>>();
+                        if reversed {
+                            xs.reverse();
+                        }
+                        xs
+                    })
+                    .collect::<Vec<_>>();
+                assert_eq!(path, path_naive(&tree_parent, u, v));
+            }
+        }
+    }
+
+    #[test]
+    fn test_hld_segtree_vertex() {
+        // 0 - 1 - 2
+        //     |
+        //     3 - 4
+        let adj = vec![vec![1], vec![0, 2, 3], vec![1], vec![1, 4], vec![3]];
+        let mut seg = HldSegtree::<Additive<i64>>::new(&adj, 0, &[1, 2, 4, 8, 16]);
+        assert_eq!(seg.path_prod(2, 4), 2 + 4 + 8 + 16);
+        assert_eq!(seg.path_prod(0, 0), 1);
+        assert_eq!(seg.subtree_prod(1), 2 + 4 + 8 + 16);
+        assert_eq!(seg.subtree_prod(3), 8 + 16);
+
+        seg.set(1, 100);
+        assert_eq!(seg.get(1), 100);
+        assert_eq!(seg.path_prod(2, 4), 100 + 4 + 8 + 16);
+    }
+
+    #[test]
+    fn test_hld_segtree_edge() {
+        // 0 -1- 1 -2- 2
+        //       |
+        //       4
+        //       |
+        //       3 -8- 4
+        let adj = vec![
+            vec![(1, 1)],
+            vec![(0, 1), (2, 2), (3, 4)],
+            vec![(1, 2)],
+            vec![(1, 4), (4, 8)],
+            vec![(3, 8)],
+        ];
+        let mut seg = HldSegtree::<Additive<i64>>::new_edge(&adj, 0);
+        assert_eq!(seg.path_prod(2, 4), 2 + 4 + 8);
+        assert_eq!(seg.path_prod(0, 4), 1 + 4 + 8);
+        assert_eq!(seg.path_prod(3, 3), 0);
+        assert_eq!(seg.subtree_prod(1), 2 + 4 + 8);
+        assert_eq!(seg.subtree_prod(4), 0);
+
+        seg.set_edge(3, 1, 100);
+        assert_eq!(seg.get_edge(1, 3), 100);
+        assert_eq!(seg.path_prod(2, 4), 2 + 100 + 8);
+    }
+
+    #[test]
+    fn test_hld_segtree_non_commutative() {
+        type Mint = ac_library::ModInt998244353;
+        type M = AffineComposition<Mint>;
+        // 0 - 1 - 2
+        //     |
+        //     3 - 4
+        let adj = vec![vec![1], vec![0, 2, 3], vec![1], vec![1, 4], vec![3]];
+        let xs = (0..5)
+            .map(|i| AffineTransform::new(Mint::new(i + 2), Mint::new(i + 1)))
+            .collect::<Vec<_>>();
+        let seg = HldSegtree::<M>::new(&adj, 0, &xs);
+
+        let expected = [2, 1, 3, 4]
+            .iter()
+            .fold(M::identity(), |acc, &v| M::binary_operation(&acc, &xs[v]));
+        assert_eq!(seg.path_prod(2, 4), expected);
+        let expected = [4, 3, 1, 2]
+            .iter()
+            .fold(M::identity(), |acc, &v| M::binary_operation(&acc, &xs[v]));
+        assert_eq!(seg.path_prod(4, 2), expected);
+    }
+
+    #[test]
+    fn test_hld_lazy_segtree() {
+        // 0 - 1 - 2
+        //     |
+        //     3 - 4
+        let adj = vec![vec![1], vec![0, 2, 3], vec![1], vec![1, 4], vec![3]];
+        let mut seg =
+            HldLazySegtree::<RangeAddRangeSum<i64>>::new(&adj, 0, &[RangeSum::unit(0); 5]);
+        seg.path_apply(2, 4, 1);
+        assert_eq!(seg.path_prod(0, 4).sum, 3);
+        assert_eq!(seg.subtree_prod(1).sum, 4);
+        seg.subtree_apply(3, 10);
+        assert_eq!(seg.get(4).sum, 11);
+        assert_eq!(seg.path_prod(0, 4).sum, 23);
+        seg.set(4, RangeSum::unit(0));
+        assert_eq!(seg.path_prod(2, 4).sum, 1 + 1 + 11);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_hld_segtree_random() {
+        type Mint = ac_library::ModInt998244353;
+        type M = AffineComposition<Mint>;
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let n = rng.random_range(1..=20);
+            let tree_parent = (0..n)
+                .map(|v| if v == 0 { 0 } else { rng.random_range(0..v) })
+                .collect::<Vec<_>>();
+            let adj = adj_from_parent(&tree_parent);
+            let root = rng.random_range(0..n);
+            let tree_parent = {
+                let parent = crate::graph::tree::tree::make_tree_parent(&adj, root);
+                (0..n).map(|v| parent[v].unwrap_or(v)).collect::<Vec<_>>()
+            };
+            let random_affine = |rng: &mut StdRng| {
+                AffineTransform::new(
+                    Mint::new(rng.random_range(1..10)),
+                    Mint::new(rng.random_range(0..10)),
+                )
+            };
+
+            // 頂点重み
+            let mut xs = (0..n).map(|_| random_affine(&mut rng)).collect::<Vec<_>>();
+            let mut seg = HldSegtree::<M>::new(&adj, root, &xs);
+            // 辺重み (子の頂点に対応づける)
+            let mut ws = (0..n).map(|_| random_affine(&mut rng)).collect::<Vec<_>>();
+            ws[root] = M::identity();
+            let adj_weighted = (0..n)
+                .map(|u| {
+                    adj[u]
+                        .iter()
+                        .map(|&v| (v, ws[if tree_parent[v] == u { v } else { u }]))
+                        .collect()
+                })
+                .collect::<Vec<Vec<_>>>();
+            let mut seg_edge = HldSegtree::<M>::new_edge(&adj_weighted, root);
+
+            for _ in 0..50 {
+                let u = rng.random_range(0..n);
+                let v = rng.random_range(0..n);
+                match rng.random_range(0..4) {
+                    0 => {
+                        let x = random_affine(&mut rng);
+                        xs[u] = x;
+                        seg.set(u, x);
+                    }
+                    1 => {
+                        if u != root {
+                            let x = random_affine(&mut rng);
+                            ws[u] = x;
+                            seg_edge.set_edge(u, tree_parent[u], x);
+                        }
+                    }
+                    _ => {
+                        let path = path_naive(&tree_parent, u, v);
+                        let expected = path
+                            .iter()
+                            .fold(M::identity(), |acc, &x| M::binary_operation(&acc, &xs[x]));
+                        assert_eq!(seg.path_prod(u, v), expected);
+
+                        let expected_edge = path.windows(2).fold(M::identity(), |acc, w| {
+                            let child = if tree_parent[w[0]] == w[1] {
+                                w[0]
+                            } else {
+                                w[1]
+                            };
+                            M::binary_operation(&acc, &ws[child])
+                        });
+                        assert_eq!(seg_edge.path_prod(u, v), expected_edge);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn test_hld_lazy_segtree_random() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let n = rng.random_range(1..=20);
+            let tree_parent = (0..n)
+                .map(|v| if v == 0 { 0 } else { rng.random_range(0..v) })
+                .collect::<Vec<_>>();
+            let adj = adj_from_parent(&tree_parent);
+            let root = rng.random_range(0..n);
+            let tree_parent = {
+                let parent = crate::graph::tree::tree::make_tree_parent(&adj, root);
+                (0..n).map(|v| parent[v].unwrap_or(v)).collect::<Vec<_>>()
+            };
+            // 頂点 x が頂点 v の部分木に含まれるか
+            let in_subtree = |x: usize, v: usize| path_naive(&tree_parent, x, root).contains(&v);
+
+            // 頂点重み
+            let mut xs = (0..n)
+                .map(|_| rng.random_range(-10..=10))
+                .collect::<Vec<i64>>();
+            let init = xs.iter().map(|&x| RangeSum::unit(x)).collect::<Vec<_>>();
+            let mut seg = HldLazySegtree::<RangeAddRangeSum<i64>>::new(&adj, root, &init);
+            // 辺重み (子の頂点に対応づける)
+            let mut ws = (0..n)
+                .map(|v| {
+                    if v == root {
+                        0
+                    } else {
+                        rng.random_range(-10..=10)
+                    }
+                })
+                .collect::<Vec<i64>>();
+            let adj_weighted = (0..n)
+                .map(|u| {
+                    adj[u]
+                        .iter()
+                        .map(|&v| {
+                            let child = if tree_parent[v] == u { v } else { u };
+                            (v, RangeSum::unit(ws[child]))
+                        })
+                        .collect()
+                })
+                .collect::<Vec<Vec<_>>>();
+            let mut seg_edge =
+                HldLazySegtree::<RangeAddRangeSum<i64>>::new_edge(&adj_weighted, root);
+
+            for _ in 0..50 {
+                let u = rng.random_range(0..n);
+                let v = rng.random_range(0..n);
+                let f = rng.random_range(-5..=5);
+                let path = path_naive(&tree_parent, u, v);
+                // パス上の辺に対応する子の頂点
+                let path_edges = path
+                    .windows(2)
+                    .map(|w| {
+                        if tree_parent[w[0]] == w[1] {
+                            w[0]
+                        } else {
+                            w[1]
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                match rng.random_range(0..7) {
+                    0 => {
+                        xs[u] = f;
+                        seg.set(u, RangeSum::unit(f));
+                        if u != root {
+                            ws[u] = f;
+                            seg_edge.set_edge(u, tree_parent[u], RangeSum::unit(f));
+                        }
+                    }
+                    1 => {
+                        for &x in &path {
+                            xs[x] += f;
+                        }
+                        seg.path_apply(u, v, f);
+                        for &x in &path_edges {
+                            ws[x] += f;
+                        }
+                        seg_edge.path_apply(u, v, f);
+                    }
+                    2 => {
+                        for x in 0..n {
+                            if in_subtree(x, u) {
+                                xs[x] += f;
+                                if x != u {
+                                    ws[x] += f;
+                                }
+                            }
+                        }
+                        seg.subtree_apply(u, f);
+                        seg_edge.subtree_apply(u, f);
+                    }
+                    3 => {
+                        assert_eq!(seg.get(u).sum, xs[u]);
+                        if u != root {
+                            assert_eq!(seg_edge.get_edge(tree_parent[u], u).sum, ws[u]);
+                        }
+                    }
+                    4 => {
+                        let expected = (0..n).filter(|&x| in_subtree(x, u)).map(|x| xs[x]).sum();
+                        assert_eq!(seg.subtree_prod(u).sum, expected);
+                        let expected_edge = (0..n)
+                            .filter(|&x| x != u && in_subtree(x, u))
+                            .map(|x| ws[x])
+                            .sum();
+                        assert_eq!(seg_edge.subtree_prod(u).sum, expected_edge);
+                    }
+                    _ => {
+                        let expected = path.iter().map(|&x| xs[x]).sum();
+                        assert_eq!(seg.path_prod(u, v).sum, expected);
+                        let expected_edge = path_edges.iter().map(|&x| ws[x]).sum();
+                        assert_eq!(seg_edge.path_prod(u, v).sum, expected_edge);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -112,6 +112,22 @@ pub mod lca {
             self.euler_tour_dist.prod(time_min..=time_max).1
         }
 
+        /// 根から頂点 v までの距離を返します。
+        ///
+        /// # 計算量
+        /// O(1)
+        pub fn depth(&self, v: usize) -> i64 {
+            self.depth[v]
+        }
+
+        /// オイラーツアーで頂点 v に初めて訪れる時刻を返します。この値の昇順に並べると行きがけ順になります。
+        ///
+        /// # 計算量
+        /// O(1)
+        pub fn in_time(&self, v: usize) -> usize {
+            self.euler_tour_in_time[v]
+        }
+
         /// 頂点 u と v の距離を求めます。
         ///
         /// # 計算量
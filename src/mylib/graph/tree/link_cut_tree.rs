@@ -0,0 +1,511 @@
+use cargo_snippet::snippet;
+
+#[allow(clippy::module_inception)]
+#[snippet(prefix = "use link_cut_tree::*;")]
+pub mod link_cut_tree {
+    use ac_library::{MapMonoid, Monoid};
+
+    type S<F> = <<F as MapMonoid>::M as Monoid>::S;
+
+    struct Node<F: MapMonoid> {
+        /// 左右の子 (splay 木上)
+        ch: [Option<usize>; 2],
+        /// splay 木上の親、または splay 木の根の場合は path-parent
+        parent: Option<usize>,
+        val: S<F>,
+        /// 部分木の積 (左から右の順)
+        prod: S<F>,
+        /// 部分木の積 (右から左の順)
+        rev_prod: S<F>,
+        lazy: F::F,
+        rev: bool,
+    }
+
+    /// Link-Cut Tree
+    ///
+    /// 森に対して辺の追加・削除、根の変更、パス上の頂点重みの積の取得・作用の適用を行う。
+    ///
+    /// 非可換なモノイドでも `path_prod(u, v)` は u から v へたどる順に積を取る。
+    /// `path_apply` で使う作用は、モノイドの積について準同型 (`f(a * b) = f(a) * f(b)`) であること。
+    pub struct LinkCutTree<F: MapMonoid> {
+        nodes: Vec<Node<F>>,
+    }
+
+    impl<F: MapMonoid> From<Vec<S<F>>> for LinkCutTree<F> {
+        fn from(xs: Vec<S<F>>) -> Self {
+            let nodes = xs
+                .into_iter()
+                .map(|x| Node {
+                    ch: [None, None],
+                    parent: None,
+                    val: x.clone(),
+                    prod: x.clone(),
+                    rev_prod: x,
+                    lazy: F::identity_map(),
+                    rev: false,
+                })
+                .collect();
+            LinkCutTree { nodes }
+        }
+    }
+
+    impl<F: MapMonoid> LinkCutTree<F> {
+        /// 頂点数 `n` で、すべての頂点の重みが単位元、辺がない森を作る。
+        ///
+        /// # 計算量
+        /// O(n)
+        pub fn new(n: usize) -> Self {
+            Self::from(vec![F::M::identity(); n])
+        }
+
+        fn prod_of(&self, x: Option<usize>) -> S<F> {
+            x.map_or_else(F::M::identity, |x| self.nodes[x].prod.clone())
+        }
+
+        fn rev_prod_of(&self, x: Option<usize>) -> S<F> {
+            x.map_or_else(F::M::identity, |x| self.nodes[x].rev_prod.clone())
+        }
+
+        fn update(&mut self, x: usize) {
+            let [l, r] = self.nodes[x].ch;
+            let val = &self.nodes[x].val;
+            let prod = F::M::binary_operation(
+                &F::M::binary_operation(&self.prod_of(l), val),
+                &self.prod_of(r),
+            );
+            let rev_prod = F::M::binary_operation(
+                &F::M::binary_operation(&self.rev_prod_of(r), val),
+                &self.rev_prod_of(l),
+            );
+            self.nodes[x].prod = prod;
+            self.nodes[x].rev_prod = rev_prod;
+        }
+
+        fn all_apply(&mut self, x: usize, f: &F::F) {
+            let node = &mut self.nodes[x];
+            node.val = F::mapping(f, &node.val);
+            node.prod = F::mapping(f, &node.prod);
+            node.rev_prod = F::mapping(f, &node.rev_prod);
+            node.lazy = F::composition(f, &node.lazy);
+        }
+
+        fn toggle(&mut self, x: usize) {
+            let node = &mut self.nodes[x];
+            node.ch.swap(0, 1);
+            std::mem::swap(&mut node.prod, &mut node.rev_prod);
+            node.rev ^= true;
+        }
+
+        fn push(&mut self, x: usize) {
+            let lazy = std::mem::replace(&mut self.nodes[x].lazy, F::identity_map());
+            let rev = std::mem::replace(&mut self.nodes[x].rev, false);
+            for c in self.nodes[x].ch.into_iter().flatten() {
+                self.all_apply(c, &lazy);
+                if rev {
+                    self.toggle(c);
+                }
+            }
+        }
+
+        fn is_root(&self, x: usize) -> bool {
+            match self.nodes[x].parent {
+                None => true,
+                Some(p) => !self.nodes[p].ch.contains(&Some(x)),
+            }
+        }
+
+        fn rotate(&mut self, x: usize) {
+            let p = self.nodes[x].parent.unwrap();
+            let g = self.nodes[p].parent;
+            let p_is_root = self.is_root(p);
+            let dir = (self.nodes[p].ch[1] == Some(x)) as usize;
+
+            let b = self.nodes[x].ch[dir ^ 1];
+            self.nodes[p].ch[dir] = b;
+            if let Some(b) = b {
+                self.nodes[b].parent = Some(p);
+            }
+            self.nodes[x].ch[dir ^ 1] = Some(p);
+            self.nodes[p].parent = Some(x);
+            self.nodes[x].parent = g;
+            if let (Some(g), false) = (g, p_is_root) {
+                let d = (self.nodes[g].ch[1] == Some(p)) as usize;
+                self.nodes[g].ch[d] = Some(x);
+            }
+            self.update(p);
+            self.update(x);
+        }
+
+        fn splay(&mut self, x: usize) {
+            // 根から順に遅延評価を解消する
+            let mut path = vec![x];
+            let mut cur = x;
+            while !self.is_root(cur) {
+                cur = self.nodes[cur].parent.unwrap();
+                path.push(cur);
+            }
+            for &y in path.iter().rev() {
+                self.push(y);
+            }
+
+            while !self.is_root(x) {
+                let p = self.nodes[x].parent.unwrap();
+                if !self.is_root(p) {
+                    let g = self.nodes[p].parent.unwrap();
+                    let zig_zig =
+                        (self.nodes[g].ch[0] == Some(p)) == (self.nodes[p].ch[0] == Some(x));
+                    self.rotate(if zig_zig { p } else { x });
+                }
+                self.rotate(x);
+            }
+        }
+
+        /// 根から x までのパスを 1 つの splay 木にまとめ、x をその根にする。
+        /// 最後に path-parent をたどって移った頂点を返す。
+        fn expose(&mut self, x: usize) -> usize {
+            let mut last = None;
+            let mut cur = Some(x);
+            while let Some(c) = cur {
+                self.splay(c);
+                self.nodes[c].ch[1] = last;
+                self.update(c);
+                last = Some(c);
+                cur = self.nodes[c].parent;
+            }
+            self.splay(x);
+            last.unwrap()
+        }
+
+        /// 頂点 v を含む木の根を v にする。
+        ///
+        /// # 計算量
+        /// ならし O(log n)
+        pub fn evert(&mut self, v: usize) {
+            self.expose(v);
+            self.toggle(v);
+        }
+
+        /// 頂点 v を含む木の根を返す。
+        ///
+        /// # 計算量
+        /// ならし O(log n)
+        pub fn root(&mut self, v: usize) -> usize {
+            self.expose(v);
+            let mut r = v;
+            loop {
+                self.push(r);
+                match self.nodes[r].ch[0] {
+                    Some(l) => r = l,
+                    None => break,
+                }
+            }
+            self.splay(r);
+            r
+        }
+
+        /// 頂点 u と v が同じ木に含まれるかどうかを返す。
+        ///
+        /// # 計算量
+        /// ならし O(log n)
+        pub fn connected(&mut self, u: usize, v: usize) -> bool {
+            self.root(u) == self.root(v)
+        }
+
+        /// 辺 u-v を追加する。
+        ///
+        /// # Panics
+        /// u と v がすでに同じ木に含まれる場合
+        ///
+        /// # 計算量
+        /// ならし O(log n)
+        pub fn link(&mut self, u: usize, v: usize) {
+            self.evert(u);
+            assert!(self.root(v) != u, "u and v are already connected");
+            self.nodes[u].parent = Some(v);
+        }
+
+        /// 辺 u-v を削除する。
+        ///
+        /// # Panics
+        /// 辺 u-v が存在しない場合
+        ///
+        /// # 計算量
+        /// ならし O(log n)
+        pub fn cut(&mut self, u: usize, v: usize) {
+            self.evert(u);
+            self.expose(v);
+            // 辺が存在するなら、根から v へのパスは u, v の 2 頂点のみ
+            assert!(
+                self.nodes[v].ch[0] == Some(u) && self.nodes[u].ch == [None, None],
+                "edge u-v does not exist"
+            );
+            self.nodes[v].ch[0] = None;
+            self.nodes[u].parent = None;
+            self.update(v);
+        }
+
+        /// 現在の根に関する頂点 u と v の LCA を返す。u と v が異なる木に含まれる場合は `None`。
+        ///
+        /// 根は `evert` などで変わるため、必要に応じて直前に `evert` で根を指定すること。
+        ///
+        /// # 計算量
+        /// ならし O(log n)
+        pub fn lca(&mut self, u: usize, v: usize) -> Option<usize> {
+            if !self.connected(u, v) {
+                return None;
+            }
+            self.expose(u);
+            Some(self.expose(v))
+        }
+
+        /// 頂点 v の重みを返す。
+        ///
+        /// # 計算量
+        /// ならし O(log n)
+        pub fn get(&mut self, v: usize) -> S<F> {
+            self.splay(v);
+            self.nodes[v].val.clone()
+        }
+
+        /// 頂点 v の重みを x にする。
+        ///
+        /// # 計算量
+        /// ならし O(log n)
+        pub fn set(&mut self, v: usize, x: S<F>) {
+            self.expose(v);
+            self.nodes[v].val = x;
+            self.update(v);
+        }
+
+        /// u から v へのパス上の頂点の重みを、u から v へたどる順に掛けた値を返す。
+        ///
+        /// 木の根は u に変わる。
+        ///
+        /// # Panics
+        /// u と v が異なる木に含まれる場合
+        ///
+        /// # 計算量
+        /// ならし O(log n)
+        pub fn path_prod(&mut self, u: usize, v: usize) -> S<F> {
+            assert!(self.connected(u, v), "u and v are not connected");
+            self.evert(u);
+            self.expose(v);
+            self.nodes[v].prod.clone()
+        }
+
+        /// u から v へのパス上の頂点の重みに作用 f を適用する。
+        ///
+        /// 木の根は u に変わる。
+        ///
+        /// # Panics
+        /// u と v が異なる木に含まれる場合
+        ///
+        /// # 計算量
+        /// ならし O(log n)
+        pub fn path_apply(&mut self, u: usize, v: usize, f: F::F) {
+            assert!(self.connected(u, v), "u and v are not connected");
+            self.evert(u);
+            self.expose(v);
+            self.all_apply(v, &f);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::link_cut_tree::*;
+    use crate::data_structure::segtree_lib::lazy_segtree::range_add_range_sum::range_add_range_sum::{
+        RangeAddRangeSum, RangeSum,
+    };
+    use ac_library::{MapMonoid, Monoid};
+    use std::convert::Infallible;
+
+    /// 列の連結 (非可換)
+    struct Concat(Infallible);
+    impl Monoid for Concat {
+        type S = Vec<i64>;
+        fn identity() -> Vec<i64> {
+            vec![]
+        }
+        fn binary_operation(a: &Vec<i64>, b: &Vec<i64>) -> Vec<i64> {
+            a.iter().chain(b).copied().collect()
+        }
+    }
+
+    /// 列の全要素に加算する
+    struct ConcatAdd(Infallible);
+    impl MapMonoid for ConcatAdd {
+        type M = Concat;
+        type F = i64;
+        fn identity_map() -> i64 {
+            0
+        }
+        fn mapping(f: &i64, x: &Vec<i64>) -> Vec<i64> {
+            x.iter().map(|a| a + f).collect()
+        }
+        fn composition(f: &i64, g: &i64) -> i64 {
+            f + g
+        }
+    }
+
+    #[test]
+    fn test_link_cut_tree() {
+        let xs = (0..6).map(|x| RangeSum::unit(x as i64)).collect::<Vec<_>>();
+        let mut lct = LinkCutTree::<RangeAddRangeSum<i64>>::from(xs);
+        // 0 - 1 - 2
+        //     |
+        //     3 - 4   5
+        lct.link(0, 1);
+        lct.link(1, 2);
+        lct.link(3, 1);
+        lct.link(4, 3);
+        assert!(lct.connected(0, 4));
+        assert!(!lct.connected(0, 5));
+        assert_eq!(lct.path_prod(0, 4), RangeSum { sum: 8, len: 4 });
+        assert_eq!(lct.path_prod(2, 2), RangeSum { sum: 2, len: 1 });
+
+        lct.path_apply(2, 4, 10);
+        assert_eq!(lct.get(0).sum, 0);
+        assert_eq!(lct.get(1).sum, 11);
+        assert_eq!(lct.get(3).sum, 13);
+        assert_eq!(lct.path_prod(0, 2), RangeSum { sum: 23, len: 3 });
+
+        lct.evert(0);
+        assert_eq!(lct.lca(2, 4), Some(1));
+        assert_eq!(lct.lca(3, 4), Some(3));
+        assert_eq!(lct.lca(0, 5), None);
+        lct.evert(4);
+        assert_eq!(lct.lca(0, 2), Some(1));
+        assert_eq!(lct.root(0), 4);
+
+        lct.cut(1, 3);
+        assert!(!lct.connected(0, 4));
+        lct.link(4, 5);
+        lct.set(5, RangeSum::unit(100));
+        assert_eq!(lct.path_prod(3, 5), RangeSum { sum: 127, len: 3 });
+    }
+
+    #[test]
+    fn test_link_cut_tree_non_commutative() {
+        let mut lct = LinkCutTree::<ConcatAdd>::from((0..5).map(|x| vec![x]).collect::<Vec<_>>());
+        // 0 - 1 - 2 - 3
+        //         |
+        //         4
+        lct.link(0, 1);
+        lct.link(2, 1);
+        lct.link(2, 3);
+        lct.link(4, 2);
+        assert_eq!(lct.path_prod(0, 3), vec![0, 1, 2, 3]);
+        assert_eq!(lct.path_prod(3, 0), vec![3, 2, 1, 0]);
+        assert_eq!(lct.path_prod(4, 0), vec![4, 2, 1, 0]);
+        lct.path_apply(3, 1, 10);
+        assert_eq!(lct.path_prod(0, 4), vec![0, 11, 12, 4]);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_link_cut_tree_random() {
+        use rand::prelude::*;
+
+        // 森上で u から v へのパスを求める
+        fn path_naive(adj: &[Vec<usize>], u: usize, v: usize) -> Option<Vec<usize>> {
+            let n = adj.len();
+            let mut prev = vec![None; n];
+            let mut visited = vec![false; n];
+            let mut stack = vec![u];
+            visited[u] = true;
+            while let Some(x) = stack.pop() {
+                for &y in &adj[x] {
+                    if !visited[y] {
+                        visited[y] = true;
+                        prev[y] = Some(x);
+                        stack.push(y);
+                    }
+                }
+            }
+            if !visited[v] {
+                return None;
+            }
+            let mut path = vec![v];
+            let mut x = v;
+            while let Some(p) = prev[x] {
+                path.push(p);
+                x = p;
+            }
+            path.reverse();
+            Some(path)
+        }
+
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..50 {
+            let n = rng.random_range(1..=12);
+            let mut vals = (0..n)
+                .map(|_| rng.random_range(-10..=10))
+                .collect::<Vec<i64>>();
+            let mut lct =
+                LinkCutTree::<ConcatAdd>::from(vals.iter().map(|&x| vec![x]).collect::<Vec<_>>());
+            let mut adj = vec![vec![]; n];
+
+            for _ in 0..300 {
+                let u = rng.random_range(0..n);
+                let v = rng.random_range(0..n);
+                let path = path_naive(&adj, u, v);
+                assert_eq!(lct.connected(u, v), path.is_some());
+                match rng.random_range(0..6) {
+                    0 => {
+                        if path.is_none() {
+                            lct.link(u, v);
+                            adj[u].push(v);
+                            adj[v].push(u);
+                        }
+                    }
+                    1 => {
+                        if adj[u].contains(&v) {
+                            lct.cut(u, v);
+                            adj[u].retain(|&x| x != v);
+                            adj[v].retain(|&x| x != u);
+                        }
+                    }
+                    2 => {
+                        let expected = path.map(|p| p.iter().map(|&x| vals[x]).collect::<Vec<_>>());
+                        if let Some(expected) = expected {
+                            assert_eq!(lct.path_prod(u, v), expected);
+                        }
+                    }
+                    3 => {
+                        if let Some(path) = path {
+                            let f = rng.random_range(-5..=5);
+                            lct.path_apply(u, v, f);
+                            for x in path {
+                                vals[x] += f;
+                            }
+                        }
+                    }
+                    4 => {
+                        let x = rng.random_range(-10..=10);
+                        lct.set(u, vec![x]);
+                        vals[u] = x;
+                        assert_eq!(lct.get(v), vec![vals[v]]);
+                    }
+                    _ => {
+                        // r を根としたときの LCA は、u から r へのパスと v から r へのパスが最初に合流する頂点
+                        let r = rng.random_range(0..n);
+                        let r = if path_naive(&adj, u, r).is_some() {
+                            r
+                        } else {
+                            u
+                        };
+                        lct.evert(r);
+                        assert_eq!(lct.root(u), r);
+                        let expected = path.map(|_| {
+                            let pu = path_naive(&adj, u, r).unwrap();
+                            let pv = path_naive(&adj, v, r).unwrap();
+                            pu.into_iter().find(|x| pv.contains(x)).unwrap()
+                        });
+                        assert_eq!(lct.lca(u, v), expected);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,344 @@
+use cargo_snippet::snippet;
+
+use crate::graph::tree::tree::make_tree_children;
+use crate::math::modint_u64::modint_u64::ModInt2305843009213693951;
+
+#[allow(clippy::module_inception)]
+#[snippet(
+    prefix = "use tree_isomorphism::*;",
+    include = "make_tree_children, modint_u64"
+)]
+pub mod tree_isomorphism {
+    use super::make_tree_children;
+    use std::collections::HashMap;
+
+    type Mint = super::ModInt2305843009213693951;
+
+    /// 根から BFS した順に頂点を並べる
+    fn bfs_order(children: &[Vec<usize>], root: usize) -> Vec<usize> {
+        let mut order = vec![root];
+        let mut i = 0;
+        while i < order.len() {
+            let v = order[i];
+            i += 1;
+            order.extend(children[v].iter().copied());
+        }
+        order
+    }
+
+    /// 木の中心 (直径の中点) を求める。直径の長さが奇数の場合は 2 つある。
+    ///
+    /// 葉を外側から 1 層ずつ取り除き、最後に残った頂点を中心とする。
+    ///
+    /// # Arguments
+    /// * `adj` - 木の隣接リスト (無向グラフ)
+    ///
+    /// # Returns
+    /// 中心の頂点を昇順に並べたもの (1 個または 2 個)
+    ///
+    /// # 計算量
+    /// O(V)
+    pub fn tree_centers(adj: &[Vec<usize>]) -> Vec<usize> {
+        let n = adj.len();
+        let mut deg = adj.iter().map(|a| a.len()).collect::<Vec<_>>();
+        let mut layer = (0..n).filter(|&v| deg[v] <= 1).collect::<Vec<_>>();
+        let mut remaining = n;
+        while remaining > 2 {
+            remaining -= layer.len();
+            let mut next = vec![];
+            for &v in &layer {
+                for &u in &adj[v] {
+                    deg[u] -= 1;
+                    if deg[u] == 1 {
+                        next.push(u);
+                    }
+                }
+            }
+            layer = next;
+        }
+        layer.sort_unstable();
+        layer
+    }
+
+    /// AHU アルゴリズムによる根付き木の同型判定
+    ///
+    /// 各部分木に、同型なとき・そのときに限り等しくなる ID を割り当てる。
+    /// 同じ `AhuTreeIds` で計算した ID どうしは、異なる木の間でも比較できる。
+    #[derive(Clone, Debug, Default)]
+    pub struct AhuTreeIds {
+        map: HashMap<Vec<usize>, usize>,
+    }
+
+    impl AhuTreeIds {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// これまでに割り当てた ID の種類数
+        pub fn num_ids(&self) -> usize {
+            self.map.len()
+        }
+
+        /// 各頂点を根とする部分木の ID を求める
+        ///
+        /// # Arguments
+        /// * `children` - 各頂点の子のリスト (`make_tree_children` の返り値)。すべての頂点が `root` の子孫であること。
+        /// * `root` - 根
+        ///
+        /// # Returns
+        /// `ids[v]` は頂点 `v` を根とする部分木の ID
+        ///
+        /// # 計算量
+        /// O(V log V) (ハッシュマップの操作を O(子の数) とみなした場合)
+        pub fn subtree_ids(&mut self, children: &[Vec<usize>], root: usize) -> Vec<usize> {
+            let mut ids = vec![usize::MAX; children.len()];
+            for &v in bfs_order(children, root).iter().rev() {
+                let mut key = children[v].iter().map(|&c| ids[c]).collect::<Vec<_>>();
+                key.sort_unstable();
+                let next_id = self.map.len();
+                ids[v] = *self.map.entry(key).or_insert(next_id);
+            }
+            ids
+        }
+
+        /// 根なし木の ID を求める。2 つの根なし木が同型なとき・そのときに限り等しくなる。
+        ///
+        /// 木の中心を根とした根付き木の ID (中心が 2 つある場合は小さい方) を返す。
+        ///
+        /// # Arguments
+        /// * `adj` - 木の隣接リスト (無向グラフ)
+        ///
+        /// # 計算量
+        /// O(V log V)
+        pub fn unrooted_id(&mut self, adj: &[Vec<usize>]) -> usize {
+            tree_centers(adj)
+                .into_iter()
+                .map(|c| self.subtree_ids(&make_tree_children(adj, c), c)[c])
+                .min()
+                .unwrap()
+        }
+    }
+
+    /// 乱択ハッシュによる根付き木の同型判定
+    ///
+    /// 高さ `h` の頂点 `v` のハッシュを `Π_{c: v の子} (x_h + hash(c)) mod (2^61 - 1)` で定める (`x_h` は乱数)。
+    /// 同型な木のハッシュは等しく、同型でない木のハッシュは高い確率で異なる。
+    /// 同じ `TreeHasher` で計算したハッシュどうしは、異なる木の間でも比較できる。
+    #[derive(Clone, Debug, Default)]
+    pub struct TreeHasher {
+        bases: Vec<Mint>,
+    }
+
+    impl TreeHasher {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        fn base(&mut self, height: usize) -> Mint {
+            use rand::{Rng, SeedableRng, rngs::SmallRng};
+            if self.bases.len() <= height {
+                let mut rng = SmallRng::from_os_rng();
+                while self.bases.len() <= height {
+                    self.bases
+                        .push(Mint::new(rng.random_range(2..Mint::modulus())));
+                }
+            }
+            self.bases[height]
+        }
+
+        /// 各頂点を根とする部分木のハッシュ値を求める
+        ///
+        /// # Arguments
+        /// * `children` - 各頂点の子のリスト (`make_tree_children` の返り値)。すべての頂点が `root` の子孫であること。
+        /// * `root` - 根
+        ///
+        /// # Returns
+        /// `hashes[v]` は頂点 `v` を根とする部分木のハッシュ値
+        ///
+        /// # 計算量
+        /// O(V)
+        pub fn subtree_hashes(&mut self, children: &[Vec<usize>], root: usize) -> Vec<u64> {
+            let n = children.len();
+            let mut hash = vec![Mint::new(1); n];
+            let mut height = vec![0; n];
+            for &v in bfs_order(children, root).iter().rev() {
+                height[v] = children[v]
+                    .iter()
+                    .map(|&c| height[c] + 1)
+                    .max()
+                    .unwrap_or(0);
+                let x = self.base(height[v]);
+                hash[v] = children[v]
+                    .iter()
+                    .fold(Mint::new(1), |acc, &c| acc * (x + hash[c]));
+            }
+            hash.into_iter().map(|h| h.val()).collect()
+        }
+
+        /// 根なし木のハッシュ値を求める
+        ///
+        /// 木の中心を根とした根付き木のハッシュ値 (中心が 2 つある場合は小さい方) を返す。
+        ///
+        /// # Arguments
+        /// * `adj` - 木の隣接リスト (無向グラフ)
+        ///
+        /// # 計算量
+        /// O(V)
+        pub fn unrooted_hash(&mut self, adj: &[Vec<usize>]) -> u64 {
+            tree_centers(adj)
+                .into_iter()
+                .map(|c| self.subtree_hashes(&make_tree_children(adj, c), c)[c])
+                .min()
+                .unwrap()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::tree_isomorphism::*;
+    use crate::graph::graph::make_adj_from_undirected;
+    use crate::graph::tree::tree::make_tree_children;
+
+    #[test]
+    fn test_subtree_ids() {
+        // 0
+        // ├ 1
+        // │ ├ 3
+        // │ └ 4
+        // │   └ 7
+        // └ 2
+        //   ├ 5
+        //   │ └ 8
+        //   └ 6
+        let adj = make_adj_from_undirected(
+            9,
+            &[
+                (0, 1),
+                (0, 2),
+                (1, 3),
+                (1, 4),
+                (4, 7),
+                (2, 5),
+                (2, 6),
+                (5, 8),
+            ],
+        );
+        let children = make_tree_children(&adj, 0);
+
+        let mut ahu = AhuTreeIds::new();
+        let ids = ahu.subtree_ids(&children, 0);
+        assert_eq!(ids[1], ids[2]);
+        assert_eq!(ids[4], ids[5]);
+        assert_eq!(ids[3], ids[7]);
+        assert_ne!(ids[0], ids[1]);
+        assert_ne!(ids[3], ids[4]);
+        // 葉, 葉1つの親, 1 と 2, 0
+        assert_eq!(ahu.num_ids(), 4);
+
+        let mut hasher = TreeHasher::new();
+        let hashes = hasher.subtree_hashes(&children, 0);
+        assert_eq!(hashes[1], hashes[2]);
+        assert_eq!(hashes[4], hashes[5]);
+        assert_ne!(hashes[0], hashes[1]);
+        assert_ne!(hashes[3], hashes[4]);
+    }
+
+    #[test]
+    fn test_unrooted() {
+        // パス 0-1-2-3 と、頂点番号を付け替えたもの
+        let path1 = make_adj_from_undirected(4, &[(0, 1), (1, 2), (2, 3)]);
+        let path2 = make_adj_from_undirected(4, &[(2, 0), (0, 3), (3, 1)]);
+        // スター
+        let star = make_adj_from_undirected(4, &[(0, 1), (0, 2), (0, 3)]);
+
+        assert_eq!(tree_centers(&path1), vec![1, 2]);
+        assert_eq!(tree_centers(&star), vec![0]);
+
+        let mut ahu = AhuTreeIds::new();
+        assert_eq!(ahu.unrooted_id(&path1), ahu.unrooted_id(&path2));
+        assert_ne!(ahu.unrooted_id(&path1), ahu.unrooted_id(&star));
+
+        let mut hasher = TreeHasher::new();
+        assert_eq!(hasher.unrooted_hash(&path1), hasher.unrooted_hash(&path2));
+        assert_ne!(hasher.unrooted_hash(&path1), hasher.unrooted_hash(&star));
+
+        let single = make_adj_from_undirected(1, &[]);
+        assert_eq!(tree_centers(&single), vec![0]);
+        assert_eq!(ahu.unrooted_id(&single), ahu.unrooted_id(&single));
+    }
+
+    #[test]
+    #[ignore]
+    fn test_tree_isomorphism_random() {
+        use itertools::Itertools;
+        use rand::prelude::*;
+
+        fn random_tree(rng: &mut StdRng, n: usize) -> Vec<(usize, usize)> {
+            (1..n).map(|v| (rng.random_range(0..v), v)).collect()
+        }
+
+        // 頂点の置換を全探索して同型判定する
+        fn is_isomorphic_naive(n: usize, e1: &[(usize, usize)], e2: &[(usize, usize)]) -> bool {
+            let normalize = |es: &mut Vec<(usize, usize)>| {
+                for e in es.iter_mut() {
+                    if e.0 > e.1 {
+                        *e = (e.1, e.0);
+                    }
+                }
+                es.sort();
+            };
+            let mut target = e2.to_vec();
+            normalize(&mut target);
+            (0..n).permutations(n).any(|perm| {
+                let mut mapped = e1.iter().map(|&(u, v)| (perm[u], perm[v])).collect();
+                normalize(&mut mapped);
+                mapped == target
+            })
+        }
+
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut ahu = AhuTreeIds::new();
+        let mut hasher = TreeHasher::new();
+        for _ in 0..300 {
+            let n = rng.random_range(1..=7);
+            let e1 = random_tree(&mut rng, n);
+            let e2 = random_tree(&mut rng, n);
+            let adj1 = make_adj_from_undirected(n, &e1);
+            let adj2 = make_adj_from_undirected(n, &e2);
+
+            let expected = is_isomorphic_naive(n, &e1, &e2);
+            assert_eq!(ahu.unrooted_id(&adj1) == ahu.unrooted_id(&adj2), expected);
+            assert_eq!(
+                hasher.unrooted_hash(&adj1) == hasher.unrooted_hash(&adj2),
+                expected
+            );
+
+            // 根付き木としての同型判定: 根を固定した置換の全探索
+            let r1 = rng.random_range(0..n);
+            let r2 = rng.random_range(0..n);
+            let ids1 = ahu.subtree_ids(&make_tree_children(&adj1, r1), r1);
+            let ids2 = ahu.subtree_ids(&make_tree_children(&adj2, r2), r2);
+            let hashes1 = hasher.subtree_hashes(&make_tree_children(&adj1, r1), r1);
+            let hashes2 = hasher.subtree_hashes(&make_tree_children(&adj2, r2), r2);
+            let expected_rooted = (0..n).permutations(n).any(|perm| {
+                if perm[r1] != r2 {
+                    return false;
+                }
+                let mut mapped = e1
+                    .iter()
+                    .map(|&(u, v)| (perm[u].min(perm[v]), perm[u].max(perm[v])))
+                    .collect::<Vec<_>>();
+                mapped.sort();
+                let mut target = e2
+                    .iter()
+                    .map(|&(u, v)| (u.min(v), u.max(v)))
+                    .collect::<Vec<_>>();
+                target.sort();
+                mapped == target
+            });
+            assert_eq!(ids1[r1] == ids2[r2], expected_rooted);
+            assert_eq!(hashes1[r1] == hashes2[r2], expected_rooted);
+        }
+    }
+}
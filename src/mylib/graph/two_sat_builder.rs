@@ -0,0 +1,216 @@
+use crate::data_structure::ix::{Bounds, Ix, IxVec};
+use cargo_snippet::snippet;
+
+#[allow(clippy::module_inception)]
+#[snippet(prefix = "use two_sat_builder::*;")]
+pub mod two_sat_builder {
+    use super::{Bounds, Ix, IxVec};
+    use ac_library::TwoSat;
+
+    /// 2-SAT の制約を組み立てる構造体 (Ix版)
+    ///
+    /// リテラルは `(変数, 値)` のペアで表し、「変数 `x` が `value` である」ことを意味する。
+    /// 例えば `(x, true)` は `x`、`(x, false)` は `¬x` を表す。
+    #[derive(Clone, Debug)]
+    pub struct TwoSatBuilder<I: Ix> {
+        bounds: Bounds<I>,
+        /// 補助変数を含む変数の個数
+        num_vars: usize,
+        clauses: Vec<(usize, bool, usize, bool)>,
+    }
+
+    impl<I: Ix> TwoSatBuilder<I> {
+        /// 指定された範囲の変数を持つ 2-SAT を作成する
+        ///
+        /// # Arguments
+        /// * `bounds` - 変数のインデックス範囲
+        pub fn new(bounds: Bounds<I>) -> Self {
+            Self {
+                bounds,
+                num_vars: bounds.range_size(),
+                clauses: vec![],
+            }
+        }
+
+        fn lit(&self, (x, value): (I, bool)) -> (usize, bool) {
+            (self.bounds.to_index(x), value)
+        }
+
+        fn add_clause_raw(&mut self, (i, f): (usize, bool), (j, g): (usize, bool)) {
+            self.clauses.push((i, f, j, g));
+        }
+
+        fn new_aux(&mut self) -> usize {
+            self.num_vars += 1;
+            self.num_vars - 1
+        }
+
+        /// 制約 `a ∨ b` を追加する
+        pub fn either(&mut self, a: (I, bool), b: (I, bool)) {
+            let (a, b) = (self.lit(a), self.lit(b));
+            self.add_clause_raw(a, b);
+        }
+
+        /// 制約 `a → b` を追加する
+        pub fn implies(&mut self, a: (I, bool), b: (I, bool)) {
+            let ((i, f), b) = (self.lit(a), self.lit(b));
+            self.add_clause_raw((i, !f), b);
+        }
+
+        /// 制約 `¬(a ∧ b)` を追加する
+        pub fn not_both(&mut self, a: (I, bool), b: (I, bool)) {
+            let ((i, f), (j, g)) = (self.lit(a), self.lit(b));
+            self.add_clause_raw((i, !f), (j, !g));
+        }
+
+        /// リテラル `a` が真であるという制約を追加する
+        pub fn set(&mut self, a: (I, bool)) {
+            let a = self.lit(a);
+            self.add_clause_raw(a, a);
+        }
+
+        /// 与えられたリテラルのうち真であるものが高々 1 個であるという制約を追加する
+        ///
+        /// 「先頭 i 個のリテラルのいずれかが真である」を表す補助変数を導入して、O(k) 個の節で表す。
+        ///
+        /// # 計算量
+        /// O(k) (k はリテラルの個数)
+        pub fn at_most_one(&mut self, lits: impl IntoIterator<Item = (I, bool)>) {
+            // prev: 直前までのリテラルのいずれかが真であることを表す補助変数
+            let mut prev: Option<usize> = None;
+            for lit in lits {
+                let x = self.lit(lit);
+                let cur = self.new_aux();
+                // x → cur
+                self.add_clause_raw((x.0, !x.1), (cur, true));
+                if let Some(p) = prev {
+                    // p → cur, p → ¬x
+                    self.add_clause_raw((p, false), (cur, true));
+                    self.add_clause_raw((p, false), (x.0, !x.1));
+                }
+                prev = Some(cur);
+            }
+        }
+
+        /// 制約をすべて満たす割り当てを求める
+        ///
+        /// # Returns
+        /// 制約をすべて満たす割り当てが存在すればその 1 つ、存在しなければ `None`
+        ///
+        /// # 計算量
+        /// O(V + C) (V は補助変数を含む変数の個数、C は節の個数)
+        pub fn solve(&self) -> Option<IxVec<I, bool>> {
+            let mut ts = TwoSat::new(self.num_vars);
+            for &(i, f, j, g) in &self.clauses {
+                ts.add_clause(i, f, j, g);
+            }
+            if !ts.satisfiable() {
+                return None;
+            }
+            let n = self.bounds.range_size();
+            Some(IxVec::from_vec(self.bounds, ts.answer()[..n].to_vec()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::two_sat_builder::*;
+    use crate::data_structure::ix::Bounds;
+    use crate::math::geometry::pos::pos::Pos;
+
+    #[test]
+    fn test_two_sat_builder() {
+        let mut ts = TwoSatBuilder::new(Bounds::new(0_usize, 2));
+        ts.either((0, true), (1, true));
+        ts.implies((0, true), (2, false));
+        ts.not_both((1, true), (2, false));
+        ts.set((2, false));
+        let ans = ts.solve().unwrap();
+        assert_eq!(ans.as_vec(), &vec![true, false, false]);
+
+        ts.set((0, false));
+        assert_eq!(ts.solve(), None);
+    }
+
+    #[test]
+    fn test_two_sat_builder_grid() {
+        // 2x2 グリッドの各行・各列で、高々 1 マスを選び、各行で少なくとも 1 マス選ぶ
+        let bounds = Bounds::new(Pos::new(0, 0), Pos::new(1, 1));
+        let mut ts = TwoSatBuilder::new(bounds);
+        for i in 0..2 {
+            ts.at_most_one((0..2).map(|j| (Pos::new(j, i), true)));
+            ts.at_most_one((0..2).map(|j| (Pos::new(i, j), true)));
+            ts.either((Pos::new(0, i), true), (Pos::new(1, i), true));
+        }
+        ts.set((Pos::new(0, 0), false));
+        let ans = ts.solve().unwrap();
+        assert!(!ans[Pos::new(0, 0)]);
+        assert!(ans[Pos::new(1, 0)]);
+        assert!(ans[Pos::new(0, 1)]);
+        assert!(!ans[Pos::new(1, 1)]);
+
+        ts.set((Pos::new(1, 1), true));
+        assert_eq!(ts.solve(), None);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_two_sat_builder_random() {
+        use rand::prelude::*;
+
+        type Check = Box<dyn Fn(&[bool]) -> bool>;
+
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..500 {
+            let n = rng.random_range(1..=8);
+            let mut ts = TwoSatBuilder::new(Bounds::new(0_usize, n - 1));
+            // 制約を、割り当てを受け取って満たすかどうかを返す関数として記録する
+            let mut checks: Vec<Check> = vec![];
+            for _ in 0..rng.random_range(0..=8) {
+                let a = (rng.random_range(0..n), rng.random_bool(0.5));
+                let b = (rng.random_range(0..n), rng.random_bool(0.5));
+                match rng.random_range(0..5) {
+                    0 => {
+                        ts.either(a, b);
+                        checks.push(Box::new(move |x| x[a.0] == a.1 || x[b.0] == b.1));
+                    }
+                    1 => {
+                        ts.implies(a, b);
+                        checks.push(Box::new(move |x| x[a.0] != a.1 || x[b.0] == b.1));
+                    }
+                    2 => {
+                        ts.not_both(a, b);
+                        checks.push(Box::new(move |x| !(x[a.0] == a.1 && x[b.0] == b.1)));
+                    }
+                    3 => {
+                        ts.set(a);
+                        checks.push(Box::new(move |x| x[a.0] == a.1));
+                    }
+                    _ => {
+                        let k = rng.random_range(0..=4);
+                        let lits = (0..k)
+                            .map(|_| (rng.random_range(0..n), rng.random_bool(0.5)))
+                            .collect::<Vec<_>>();
+                        ts.at_most_one(lits.iter().copied());
+                        checks.push(Box::new(move |x| {
+                            lits.iter().filter(|&&(v, f)| x[v] == f).count() <= 1
+                        }));
+                    }
+                }
+            }
+
+            let satisfiable = (0..1 << n).any(|bits: usize| {
+                let x = (0..n).map(|i| bits >> i & 1 == 1).collect::<Vec<_>>();
+                checks.iter().all(|check| check(&x))
+            });
+            match ts.solve() {
+                Some(ans) => {
+                    assert!(satisfiable);
+                    assert!(checks.iter().all(|check| check(ans.as_vec())));
+                }
+                None => assert!(!satisfiable),
+            }
+        }
+    }
+}